@@ -0,0 +1,367 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ExecuteMsg;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::state::{Config, Stage, CLAIMS, CONFIG, LATEST_STAGE, STAGES};
+
+use haloswap::airdrop::{
+    ClaimableResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, IsClaimedResponse,
+    LatestStageResponse, MerkleRootResponse, MigrateMsg, QueryMsg,
+};
+use haloswap::migration::assert_migrate_version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:halo-airdrop";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            halo_token: deps.api.addr_canonicalize(&msg.halo_token)?,
+        },
+    )?;
+    LATEST_STAGE.save(deps.storage, &0)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig { owner } => update_config(deps, info, owner),
+        ExecuteMsg::RegisterMerkleRoot {
+            merkle_root,
+            total_amount,
+            expiration,
+        } => register_merkle_root(deps, env, info, merkle_root, total_amount, expiration),
+        ExecuteMsg::Claim {
+            stage,
+            amount,
+            proof,
+        } => claim(deps, env, info, stage, amount, proof),
+        ExecuteMsg::Withdraw { stage } => withdraw(deps, env, info, stage),
+    }
+}
+
+// Only owner can execute it
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+// Only owner can execute it
+pub fn register_merkle_root(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root: String,
+    total_amount: Uint128,
+    expiration: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if total_amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    if let Some(expiration) = expiration {
+        if expiration <= env.block.time.seconds() {
+            return Err(ContractError::InvalidExpiration {});
+        }
+    }
+
+    // validate the root is a sha256 hash
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&merkle_root, &mut root_buf)?;
+
+    let stage = LATEST_STAGE
+        .load(deps.storage)?
+        .checked_add(1)
+        .ok_or(ContractError::StageLimitReached {})?;
+    LATEST_STAGE.save(deps.storage, &stage)?;
+    STAGES.save(
+        deps.storage,
+        stage,
+        &Stage {
+            merkle_root: merkle_root.clone(),
+            total_amount,
+            claimed_amount: Uint128::zero(),
+            expiration,
+            withdrawn: false,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_merkle_root"),
+        ("stage", &stage.to_string()),
+        ("merkle_root", &merkle_root),
+        ("total_amount", &total_amount.to_string()),
+    ]))
+}
+
+pub fn claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage: u8,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut stage_info = STAGES
+        .may_load(deps.storage, stage)?
+        .ok_or(ContractError::StageNotFound { stage })?;
+    if stage_info.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::StageExpired { stage });
+    }
+
+    let claimer_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if CLAIMS.has(deps.storage, (stage, &claimer_raw)) {
+        return Err(ContractError::Claimed {});
+    }
+
+    if !verify_proof(&stage_info.merkle_root, &info.sender, amount, &proof)? {
+        return Err(ContractError::VerificationFailed {});
+    }
+
+    stage_info.claimed_amount = stage_info.claimed_amount.checked_add(amount)?;
+    if stage_info.claimed_amount > stage_info.total_amount {
+        return Err(ContractError::ClaimExceedsTotalAmount {});
+    }
+    STAGES.save(deps.storage, stage, &stage_info)?;
+    CLAIMS.save(deps.storage, (stage, &claimer_raw), &true)?;
+
+    Ok(Response::new()
+        .add_message(transfer_msg(
+            deps.as_ref(),
+            &config,
+            info.sender.clone(),
+            amount,
+        )?)
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("stage", &stage.to_string()),
+            ("address", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+/// Hash the leaf of the claim up to the root, the pairs of hashes are sorted before hashing
+fn verify_proof(
+    merkle_root: &str,
+    address: &Addr,
+    amount: Uint128,
+    proof: &[String],
+) -> Result<bool, ContractError> {
+    let leaf: [u8; 32] = Sha256::digest(format!("{}{}", address, amount).as_bytes()).into();
+    let hash = proof.iter().try_fold(leaf, |hash, proof_hash| {
+        let mut proof_buf: [u8; 32] = [0; 32];
+        hex::decode_to_slice(proof_hash, &mut proof_buf)?;
+
+        let mut hashes = [hash, proof_buf];
+        hashes.sort_unstable();
+        Ok::<[u8; 32], ContractError>(Sha256::digest(hashes.concat()).into())
+    })?;
+
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(merkle_root, &mut root_buf)?;
+
+    Ok(root_buf == hash)
+}
+
+// Only owner can execute it
+pub fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage: u8,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut stage_info = STAGES
+        .may_load(deps.storage, stage)?
+        .ok_or(ContractError::StageNotFound { stage })?;
+    if !stage_info.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::StageNotExpired { stage });
+    }
+
+    let amount = stage_info
+        .total_amount
+        .saturating_sub(stage_info.claimed_amount);
+    if stage_info.withdrawn || amount.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    stage_info.withdrawn = true;
+    STAGES.save(deps.storage, stage, &stage_info)?;
+
+    Ok(Response::new()
+        .add_message(transfer_msg(deps.as_ref(), &config, info.sender, amount)?)
+        .add_attributes(vec![
+            ("action", "withdraw"),
+            ("stage", &stage.to_string()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+fn transfer_msg(
+    deps: Deps,
+    config: &Config,
+    recipient: Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.halo_token)?.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::MerkleRoot { stage } => to_json_binary(&query_merkle_root(deps, stage)?),
+        QueryMsg::LatestStage {} => to_json_binary(&query_latest_stage(deps)?),
+        QueryMsg::IsClaimed { stage, address } => {
+            to_json_binary(&query_is_claimed(deps, stage, address)?)
+        }
+        QueryMsg::Claimable {
+            stage,
+            address,
+            amount,
+            proof,
+        } => to_json_binary(&query_claimable(deps, env, stage, address, amount, proof)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        halo_token: deps.api.addr_humanize(&config.halo_token)?.to_string(),
+    })
+}
+
+pub fn query_merkle_root(deps: Deps, stage: u8) -> StdResult<MerkleRootResponse> {
+    let stage_info = STAGES.load(deps.storage, stage)?;
+
+    Ok(MerkleRootResponse {
+        stage,
+        merkle_root: stage_info.merkle_root,
+        total_amount: stage_info.total_amount,
+        claimed_amount: stage_info.claimed_amount,
+        expiration: stage_info.expiration,
+    })
+}
+
+pub fn query_latest_stage(deps: Deps) -> StdResult<LatestStageResponse> {
+    Ok(LatestStageResponse {
+        latest_stage: LATEST_STAGE.load(deps.storage)?,
+    })
+}
+
+pub fn query_is_claimed(deps: Deps, stage: u8, address: String) -> StdResult<IsClaimedResponse> {
+    Ok(IsClaimedResponse {
+        is_claimed: CLAIMS.has(
+            deps.storage,
+            (stage, &deps.api.addr_canonicalize(&address)?),
+        ),
+    })
+}
+
+pub fn query_claimable(
+    deps: Deps,
+    env: Env,
+    stage: u8,
+    address: String,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> StdResult<ClaimableResponse> {
+    let stage_info = STAGES.load(deps.storage, stage)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    let claimable = !stage_info.is_expired(env.block.time.seconds())
+        && stage_info
+            .claimed_amount
+            .checked_add(amount)
+            .map(|claimed_amount| claimed_amount <= stage_info.total_amount)
+            .unwrap_or(false)
+        && !CLAIMS.has(
+            deps.storage,
+            (stage, &deps.api.addr_canonicalize(address.as_str())?),
+        )
+        && verify_proof(&stage_info.merkle_root, &address, amount, &proof).unwrap_or(false);
+
+    Ok(ClaimableResponse {
+        claimable: if claimable { amount } else { Uint128::zero() },
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
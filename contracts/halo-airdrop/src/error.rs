@@ -0,0 +1,48 @@
+use cosmwasm_std::{OverflowError, StdError};
+use hex::FromHexError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("{0}")]
+    Hex(#[from] FromHexError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Expiration must be in the future")]
+    InvalidExpiration {},
+
+    #[error("No more stage can be registered")]
+    StageLimitReached {},
+
+    #[error("Stage {stage} does not exist")]
+    StageNotFound { stage: u8 },
+
+    #[error("Stage {stage} has expired")]
+    StageExpired { stage: u8 },
+
+    #[error("Stage {stage} has not expired")]
+    StageNotExpired { stage: u8 },
+
+    #[error("Already claimed")]
+    Claimed {},
+
+    #[error("Claim exceeds the total amount of the stage")]
+    ClaimExceedsTotalAmount {},
+
+    #[error("Merkle proof verification failed")]
+    VerificationFailed {},
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+}
@@ -0,0 +1,37 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub halo_token: CanonicalAddr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const LATEST_STAGE: Item<u8> = Item::new("latest_stage");
+
+#[cw_serde]
+pub struct Stage {
+    pub merkle_root: String,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub expiration: Option<u64>,
+    /// The amount not claimed is withdrawn once
+    pub withdrawn: bool,
+}
+
+impl Stage {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expiration
+            .map(|expiration| now >= expiration)
+            .unwrap_or(false)
+    }
+}
+
+// key : stage
+pub const STAGES: Map<u8, Stage> = Map::new("stage");
+
+// key : (stage, address)
+pub const CLAIMS: Map<(u8, &[u8]), bool> = Map::new("claim");
@@ -0,0 +1,334 @@
+use crate::contract::{execute, instantiate, query_claimable, query_merkle_root};
+use crate::error::ContractError;
+use crate::state::LATEST_STAGE;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{to_json_binary, CosmosMsg, DepsMut, Env, SubMsg, Timestamp, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use haloswap::airdrop::{ExecuteMsg, InstantiateMsg, MerkleRootResponse};
+use sha2::{Digest, Sha256};
+
+const START: u64 = 1_600_000_000;
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn init(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        halo_token: "halo0000".to_string(),
+    };
+    instantiate(deps, env_at(START), mock_info("owner0000", &[]), msg).unwrap();
+}
+
+fn leaf(address: &str, amount: u128) -> [u8; 32] {
+    Sha256::digest(format!("{}{}", address, amount).as_bytes()).into()
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let mut hashes = [a, b];
+    hashes.sort_unstable();
+    Sha256::digest(hashes.concat()).into()
+}
+
+/// Tree of addr0000: 100, addr0001: 200 and addr0002: 300, returns the root with the proofs
+fn merkle_tree() -> (String, Vec<Vec<String>>) {
+    let leaves = [
+        leaf("addr0000", 100),
+        leaf("addr0001", 200),
+        leaf("addr0002", 300),
+    ];
+    let node = hash_pair(leaves[0], leaves[1]);
+    let root = hash_pair(node, leaves[2]);
+
+    (
+        hex::encode(root),
+        vec![
+            vec![hex::encode(leaves[1]), hex::encode(leaves[2])],
+            vec![hex::encode(leaves[0]), hex::encode(leaves[2])],
+            vec![hex::encode(node)],
+        ],
+    )
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "halo0000".to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn claim_airdrop() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    let (merkle_root, proofs) = merkle_tree();
+    let msg = ExecuteMsg::RegisterMerkleRoot {
+        merkle_root: merkle_root.clone(),
+        total_amount: Uint128::from(600u128),
+        expiration: None,
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+
+    // the proof must match the sender and the amount
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim {
+                stage: 1,
+                amount: Uint128::from(100u128),
+                proof: proofs[0].clone(),
+            }
+        ),
+        Err(ContractError::VerificationFailed {})
+    );
+    assert_eq!(
+        query_claimable(
+            deps.as_ref(),
+            env_at(START),
+            1,
+            "addr0000".to_string(),
+            Uint128::from(101u128),
+            proofs[0].clone()
+        )
+        .unwrap()
+        .claimable,
+        Uint128::zero()
+    );
+    assert_eq!(
+        query_claimable(
+            deps.as_ref(),
+            env_at(START),
+            1,
+            "addr0000".to_string(),
+            Uint128::from(100u128),
+            proofs[0].clone()
+        )
+        .unwrap()
+        .claimable,
+        Uint128::from(100u128)
+    );
+
+    for (i, (address, amount)) in [("addr0000", 100u128), ("addr0002", 300u128)]
+        .into_iter()
+        .enumerate()
+    {
+        let res = execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info(address, &[]),
+            ExecuteMsg::Claim {
+                stage: 1,
+                amount: Uint128::from(amount),
+                proof: proofs[i * 2].clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![transfer_msg(address, amount)]);
+    }
+
+    // an address claims once per stage
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::Claim {
+                stage: 1,
+                amount: Uint128::from(100u128),
+                proof: proofs[0].clone(),
+            }
+        ),
+        Err(ContractError::Claimed {})
+    );
+    assert_eq!(
+        query_merkle_root(deps.as_ref(), 1).unwrap(),
+        MerkleRootResponse {
+            stage: 1,
+            merkle_root,
+            total_amount: Uint128::from(600u128),
+            claimed_amount: Uint128::from(400u128),
+            expiration: None,
+        }
+    );
+}
+
+#[test]
+fn withdraw_expired_stage() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    let (merkle_root, proofs) = merkle_tree();
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        ExecuteMsg::RegisterMerkleRoot {
+            merkle_root,
+            total_amount: Uint128::from(600u128),
+            expiration: Some(START + 100),
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim {
+            stage: 1,
+            amount: Uint128::from(200u128),
+            proof: proofs[1].clone(),
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Withdraw { stage: 1 };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 99),
+            mock_info("owner0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::StageNotExpired { stage: 1 })
+    );
+
+    // the claims are closed once the stage expires
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 100),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::Claim {
+                stage: 1,
+                amount: Uint128::from(100u128),
+                proof: proofs[0].clone(),
+            }
+        ),
+        Err(ContractError::StageExpired { stage: 1 })
+    );
+
+    // the owner withdraws the amount not claimed
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 100),
+        mock_info("owner0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("owner0000", 400)]);
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 100),
+            mock_info("owner0000", &[]),
+            msg
+        ),
+        Err(ContractError::NothingToWithdraw {})
+    );
+}
+
+#[test]
+fn claims_are_capped_by_the_total_amount() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    // the total amount of the stage is lower than the sum of the leaves
+    let (merkle_root, proofs) = merkle_tree();
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        ExecuteMsg::RegisterMerkleRoot {
+            merkle_root,
+            total_amount: Uint128::from(350u128),
+            expiration: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim {
+            stage: 1,
+            amount: Uint128::from(200u128),
+            proof: proofs[1].clone(),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_claimable(
+            deps.as_ref(),
+            env_at(START),
+            1,
+            "addr0002".to_string(),
+            Uint128::from(300u128),
+            proofs[2].clone()
+        )
+        .unwrap()
+        .claimable,
+        Uint128::zero()
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0002", &[]),
+            ExecuteMsg::Claim {
+                stage: 1,
+                amount: Uint128::from(300u128),
+                proof: proofs[2].clone(),
+            }
+        ),
+        Err(ContractError::ClaimExceedsTotalAmount {})
+    );
+}
+
+#[test]
+fn register_merkle_root_stage_limit() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    LATEST_STAGE.save(deps.as_mut().storage, &u8::MAX).unwrap();
+
+    let (merkle_root, _) = merkle_tree();
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("owner0000", &[]),
+            ExecuteMsg::RegisterMerkleRoot {
+                merkle_root,
+                total_amount: Uint128::from(600u128),
+                expiration: None,
+            }
+        ),
+        Err(ContractError::StageLimitReached {})
+    );
+}
@@ -1,24 +1,38 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response,
-    StdError, StdResult, SubMsg, WasmMsg,
+    instantiate2_address, to_json_binary, Addr, Api, Binary, CanonicalAddr, CosmosMsg, Decimal,
+    Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg,
+    Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
+use cw20::Cw20ExecuteMsg;
 use haloswap::querier::{query_balance, query_pair_info_from_pair};
 
+use crate::migration::{migrate_to_v0_2, migrate_to_v0_3};
 use crate::state::{
-    add_allow_native_token, pair_key, read_pairs, Config, TmpPairInfo, ALLOW_NATIVE_TOKENS, CONFIG,
-    PAIRS, TMP_PAIR_INFO,
+    add_allow_native_token, asset_symbol, deprecate_pair, is_pending_pair, next_reply_id, pair_key,
+    pair_salt, pairs, read_deprecated_pairs, read_ibc_denoms, read_native_tokens,
+    read_outdated_pairs, read_pairs, read_pairs_by_asset, read_pairs_by_creator,
+    remove_allow_native_token, Config, PairCreationConfigRaw, TmpPairInfo, ALLOW_NATIVE_TOKENS,
+    CONFIG, IBC_DENOMS, PAIR_CODE_IDS, PAIR_CREATION, PAIR_INCENTIVES, TMP_PAIR_INFOS,
 };
 
-use cw_utils::parse_reply_instantiate_data;
-use haloswap::asset::{AssetInfo, CreatePairRequirements, PairInfo, PairInfoRaw};
+use cw_utils::{must_pay, nonpayable, parse_reply_instantiate_data};
+use haloswap::asset::{
+    classify_denom, is_token_factory_denom, Asset, AssetInfo, AssetInfoRaw, CreatePairRequirements,
+    DenomKind, PairInfo, PairInfoRaw,
+};
 use haloswap::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, NativeTokenDecimalsResponse,
-    PairsResponse, QueryMsg,
+    ConfigResponse, CreatePairRequest, ExecuteMsg, IbcDenomInfo, IbcDenomsResponse, InstantiateMsg,
+    MigrateMsg, NativeTokenDecimalsResponse, NativeTokensResponse, OutdatedPairsResponse,
+    PairCreationConfig, PairsResponse, PredictedPairAddressesResponse, QueryMsg,
+};
+use haloswap::migration::{assert_migrate_version, Version};
+use haloswap::pair::{
+    ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg, LiquidityTokenKind,
+    MigrateMsg as PairMigrateMsg, PauseFlags, LIQUIDITY_TOKEN_SALT,
 };
-use haloswap::pair::{InstantiateMsg as PairInstantiateMsg, MigrateMsg as PairMigrateMsg};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:halo-factory";
@@ -52,16 +66,68 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             token_code_id,
             pair_code_id,
         } => execute_update_config(deps, env, info, owner, token_code_id, pair_code_id),
+        ExecuteMsg::UpdatePairCreationConfig { pair_creation } => {
+            execute_update_pair_creation_config(deps, env, info, pair_creation)
+        }
         ExecuteMsg::CreatePair {
             asset_infos,
             requirements,
-        } => execute_create_pair(deps, env, info, asset_infos, requirements),
+            liquidity_token_kind,
+        } => execute_create_pair(
+            deps,
+            env,
+            info,
+            asset_infos,
+            requirements,
+            liquidity_token_kind,
+        ),
+        ExecuteMsg::CreatePairs { pairs } => execute_create_pairs(deps, env, info, pairs),
         ExecuteMsg::AddNativeTokenDecimals { denom, decimals } => {
             execute_add_native_token_decimals(deps, env, info, denom, decimals)
         }
+        ExecuteMsg::RemoveNativeTokenDecimals { denom } => {
+            execute_remove_native_token_decimals(deps, env, info, denom)
+        }
+        ExecuteMsg::UpdatePairConfig {
+            asset_infos,
+            requirements,
+            commission_rate,
+            pause,
+        } => execute_update_pair_config(
+            deps,
+            env,
+            info,
+            asset_infos,
+            requirements,
+            commission_rate,
+            pause,
+        ),
+        ExecuteMsg::RegisterIbcDenom {
+            denom,
+            symbol,
+            channel,
+        } => execute_register_ibc_denom(deps, env, info, denom, symbol, channel),
+        ExecuteMsg::DeregisterIbcDenom { denom } => {
+            execute_deregister_ibc_denom(deps, env, info, denom)
+        }
         ExecuteMsg::MigratePair { contract, code_id } => {
             execute_migrate_pair(deps, env, info, contract, code_id)
         }
+        ExecuteMsg::MigratePairs {
+            start_after,
+            limit,
+            code_id,
+            msg,
+        } => execute_migrate_pairs(deps, env, info, start_after, limit, code_id, msg),
+        ExecuteMsg::SetPairIncentive {
+            asset_infos,
+            incentive_contract,
+        } => execute_set_pair_incentive(deps, env, info, asset_infos, incentive_contract),
+        ExecuteMsg::Deregister { asset_infos } => execute_deregister(deps, env, info, asset_infos),
+        ExecuteMsg::ReplacePair {
+            asset_infos,
+            pair_contract,
+        } => execute_replace_pair(deps, env, info, asset_infos, pair_contract),
     }
 }
 
@@ -101,16 +167,229 @@ pub fn execute_update_config(
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
-// Anyone can execute it to create swap pair
-pub fn execute_create_pair(
+// Only owner can execute it
+pub fn execute_update_pair_creation_config(
     deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pair_creation: PairCreationConfig,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let pair_creation = pair_creation_config_to_raw(deps.api, pair_creation)?;
+    PAIR_CREATION.save(deps.storage, &pair_creation)?;
+
+    Ok(Response::new().add_attribute("action", "update_pair_creation_config"))
+}
+
+fn pair_creation_config_to_raw(
+    api: &dyn Api,
+    pair_creation: PairCreationConfig,
+) -> StdResult<PairCreationConfigRaw> {
+    let creation_fee = match pair_creation.creation_fee {
+        Some(creation_fee) => {
+            if creation_fee.amount.is_zero() {
+                return Err(StdError::generic_err(
+                    "creation fee must be greater than zero",
+                ));
+            }
+            if pair_creation.fee_collector.is_none() {
+                return Err(StdError::generic_err(
+                    "a fee collector is required to charge a creation fee",
+                ));
+            }
+
+            Some(creation_fee.to_raw(api)?)
+        }
+        None => None,
+    };
+
+    let fee_collector = match pair_creation.fee_collector {
+        Some(fee_collector) => {
+            api.addr_validate(&fee_collector)?;
+            Some(api.addr_canonicalize(&fee_collector)?)
+        }
+        None => None,
+    };
+
+    let allowed_assets = match pair_creation.allowed_assets {
+        Some(allowed_assets) => Some(
+            allowed_assets
+                .iter()
+                .map(|asset_info| asset_info.to_raw(api))
+                .collect::<StdResult<Vec<AssetInfoRaw>>>()?,
+        ),
+        None => None,
+    };
+
+    Ok(PairCreationConfigRaw {
+        creation_fee,
+        fee_collector,
+        owner_only: pair_creation.owner_only,
+        allowed_assets,
+        denied_assets: pair_creation
+            .denied_assets
+            .iter()
+            .map(|asset_info| asset_info.to_raw(api))
+            .collect::<StdResult<Vec<AssetInfoRaw>>>()?,
+    })
+}
+
+/// Collect the creation fee of `pair_count` pairs from the pair creator into the fee collector,
+/// native fees are sent along with the message and cw20 fees are transferred from an allowance
+fn collect_creation_fee(
+    deps: Deps,
+    info: &MessageInfo,
+    pair_creation: &PairCreationConfigRaw,
+    pair_count: u128,
+) -> StdResult<Option<CosmosMsg>> {
+    let (creation_fee, fee_collector) =
+        match (&pair_creation.creation_fee, &pair_creation.fee_collector) {
+            (Some(creation_fee), Some(fee_collector)) => (
+                Asset {
+                    amount: creation_fee.amount * Uint128::from(pair_count),
+                    ..creation_fee.to_normal(deps.api)?
+                },
+                deps.api.addr_humanize(fee_collector)?,
+            ),
+            _ => {
+                nonpayable(info).map_err(|err| StdError::generic_err(err.to_string()))?;
+                return Ok(None);
+            }
+        };
+
+    match &creation_fee.info {
+        AssetInfo::NativeToken { denom } => {
+            // exactly the fee must be sent, without any other coin
+            let sent =
+                must_pay(info, denom).map_err(|err| StdError::generic_err(err.to_string()))?;
+            if sent != creation_fee.amount {
+                return Err(StdError::generic_err(format!(
+                    "a pair creation fee of {} is required",
+                    creation_fee
+                )));
+            }
+
+            Ok(Some(creation_fee.into_msg(fee_collector)?))
+        }
+        AssetInfo::Token { contract_addr } => {
+            nonpayable(info).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+            Ok(Some(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: fee_collector.to_string(),
+                    amount: creation_fee.amount,
+                })?,
+                funds: vec![],
+            })))
+        }
+    }
+}
+
+// Anyone can execute it to create swap pair, unless restricted to the owner
+pub fn execute_create_pair(
+    mut deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     asset_infos: [AssetInfo; 2],
     requirements: CreatePairRequirements,
+    liquidity_token_kind: Option<LiquidityTokenKind>,
 ) -> StdResult<Response> {
     let config: Config = CONFIG.load(deps.storage)?;
+    let pair_creation = PAIR_CREATION.may_load(deps.storage)?.unwrap_or_default();
+
+    if pair_creation.owner_only && deps.api.addr_canonicalize(info.sender.as_str())? != config.owner
+    {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let creation_fee_msg = collect_creation_fee(deps.as_ref(), &info, &pair_creation, 1)?;
+    let pair = format!("{}-{}", asset_infos[0], asset_infos[1]);
+    let creator = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let create_pair_msg = create_pair_submsg(
+        deps.branch(),
+        &env,
+        &config,
+        &pair_creation,
+        asset_infos,
+        requirements,
+        liquidity_token_kind,
+        &creator,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(creation_fee_msg)
+        .add_attributes(vec![("action", "create_pair"), ("pair", &pair)])
+        .add_submessage(create_pair_msg))
+}
 
+// Anyone can execute it to create several swap pairs, unless restricted to the owner
+pub fn execute_create_pairs(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pairs: Vec<CreatePairRequest>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pair_creation = PAIR_CREATION.may_load(deps.storage)?.unwrap_or_default();
+
+    if pair_creation.owner_only && deps.api.addr_canonicalize(info.sender.as_str())? != config.owner
+    {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if pairs.is_empty() {
+        return Err(StdError::generic_err("no pairs to create"));
+    }
+
+    let creation_fee_msg =
+        collect_creation_fee(deps.as_ref(), &info, &pair_creation, pairs.len() as u128)?;
+    let mut attributes = vec![("action", "create_pairs".to_string())];
+    let creator = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut create_pair_msgs: Vec<SubMsg> = vec![];
+    for pair in pairs {
+        attributes.push((
+            "pair",
+            format!("{}-{}", pair.asset_infos[0], pair.asset_infos[1]),
+        ));
+        create_pair_msgs.push(create_pair_submsg(
+            deps.branch(),
+            &env,
+            &config,
+            &pair_creation,
+            pair.asset_infos,
+            pair.requirements,
+            pair.liquidity_token_kind,
+            &creator,
+        )?);
+    }
+
+    Ok(Response::new()
+        .add_messages(creation_fee_msg)
+        .add_attributes(attributes)
+        .add_submessages(create_pair_msgs))
+}
+
+/// Build the instantiate2 submessage of a pair,
+/// the pair context is kept under the reply id of the submessage until the pair is registered
+#[allow(clippy::too_many_arguments)]
+fn create_pair_submsg(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    pair_creation: &PairCreationConfigRaw,
+    asset_infos: [AssetInfo; 2],
+    requirements: CreatePairRequirements,
+    liquidity_token_kind: Option<LiquidityTokenKind>,
+    creator: &CanonicalAddr,
+) -> StdResult<SubMsg> {
     // don't allow to create pair with same token
     if asset_infos[0] == asset_infos[1] {
         return Err(StdError::generic_err("same asset"));
@@ -133,52 +412,74 @@ pub fn execute_create_pair(
         asset_infos[1].to_raw(deps.api)?,
     ];
 
+    for raw_info in raw_infos.iter() {
+        let denied = pair_creation
+            .denied_assets
+            .iter()
+            .any(|denied| denied.equal(raw_info));
+        let allowed = match &pair_creation.allowed_assets {
+            Some(allowed_assets) => allowed_assets.iter().any(|allowed| allowed.equal(raw_info)),
+            None => true,
+        };
+        if denied || !allowed {
+            return Err(StdError::generic_err(format!(
+                "asset {} is not allowed",
+                raw_info.to_normal(deps.api)?
+            )));
+        }
+    }
+
     let asset_decimals = [asset_1_decimal, asset_2_decimal];
 
     let pair_key = pair_key(&raw_infos);
-    if let Ok(Some(_)) = PAIRS.may_load(deps.storage, &pair_key) {
+    if let Ok(Some(_)) = pairs().may_load(deps.storage, &pair_key) {
         return Err(StdError::generic_err("Pair already exists"));
     }
+    if is_pending_pair(deps.storage, &pair_key)? {
+        return Err(StdError::generic_err("Pair is already being created"));
+    }
 
-    TMP_PAIR_INFO.save(
+    let salt = pair_salt(deps.storage, &pair_key)?;
+    let reply_id = next_reply_id(deps.storage)?;
+    TMP_PAIR_INFOS.save(
         deps.storage,
+        reply_id,
         &TmpPairInfo {
             pair_key,
             asset_infos: raw_infos,
             asset_decimals,
+            creator: creator.clone(),
         },
     )?;
 
-    Ok(Response::new()
-        .add_attributes(vec![
-            ("action", "create_pair"),
-            ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
-        ])
-        .add_submessage(SubMsg {
-            id: 1,
-            gas_limit: None,
-            msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
-                code_id: config.pair_code_id,
-                funds: vec![],
-                admin: Some(env.contract.address.to_string()),
-                label: "pair".to_string(),
-                msg: to_binary(&PairInstantiateMsg {
-                    asset_infos,
-                    token_code_id: config.token_code_id,
-                    asset_decimals,
-                    requirements,
-                })?,
-            }),
-            reply_on: ReplyOn::Success,
-        }))
+    Ok(SubMsg {
+        id: reply_id,
+        gas_limit: None,
+        msg: CosmosMsg::Wasm(WasmMsg::Instantiate2 {
+            code_id: config.pair_code_id,
+            funds: vec![],
+            admin: Some(env.contract.address.to_string()),
+            label: "pair".to_string(),
+            salt: Binary::from(salt),
+            msg: to_json_binary(&PairInstantiateMsg {
+                asset_infos,
+                token_code_id: config.token_code_id,
+                asset_decimals,
+                requirements,
+                liquidity_token_kind,
+            })?,
+        }),
+        reply_on: ReplyOn::Success,
+    })
 }
 
+// Only owner can execute it
 pub fn execute_add_native_token_decimals(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     denom: String,
-    decimals: u8,
+    decimals: Option<u8>,
 ) -> StdResult<Response> {
     let config: Config = CONFIG.load(deps.storage)?;
 
@@ -187,12 +488,33 @@ pub fn execute_add_native_token_decimals(
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    let balance = query_balance(&deps.querier, env.contract.address, denom.to_string())?;
-    if balance.is_zero() {
-        return Err(StdError::generic_err(
-            "a balance greater than zero is required by the factory for verification",
-        ));
-    }
+    // the denom metadata verifies the denom, otherwise the factory must hold a balance of it
+    let (decimals, source) = match query_metadata_decimals(deps.as_ref(), &denom) {
+        Some(metadata_decimals) => {
+            if decimals.is_some() && decimals != Some(metadata_decimals) {
+                return Err(StdError::generic_err(format!(
+                    "decimals mismatch the denom metadata: {}",
+                    metadata_decimals
+                )));
+            }
+
+            (metadata_decimals, "metadata")
+        }
+        None => {
+            let decimals = decimals.ok_or_else(|| {
+                StdError::generic_err("decimals are required for a denom without metadata")
+            })?;
+
+            let balance = query_balance(&deps.querier, env.contract.address, denom.to_string())?;
+            if balance.is_zero() {
+                return Err(StdError::generic_err(
+                    "a balance greater than zero is required by the factory for verification",
+                ));
+            }
+
+            (decimals, "registry")
+        }
+    };
 
     add_allow_native_token(deps.storage, denom.to_string(), decimals)?;
 
@@ -200,9 +522,135 @@ pub fn execute_add_native_token_decimals(
         ("action", "add_allow_native_token"),
         ("denom", &denom),
         ("decimals", &decimals.to_string()),
+        ("source", source),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_remove_native_token_decimals(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denom: String,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if !ALLOW_NATIVE_TOKENS.has(deps.storage, denom.as_bytes()) {
+        return Err(StdError::generic_err("Native token is not registered"));
+    }
+
+    remove_allow_native_token(deps.storage, denom.to_string());
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_allow_native_token"),
+        ("denom", &denom),
     ]))
 }
 
+// Only owner can execute it
+pub fn execute_register_ibc_denom(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denom: String,
+    symbol: String,
+    channel: String,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let hash = ibc_denom_hash(&denom)?;
+    if symbol.is_empty() {
+        return Err(StdError::generic_err("symbol must not be empty"));
+    }
+
+    let channel_valid = channel
+        .strip_prefix("channel-")
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    if !channel_valid {
+        return Err(StdError::generic_err(format!(
+            "invalid channel: {}",
+            channel
+        )));
+    }
+
+    // the denom is stored with the normalized hash
+    let denom = format!("ibc/{}", hash);
+    IBC_DENOMS.save(
+        deps.storage,
+        &hash,
+        &IbcDenomInfo {
+            denom: denom.clone(),
+            symbol: symbol.clone(),
+            channel: channel.clone(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_ibc_denom"),
+        ("denom", &denom),
+        ("symbol", &symbol),
+        ("channel", &channel),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_deregister_ibc_denom(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denom: String,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let hash = ibc_denom_hash(&denom)?;
+    let ibc_denom = IBC_DENOMS
+        .may_load(deps.storage, &hash)?
+        .ok_or_else(|| StdError::generic_err("Ibc denom is not registered"))?;
+
+    IBC_DENOMS.remove(deps.storage, &hash);
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deregister_ibc_denom"),
+        ("denom", &ibc_denom.denom),
+    ]))
+}
+
+fn ibc_denom_hash(denom: &str) -> StdResult<String> {
+    match classify_denom(denom) {
+        DenomKind::Ibc { hash } => Ok(hash),
+        _ => Err(StdError::generic_err(format!(
+            "{} is not an ibc denom",
+            denom
+        ))),
+    }
+}
+
+/// Read the decimals of the display unit from the bank denom metadata
+fn query_metadata_decimals(deps: Deps, denom: &str) -> Option<u8> {
+    let metadata = deps.querier.query_denom_metadata(denom).ok()?;
+
+    metadata
+        .denom_units
+        .iter()
+        .find(|unit| unit.denom == metadata.display)
+        .and_then(|unit| u8::try_from(unit.exponent).ok())
+}
+
 pub fn execute_migrate_pair(
     deps: DepsMut,
     _env: Env,
@@ -217,41 +665,367 @@ pub fn execute_migrate_pair(
         return Err(StdError::generic_err("unauthorized"));
     }
 
+    // only the registered pairs are migrated through the factory
+    let contract_addr = deps.api.addr_canonicalize(&contract)?;
+    if pairs()
+        .idx
+        .contract_addr
+        .item(deps.storage, contract_addr.to_vec())?
+        .is_none()
+    {
+        return Err(StdError::generic_err("Pair is not registered"));
+    }
+
     let code_id = code_id.unwrap_or(config.pair_code_id);
+    PAIR_CODE_IDS.save(deps.storage, contract_addr.as_slice(), &code_id)?;
 
     Ok(
         Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
             contract_addr: contract,
             new_code_id: code_id,
-            msg: to_binary(&PairMigrateMsg {})?,
+            msg: to_json_binary(&PairMigrateMsg::default())?,
         })),
     )
 }
 
+// Only owner can execute it
+pub fn execute_migrate_pairs(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+    code_id: Option<u64>,
+    msg: Option<Binary>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let code_id = code_id.unwrap_or(config.pair_code_id);
+    let msg = match msg {
+        Some(msg) => msg,
+        None => to_json_binary(&PairMigrateMsg::default())?,
+    };
+
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    // pairs already migrated to the code id are skipped
+    let pairs: Vec<PairInfo> = read_pairs(deps.storage, deps.api, start_after, limit)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for pair in pairs.iter() {
+        let contract_addr = deps.api.addr_canonicalize(&pair.contract_addr)?;
+        if PAIR_CODE_IDS.may_load(deps.storage, contract_addr.as_slice())? == Some(code_id) {
+            continue;
+        }
+
+        PAIR_CODE_IDS.save(deps.storage, contract_addr.as_slice(), &code_id)?;
+        messages.push(CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: pair.contract_addr.clone(),
+            new_code_id: code_id,
+            msg: msg.clone(),
+        }));
+    }
+
+    let mut attributes = vec![
+        ("action", "migrate_pairs".to_string()),
+        ("code_id", code_id.to_string()),
+        ("migrated_count", messages.len().to_string()),
+    ];
+    if let Some(last) = pairs.last() {
+        attributes.push((
+            "last_pair",
+            format!("{}-{}", last.asset_infos[0], last.asset_infos[1]),
+        ));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+// Only owner can execute it
+pub fn execute_update_pair_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    requirements: Option<CreatePairRequirements>,
+    commission_rate: Option<Decimal>,
+    pause: Option<PauseFlags>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let mut pair_info = pairs()
+        .may_load(deps.storage, &pair_key)?
+        .ok_or_else(|| StdError::generic_err("Pair does not exist"))?;
+
+    // the registry keeps the requirements the pair is updated with
+    if let Some(requirements) = &requirements {
+        pair_info.requirements = requirements.clone();
+        pairs().save(deps.storage, &pair_key, &pair_info)?;
+    }
+
+    let pair_contract = deps.api.addr_humanize(&pair_info.contract_addr)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: pair_contract.to_string(),
+            msg: to_json_binary(&PairExecuteMsg::UpdatePairConfig {
+                requirements,
+                commission_rate,
+                pause,
+            })?,
+            funds: vec![],
+        })
+        .add_attributes(vec![
+            ("action", "update_pair_config"),
+            ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+            ("pair_contract_addr", pair_contract.as_str()),
+        ]))
+}
+
+// Only owner can execute it
+pub fn execute_set_pair_incentive(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    incentive_contract: Option<String>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    if !pairs().has(deps.storage, &pair_key) {
+        return Err(StdError::generic_err("Pair does not exist"));
+    }
+
+    let incentive_contract = match incentive_contract {
+        Some(incentive_contract) => {
+            let incentive_contract = deps.api.addr_validate(&incentive_contract)?;
+            PAIR_INCENTIVES.save(
+                deps.storage,
+                &pair_key,
+                &deps.api.addr_canonicalize(incentive_contract.as_str())?,
+            )?;
+            incentive_contract.to_string()
+        }
+        None => {
+            PAIR_INCENTIVES.remove(deps.storage, &pair_key);
+            String::new()
+        }
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_pair_incentive"),
+        ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+        ("incentive_contract", &incentive_contract),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_deregister(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    if !pairs().has(deps.storage, &pair_key) {
+        return Err(StdError::generic_err("Pair does not exist"));
+    }
+
+    let pair_info = deprecate_pair(deps.storage, &pair_key)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deregister"),
+        ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+        (
+            "pair_contract_addr",
+            deps.api.addr_humanize(&pair_info.contract_addr)?.as_str(),
+        ),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_replace_pair(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    pair_contract: String,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // the new pair must trade the same assets
+    let pair_contract = deps.api.addr_validate(&pair_contract)?;
+    let pair_info = query_pair_info_from_pair(&deps.querier, pair_contract.clone())?;
+    let raw_infos = [
+        pair_info.asset_infos[0].to_raw(deps.api)?,
+        pair_info.asset_infos[1].to_raw(deps.api)?,
+    ];
+    let expected_infos = [
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ];
+    if pair_key(&raw_infos) != pair_key(&expected_infos) {
+        return Err(StdError::generic_err("asset infos mismatch"));
+    }
+
+    // the factory must be able to migrate the new pair, its code id is recorded
+    // so `MigratePairs` only migrates it to another code
+    let contract_info = deps
+        .querier
+        .query_wasm_contract_info(pair_contract.as_str())?;
+    if contract_info.admin.as_deref() != Some(env.contract.address.as_str()) {
+        return Err(StdError::generic_err(
+            "the factory is not the admin of the pair",
+        ));
+    }
+
+    let pair_key = pair_key(&raw_infos);
+
+    let mut response = Response::new();
+    if let Some(current) = pairs().may_load(deps.storage, &pair_key)? {
+        if current.contract_addr == deps.api.addr_canonicalize(pair_contract.as_str())? {
+            return Err(StdError::generic_err("Pair already registered"));
+        }
+
+        let deprecated = deprecate_pair(deps.storage, &pair_key)?;
+        response = response.add_attribute(
+            "deprecated_pair_contract_addr",
+            deps.api.addr_humanize(&deprecated.contract_addr)?,
+        );
+    }
+
+    PAIR_CODE_IDS.save(
+        deps.storage,
+        deps.api
+            .addr_canonicalize(pair_contract.as_str())?
+            .as_slice(),
+        &contract_info.code_id,
+    )?;
+
+    let (liquidity_token, liquidity_denom) =
+        liquidity_token_to_raw(deps.api, &pair_info.liquidity_token)?;
+    pairs().save(
+        deps.storage,
+        &pair_key,
+        &PairInfoRaw {
+            liquidity_token,
+            contract_addr: deps.api.addr_canonicalize(pair_contract.as_str())?,
+            asset_infos: raw_infos,
+            asset_decimals: pair_info.asset_decimals,
+            requirements: pair_info.requirements,
+            creator: None,
+            liquidity_denom,
+        },
+    )?;
+
+    Ok(response.add_attributes(vec![
+        ("action", "replace_pair"),
+        ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+        ("pair_contract_addr", pair_contract.as_str()),
+    ]))
+}
+
+/// The liquidity token of a pair is the address of a cw20 token or a token factory denom
+fn liquidity_token_to_raw(
+    api: &dyn Api,
+    liquidity_token: &str,
+) -> StdResult<(CanonicalAddr, Option<String>)> {
+    if is_token_factory_denom(liquidity_token) {
+        return Ok((
+            CanonicalAddr::from(vec![]),
+            Some(liquidity_token.to_string()),
+        ));
+    }
+
+    Ok((api.addr_canonicalize(liquidity_token)?, None))
+}
+
 /// This just stores the result for future query
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
-    let tmp_pair_info = TMP_PAIR_INFO.load(deps.storage)?;
-
-    let reply = parse_reply_instantiate_data(msg).unwrap();
+    let tmp_pair_info = match TMP_PAIR_INFOS.may_load(deps.storage, msg.id)? {
+        Some(tmp_pair_info) => tmp_pair_info,
+        None => {
+            return Err(StdError::generic_err(format!(
+                "unknown reply id: {}",
+                msg.id
+            )))
+        }
+    };
+    TMP_PAIR_INFOS.remove(deps.storage, msg.id);
 
-    // let res: MsgInstantiateContractResponse =
-    //     Message::parse_from_bytes(msg.result.unwrap().data.unwrap().as_slice()).map_err(|_| {
-    //         StdError::parse_err("MsgInstantiateContractResponse", "failed to parse data")
-    //     })?;
+    let reply = parse_reply_instantiate_data(msg)
+        .map_err(|e| StdError::generic_err(format!("invalid pair instantiation reply: {}", e)))?;
 
     let pair_contract = &reply.contract_address;
     let pair_info = query_pair_info_from_pair(&deps.querier, Addr::unchecked(pair_contract))?;
 
-    PAIRS.save(
+    let config: Config = CONFIG.load(deps.storage)?;
+    PAIR_CODE_IDS.save(
+        deps.storage,
+        deps.api.addr_canonicalize(pair_contract)?.as_slice(),
+        &config.pair_code_id,
+    )?;
+
+    let (liquidity_token, liquidity_denom) =
+        liquidity_token_to_raw(deps.api, &pair_info.liquidity_token)?;
+    pairs().save(
         deps.storage,
         &tmp_pair_info.pair_key,
         &PairInfoRaw {
-            liquidity_token: deps.api.addr_canonicalize(&pair_info.liquidity_token)?,
+            liquidity_token,
             contract_addr: deps.api.addr_canonicalize(pair_contract)?,
             asset_infos: tmp_pair_info.asset_infos,
             asset_decimals: tmp_pair_info.asset_decimals,
             requirements: pair_info.requirements,
+            creator: Some(tmp_pair_info.creator),
+            liquidity_denom,
         },
     )?;
 
@@ -262,16 +1036,57 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::Pair { asset_infos } => to_binary(&query_pair(deps, asset_infos)?),
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Pair { asset_infos } => to_json_binary(&query_pair(deps, asset_infos)?),
         QueryMsg::Pairs { start_after, limit } => {
-            to_binary(&query_pairs(deps, start_after, limit)?)
+            to_json_binary(&query_pairs(deps, start_after, limit)?)
         }
         QueryMsg::NativeTokenDecimals { denom } => {
-            to_binary(&query_native_token_decimal(deps, denom)?)
+            to_json_binary(&query_native_token_decimal(deps, denom)?)
+        }
+        QueryMsg::NativeTokens { start_after, limit } => {
+            to_json_binary(&query_native_tokens(deps, start_after, limit)?)
         }
+        QueryMsg::PairsByAsset {
+            asset_info,
+            start_after,
+            limit,
+        } => to_json_binary(&query_pairs_by_asset(deps, asset_info, start_after, limit)?),
+        QueryMsg::PairByLiquidityToken { liquidity_token } => {
+            to_json_binary(&query_pair_by_liquidity_token(deps, liquidity_token)?)
+        }
+        QueryMsg::PairByContract { contract_addr } => {
+            to_json_binary(&query_pair_by_contract(deps, contract_addr)?)
+        }
+        QueryMsg::PairsByCreator {
+            creator,
+            start_after,
+            limit,
+        } => to_json_binary(&query_pairs_by_creator(deps, creator, start_after, limit)?),
+        QueryMsg::IbcDenom { denom } => to_json_binary(&query_ibc_denom(deps, denom)?),
+        QueryMsg::IbcDenoms { start_after, limit } => {
+            to_json_binary(&query_ibc_denoms(deps, start_after, limit)?)
+        }
+        QueryMsg::OutdatedPairs {
+            code_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query_outdated_pairs(deps, code_id, start_after, limit)?),
+        QueryMsg::PredictPairAddresses { asset_infos } => {
+            to_json_binary(&query_predict_pair_addresses(deps, env, asset_infos)?)
+        }
+        QueryMsg::DeprecatedPairs {
+            asset_infos,
+            start_after,
+            limit,
+        } => to_json_binary(&query_deprecated_pairs(
+            deps,
+            asset_infos,
+            start_after,
+            limit,
+        )?),
     }
 }
 
@@ -281,6 +1096,10 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
         token_code_id: state.token_code_id,
         pair_code_id: state.pair_code_id,
+        pair_creation: PAIR_CREATION
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .to_normal(deps.api)?,
     };
 
     Ok(resp)
@@ -291,8 +1110,8 @@ pub fn query_pair(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<PairInfo
         asset_infos[0].to_raw(deps.api)?,
         asset_infos[1].to_raw(deps.api)?,
     ]);
-    let pair_info: PairInfoRaw = PAIRS.load(deps.storage, &pair_key)?;
-    pair_info.to_normal(deps.api)
+    let pair_info: PairInfoRaw = pairs().load(deps.storage, &pair_key)?;
+    with_pair_details(deps, pair_info.to_normal(deps.api)?)
 }
 
 pub fn query_pairs(
@@ -309,7 +1128,235 @@ pub fn query_pairs(
         None
     };
 
-    let pairs: Vec<PairInfo> = read_pairs(deps.storage, deps.api, start_after, limit)?;
+    let pairs: Vec<PairInfo> = read_pairs(deps.storage, deps.api, start_after, limit)?
+        .into_iter()
+        .map(|pair_info| with_pair_details(deps, pair_info))
+        .collect::<StdResult<Vec<PairInfo>>>()?;
+    let resp = PairsResponse { pairs };
+
+    Ok(resp)
+}
+
+pub fn query_pairs_by_asset(
+    deps: Deps,
+    asset_info: AssetInfo,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    let pairs: Vec<PairInfo> = read_pairs_by_asset(
+        deps.storage,
+        deps.api,
+        &asset_info.to_raw(deps.api)?,
+        start_after,
+        limit,
+    )?
+    .into_iter()
+    .map(|pair_info| with_pair_details(deps, pair_info))
+    .collect::<StdResult<Vec<PairInfo>>>()?;
+
+    Ok(PairsResponse { pairs })
+}
+
+pub fn query_pair_by_liquidity_token(deps: Deps, liquidity_token: String) -> StdResult<PairInfo> {
+    // the native liquidity shares are indexed by their denom
+    let key = if is_token_factory_denom(&liquidity_token) {
+        liquidity_token.into_bytes()
+    } else {
+        deps.api.addr_canonicalize(&liquidity_token)?.to_vec()
+    };
+    let (_, pair_info) = pairs()
+        .idx
+        .liquidity_token
+        .item(deps.storage, key)?
+        .ok_or_else(|| StdError::not_found("PairInfoRaw"))?;
+
+    with_pair_details(deps, pair_info.to_normal(deps.api)?)
+}
+
+pub fn query_pair_by_contract(deps: Deps, contract_addr: String) -> StdResult<PairInfo> {
+    let contract_addr = deps.api.addr_canonicalize(&contract_addr)?;
+    let (_, pair_info) = pairs()
+        .idx
+        .contract_addr
+        .item(deps.storage, contract_addr.to_vec())?
+        .ok_or_else(|| StdError::not_found("PairInfoRaw"))?;
+
+    with_pair_details(deps, pair_info.to_normal(deps.api)?)
+}
+
+pub fn query_pairs_by_creator(
+    deps: Deps,
+    creator: String,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    let pairs: Vec<PairInfo> = read_pairs_by_creator(
+        deps.storage,
+        deps.api,
+        &deps.api.addr_canonicalize(&creator)?,
+        start_after,
+        limit,
+    )?
+    .into_iter()
+    .map(|pair_info| with_pair_details(deps, pair_info))
+    .collect::<StdResult<Vec<PairInfo>>>()?;
+
+    Ok(PairsResponse { pairs })
+}
+
+/// Fills the asset symbols and the incentive contract of a registered pair
+fn with_pair_details(deps: Deps, mut pair_info: PairInfo) -> StdResult<PairInfo> {
+    pair_info.asset_symbols = Some([
+        asset_symbol(deps.storage, &pair_info.asset_infos[0])?,
+        asset_symbol(deps.storage, &pair_info.asset_infos[1])?,
+    ]);
+
+    let pair_key = pair_key(&[
+        pair_info.asset_infos[0].to_raw(deps.api)?,
+        pair_info.asset_infos[1].to_raw(deps.api)?,
+    ]);
+    pair_info.incentive_contract = PAIR_INCENTIVES
+        .may_load(deps.storage, &pair_key)?
+        .map(|incentive_contract| deps.api.addr_humanize(&incentive_contract))
+        .transpose()?
+        .map(|incentive_contract| incentive_contract.to_string());
+
+    Ok(pair_info)
+}
+
+pub fn query_ibc_denom(deps: Deps, denom: String) -> StdResult<IbcDenomInfo> {
+    IBC_DENOMS.load(deps.storage, &ibc_denom_hash(&denom)?)
+}
+
+pub fn query_ibc_denoms(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<IbcDenomsResponse> {
+    let ibc_denoms = read_ibc_denoms(deps.storage, start_after, limit)?;
+
+    Ok(IbcDenomsResponse { ibc_denoms })
+}
+
+pub fn query_outdated_pairs(
+    deps: Deps,
+    code_id: Option<u64>,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<OutdatedPairsResponse> {
+    let code_id = match code_id {
+        Some(code_id) => code_id,
+        None => CONFIG.load(deps.storage)?.pair_code_id,
+    };
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    let (pairs, start_after) =
+        read_outdated_pairs(deps.storage, deps.api, code_id, start_after, limit)?;
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_normal(deps.api)?,
+            start_after[1].to_normal(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    Ok(OutdatedPairsResponse { pairs, start_after })
+}
+
+pub fn query_predict_pair_addresses(
+    deps: Deps,
+    env: Env,
+    asset_infos: [AssetInfo; 2],
+) -> StdResult<PredictedPairAddressesResponse> {
+    let (pair_contract, liquidity_token) = predict_pair_addresses(deps, &env, &asset_infos)?;
+
+    Ok(PredictedPairAddressesResponse {
+        pair_contract: deps.api.addr_humanize(&pair_contract)?.to_string(),
+        liquidity_token: deps.api.addr_humanize(&liquidity_token)?.to_string(),
+    })
+}
+
+/// Derive the instantiate2 addresses of the next pair of the asset infos and of its liquidity token
+pub fn predict_pair_addresses(
+    deps: Deps,
+    env: &Env,
+    asset_infos: &[AssetInfo; 2],
+) -> StdResult<(CanonicalAddr, CanonicalAddr)> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+
+    let pair_checksum = deps
+        .querier
+        .query_wasm_code_info(config.pair_code_id)?
+        .checksum;
+    let pair_contract = instantiate2_address(
+        pair_checksum.as_slice(),
+        &deps.api.addr_canonicalize(env.contract.address.as_str())?,
+        &pair_salt(deps.storage, &pair_key)?,
+    )
+    .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    let token_checksum = deps
+        .querier
+        .query_wasm_code_info(config.token_code_id)?
+        .checksum;
+    let liquidity_token = instantiate2_address(
+        token_checksum.as_slice(),
+        &pair_contract,
+        LIQUIDITY_TOKEN_SALT,
+    )
+    .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    Ok((pair_contract, liquidity_token))
+}
+
+pub fn query_deprecated_pairs(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let start_after = if let Some(start_after) = start_after {
+        Some(deps.api.addr_canonicalize(&start_after)?)
+    } else {
+        None
+    };
+
+    let pairs: Vec<PairInfo> =
+        read_deprecated_pairs(deps.storage, deps.api, &pair_key, start_after, limit)?;
     let resp = PairsResponse { pairs };
 
     Ok(resp)
@@ -319,14 +1366,41 @@ pub fn query_native_token_decimal(
     deps: Deps,
     denom: String,
 ) -> StdResult<NativeTokenDecimalsResponse> {
-    let decimals = ALLOW_NATIVE_TOKENS.load(deps.storage, denom.as_bytes())?;
+    let decimals = match query_metadata_decimals(deps, &denom) {
+        Some(decimals) => decimals,
+        None => ALLOW_NATIVE_TOKENS.load(deps.storage, denom.as_bytes())?,
+    };
 
     Ok(NativeTokenDecimalsResponse { decimals })
 }
 
+pub fn query_native_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<NativeTokensResponse> {
+    let native_tokens = read_native_tokens(deps.storage, start_after, limit)?;
+
+    Ok(NativeTokensResponse { native_tokens })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // the migration steps run in the order of the versions introducing them
+    if stored_version < Version::new(0, 2, 0) {
+        migrate_to_v0_2(deps.storage, msg.pair_code_id)?;
+    }
+    if stored_version < Version::new(0, 3, 0) {
+        migrate_to_v0_3(deps.storage)?;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    Ok(Response::default())
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
 }
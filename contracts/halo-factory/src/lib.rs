@@ -1,5 +1,7 @@
 pub mod contract;
 pub mod state;
 
+mod migration;
+
 #[cfg(test)]
 mod testing;
@@ -0,0 +1,41 @@
+use cosmwasm_std::{Order, StdResult, Storage};
+
+use crate::state::{pairs, PAIR_CODE_IDS};
+
+/// 0.2.0 tracks the code id of each pair,
+/// the registered pairs are recorded with the code id given by the migration
+pub fn migrate_to_v0_2(storage: &mut dyn Storage, pair_code_id: Option<u64>) -> StdResult<()> {
+    // the pair context is kept per reply id since 0.2.0
+    storage.remove(b"tmp_pair_info");
+
+    let pair_code_id = match pair_code_id {
+        Some(pair_code_id) => pair_code_id,
+        None => return Ok(()),
+    };
+
+    let contract_addrs = pairs()
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.1.contract_addr))
+        .collect::<StdResult<Vec<_>>>()?;
+    for contract_addr in contract_addrs {
+        if !PAIR_CODE_IDS.has(storage, contract_addr.as_slice()) {
+            PAIR_CODE_IDS.save(storage, contract_addr.as_slice(), &pair_code_id)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 0.3.0 indexes the pairs by their assets, liquidity token, contract and creator,
+/// the registered pairs are saved again to build the indexes
+pub fn migrate_to_v0_3(storage: &mut dyn Storage) -> StdResult<()> {
+    let pair_infos = pairs()
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (pair_key, pair_info) in pair_infos {
+        // the existing index entries are replaced, so the migration can be repeated
+        pairs().replace(storage, &pair_key, Some(&pair_info), Some(&pair_info))?;
+    }
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{Api, CanonicalAddr, Order, StdResult, Storage};
-use cw_storage_plus::{Bound, Item, Map};
-use haloswap::asset::{AssetInfoRaw, PairInfo, PairInfoRaw};
+use cosmwasm_std::{Api, CanonicalAddr, Order, StdError, StdResult, Storage};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
+use haloswap::asset::{
+    classify_denom, AssetInfo, AssetInfoRaw, AssetRaw, DenomKind, PairInfo, PairInfoRaw,
+};
+use haloswap::factory::{IbcDenomInfo, NativeToken, PairCreationConfig};
+use sha2::{Digest, Sha256};
 
 #[cw_serde]
 pub struct Config {
@@ -13,15 +17,121 @@ pub struct Config {
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+#[cw_serde]
+#[derive(Default)]
+pub struct PairCreationConfigRaw {
+    pub creation_fee: Option<AssetRaw>,
+    pub fee_collector: Option<CanonicalAddr>,
+    pub owner_only: bool,
+    pub allowed_assets: Option<Vec<AssetInfoRaw>>,
+    pub denied_assets: Vec<AssetInfoRaw>,
+}
+
+impl PairCreationConfigRaw {
+    pub fn to_normal(&self, api: &dyn Api) -> StdResult<PairCreationConfig> {
+        Ok(PairCreationConfig {
+            creation_fee: match &self.creation_fee {
+                Some(creation_fee) => Some(creation_fee.to_normal(api)?),
+                None => None,
+            },
+            fee_collector: match &self.fee_collector {
+                Some(fee_collector) => Some(api.addr_humanize(fee_collector)?.to_string()),
+                None => None,
+            },
+            owner_only: self.owner_only,
+            allowed_assets: match &self.allowed_assets {
+                Some(allowed_assets) => Some(
+                    allowed_assets
+                        .iter()
+                        .map(|asset_info| asset_info.to_normal(api))
+                        .collect::<StdResult<Vec<AssetInfo>>>()?,
+                ),
+                None => None,
+            },
+            denied_assets: self
+                .denied_assets
+                .iter()
+                .map(|asset_info| asset_info.to_normal(api))
+                .collect::<StdResult<Vec<AssetInfo>>>()?,
+        })
+    }
+}
+
+// pair creation is unrestricted until the owner sets it
+pub const PAIR_CREATION: Item<PairCreationConfigRaw> = Item::new("pair_creation");
+
 #[cw_serde]
 pub struct TmpPairInfo {
     pub pair_key: Vec<u8>,
     pub asset_infos: [AssetInfoRaw; 2],
     pub asset_decimals: [u8; 2],
+    pub creator: CanonicalAddr,
 }
 
-pub const TMP_PAIR_INFO: Item<TmpPairInfo> = Item::new("tmp_pair_info");
-pub const PAIRS: Map<&[u8], PairInfoRaw> = Map::new("pair_info");
+// key : reply id of the pair instantiation / value: pair context until the pair is registered
+pub const TMP_PAIR_INFOS: Map<u64, TmpPairInfo> = Map::new("tmp_pair_infos");
+pub const NEXT_REPLY_ID: Item<u64> = Item::new("next_reply_id");
+
+pub struct PairIndexes<'a> {
+    pub first_asset: MultiIndex<'a, Vec<u8>, PairInfoRaw, &'a [u8]>,
+    pub second_asset: MultiIndex<'a, Vec<u8>, PairInfoRaw, &'a [u8]>,
+    pub liquidity_token: UniqueIndex<'a, Vec<u8>, PairInfoRaw, &'a [u8]>,
+    pub contract_addr: UniqueIndex<'a, Vec<u8>, PairInfoRaw, &'a [u8]>,
+    /// the pairs not created through the factory are indexed by an empty creator
+    pub creator: MultiIndex<'a, Vec<u8>, PairInfoRaw, &'a [u8]>,
+}
+
+impl<'a> IndexList<PairInfoRaw> for PairIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PairInfoRaw>> + '_> {
+        let v: Vec<&dyn Index<PairInfoRaw>> = vec![
+            &self.first_asset,
+            &self.second_asset,
+            &self.liquidity_token,
+            &self.contract_addr,
+            &self.creator,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+// key : pair key / value: pair info of the registered pair
+pub fn pairs<'a>() -> IndexedMap<'a, &'a [u8], PairInfoRaw, PairIndexes<'a>> {
+    let indexes = PairIndexes {
+        first_asset: MultiIndex::new(
+            |_, v| v.asset_infos[0].as_bytes().to_vec(),
+            "pair_info",
+            "pair_info__first_asset",
+        ),
+        second_asset: MultiIndex::new(
+            |_, v| v.asset_infos[1].as_bytes().to_vec(),
+            "pair_info",
+            "pair_info__second_asset",
+        ),
+        liquidity_token: UniqueIndex::new(
+            |v| v.liquidity_token_key(),
+            "pair_info__liquidity_token",
+        ),
+        contract_addr: UniqueIndex::new(|v| v.contract_addr.to_vec(), "pair_info__contract_addr"),
+        creator: MultiIndex::new(
+            |_, v| v.creator.as_ref().map(|c| c.to_vec()).unwrap_or_default(),
+            "pair_info",
+            "pair_info__creator",
+        ),
+    };
+
+    IndexedMap::new("pair_info", indexes)
+}
+
+// key : pair contract / value: code id the pair was instantiated with or last migrated to
+pub const PAIR_CODE_IDS: Map<&[u8], u64> = Map::new("pair_code_id");
+// key : (pair key, pair contract) / value: pair info of a deregistered pair
+pub const DEPRECATED_PAIRS: Map<(&[u8], &[u8]), PairInfoRaw> = Map::new("deprecated_pair_info");
+
+// key : pair key / value: staking contract rewarding the liquidity providers of the pair
+pub const PAIR_INCENTIVES: Map<&[u8], CanonicalAddr> = Map::new("pair_incentive");
+
+// key : hash of the ibc denom / value: symbol and channel of the denom
+pub const IBC_DENOMS: Map<&str, IbcDenomInfo> = Map::new("ibc_denoms");
 
 pub fn pair_key(asset_infos: &[AssetInfoRaw; 2]) -> Vec<u8> {
     let mut asset_infos = asset_infos.to_vec();
@@ -30,6 +140,26 @@ pub fn pair_key(asset_infos: &[AssetInfoRaw; 2]) -> Vec<u8> {
     [asset_infos[0].as_bytes(), asset_infos[1].as_bytes()].concat()
 }
 
+/// Allocate a unique reply id for a pair instantiation
+pub fn next_reply_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let reply_id = NEXT_REPLY_ID.may_load(storage)?.unwrap_or_default() + 1;
+    NEXT_REPLY_ID.save(storage, &reply_id)?;
+
+    Ok(reply_id)
+}
+
+/// Check if a pair of the pair key is instantiated in the current transaction
+pub fn is_pending_pair(storage: &dyn Storage, pair_key: &[u8]) -> StdResult<bool> {
+    for item in TMP_PAIR_INFOS.range(storage, None, None, Order::Ascending) {
+        let (_, tmp_pair_info) = item?;
+        if tmp_pair_info.pair_key == pair_key {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
 // settings for pagination
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
@@ -42,7 +172,65 @@ pub fn read_pairs(
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = calc_range_start(start_after).map(Bound::ExclusiveRaw);
 
-    PAIRS
+    pairs()
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            v.to_normal(api)
+        })
+        .collect::<StdResult<Vec<PairInfo>>>()
+}
+
+/// Read the pairs trading the asset, ordered by the pair key
+pub fn read_pairs_by_asset(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    asset_info: &AssetInfoRaw,
+    start_after: Option<[AssetInfoRaw; 2]>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|asset_infos| pair_key(&asset_infos));
+
+    // the asset is either the first or the second asset of a pair,
+    // both indexes are merged in the order of the pair key
+    let indexes = pairs().idx;
+    let mut pair_infos: Vec<(Vec<u8>, PairInfoRaw)> = vec![];
+    for index in [&indexes.first_asset, &indexes.second_asset] {
+        let start = start_after.clone().map(Bound::ExclusiveRaw);
+        for item in index
+            .prefix(asset_info.as_bytes().to_vec())
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+        {
+            pair_infos.push(item?);
+        }
+    }
+    pair_infos.sort_by(|a, b| a.0.cmp(&b.0));
+
+    pair_infos
+        .into_iter()
+        .take(limit)
+        .map(|(_, v)| v.to_normal(api))
+        .collect::<StdResult<Vec<PairInfo>>>()
+}
+
+/// Read the pairs created by the creator, ordered by the pair key
+pub fn read_pairs_by_creator(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    creator: &CanonicalAddr,
+    start_after: Option<[AssetInfoRaw; 2]>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|asset_infos| Bound::ExclusiveRaw(pair_key(&asset_infos)));
+
+    pairs()
+        .idx
+        .creator
+        .prefix(creator.to_vec())
         .range(storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| {
@@ -52,6 +240,91 @@ pub fn read_pairs(
         .collect::<StdResult<Vec<PairInfo>>>()
 }
 
+/// Read the pairs which are not known to run `code_id` among the `limit` pairs after `start_after`,
+/// the asset infos of the last visited pair are returned while pairs remain to be visited
+pub fn read_outdated_pairs(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    code_id: u64,
+    start_after: Option<[AssetInfoRaw; 2]>,
+    limit: Option<u32>,
+) -> StdResult<(Vec<PairInfo>, Option<[AssetInfoRaw; 2]>)> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after).map(Bound::ExclusiveRaw);
+
+    let visited = pairs()
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, v)| v))
+        .collect::<StdResult<Vec<PairInfoRaw>>>()?;
+
+    let mut outdated_pairs: Vec<PairInfo> = vec![];
+    for v in visited.iter() {
+        if PAIR_CODE_IDS.may_load(storage, v.contract_addr.as_slice())? != Some(code_id) {
+            outdated_pairs.push(v.to_normal(api)?);
+        }
+    }
+
+    let next_start_after = if visited.len() == limit {
+        visited.last().map(|v| v.asset_infos.clone())
+    } else {
+        None
+    };
+
+    Ok((outdated_pairs, next_start_after))
+}
+
+/// Move the registered pair of the pair key to the deprecated pairs
+pub fn deprecate_pair(storage: &mut dyn Storage, pair_key: &[u8]) -> StdResult<PairInfoRaw> {
+    let pair_info = pairs().load(storage, pair_key)?;
+    pairs().remove(storage, pair_key)?;
+    // the incentive contract of the deprecated pair must not carry over to a replacement
+    PAIR_INCENTIVES.remove(storage, pair_key);
+    DEPRECATED_PAIRS.save(
+        storage,
+        (pair_key, pair_info.contract_addr.as_slice()),
+        &pair_info,
+    )?;
+
+    Ok(pair_info)
+}
+
+pub fn read_deprecated_pairs(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    pair_key: &[u8],
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|addr| Bound::ExclusiveRaw(addr.to_vec()));
+
+    DEPRECATED_PAIRS
+        .prefix(pair_key)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            v.to_normal(api)
+        })
+        .collect::<StdResult<Vec<PairInfo>>>()
+}
+
+/// The instantiate2 salt of the pair of the pair key,
+/// the deprecated pairs count keeps the salt unique when a deregistered pair is created again
+pub fn pair_salt(storage: &dyn Storage, pair_key: &[u8]) -> StdResult<Vec<u8>> {
+    let generation = DEPRECATED_PAIRS
+        .prefix(pair_key)
+        .keys_raw(storage, None, None, Order::Ascending)
+        .count() as u64;
+
+    let mut hasher = Sha256::new();
+    hasher.update(pair_key);
+    hasher.update(generation.to_be_bytes());
+
+    Ok(hasher.finalize().to_vec())
+}
+
 // this will set the first key after the provided key, by appending a 1 byte
 fn calc_range_start(start_after: Option<[AssetInfoRaw; 2]>) -> Option<Vec<u8>> {
     start_after.map(|asset_infos| {
@@ -77,6 +350,63 @@ pub fn add_allow_native_token(
     ALLOW_NATIVE_TOKENS.save(storage, denom.as_bytes(), &decimals)
 }
 
+pub fn remove_allow_native_token(storage: &mut dyn Storage, denom: String) {
+    ALLOW_NATIVE_TOKENS.remove(storage, denom.as_bytes())
+}
+
+pub fn read_native_tokens(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<NativeToken>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|denom| Bound::ExclusiveRaw(denom.into_bytes()));
+
+    ALLOW_NATIVE_TOKENS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (denom, decimals) = item?;
+            Ok(NativeToken {
+                denom: String::from_utf8(denom)?,
+                decimals,
+            })
+        })
+        .collect::<StdResult<Vec<NativeToken>>>()
+}
+
+pub fn read_ibc_denoms(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<IbcDenomInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = match start_after.as_deref().map(classify_denom) {
+        Some(DenomKind::Ibc { hash }) => Some(Bound::ExclusiveRaw(hash.into_bytes())),
+        Some(_) => return Err(StdError::generic_err("start_after must be an ibc denom")),
+        None => None,
+    };
+
+    IBC_DENOMS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect::<StdResult<Vec<IbcDenomInfo>>>()
+}
+
+/// Resolve the symbol of an asset, ibc denoms are shown by their registered symbol
+/// and token factory denoms by their subdenom
+pub fn asset_symbol(storage: &dyn Storage, asset_info: &AssetInfo) -> StdResult<String> {
+    match asset_info.denom_kind() {
+        Some(DenomKind::Ibc { hash }) => Ok(match IBC_DENOMS.may_load(storage, &hash)? {
+            Some(ibc_denom) => ibc_denom.symbol,
+            None => asset_info.to_string(),
+        }),
+        Some(DenomKind::TokenFactory { subdenom, .. }) => Ok(subdenom),
+        _ => Ok(asset_info.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod allow_native_token {
 
@@ -1,20 +1,31 @@
-use crate::contract::{execute, instantiate, query, reply};
+use crate::contract::{execute, instantiate, migrate, predict_pair_addresses, query, reply};
 use haloswap::mock_querier::{mock_dependencies, WasmMockQuerier};
 
-use crate::state::{pair_key, TmpPairInfo, TMP_PAIR_INFO};
+use crate::state::{
+    pair_key, pair_salt, pairs, TmpPairInfo, PAIR_CODE_IDS, PAIR_INCENTIVES, TMP_PAIR_INFOS,
+};
 
 use cosmwasm_std::testing::{
     mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR,
 };
 use cosmwasm_std::{
-    attr, coin, from_binary, to_binary, Addr, CosmosMsg, OwnedDeps, Reply, ReplyOn, Response,
+    attr, coin, from_json, instantiate2_address, to_json_binary, Addr, Api, BankMsg, Binary,
+    CosmosMsg, Decimal, DenomMetadata, DenomUnit, Deps, OwnedDeps, Reply, ReplyOn, Response,
     StdError, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
-use haloswap::asset::{AssetInfo, CreatePairRequirements, PairInfo};
+use cw2::set_contract_version;
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Map;
+use haloswap::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo, PairInfoRaw};
 use haloswap::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, NativeTokenDecimalsResponse, QueryMsg,
+    ConfigResponse, CreatePairRequest, ExecuteMsg, IbcDenomInfo, IbcDenomsResponse, InstantiateMsg,
+    MigrateMsg, NativeToken, NativeTokenDecimalsResponse, NativeTokensResponse,
+    OutdatedPairsResponse, PairCreationConfig, PairsResponse, QueryMsg,
+};
+use haloswap::pair::{
+    ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg,
+    MigrateMsg as PairMigrateMsg, PauseFlags, LIQUIDITY_TOKEN_SALT,
 };
-use haloswap::pair::{InstantiateMsg as PairInstantiateMsg, MigrateMsg as PairMigrateMsg};
 
 #[test]
 fn proper_initialization() {
@@ -31,10 +42,11 @@ fn proper_initialization() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    let config_res: ConfigResponse = from_json(&query_res).unwrap();
     assert_eq!(123u64, config_res.token_code_id);
     assert_eq!(321u64, config_res.pair_code_id);
     assert_eq!("addr0000".to_string(), config_res.owner);
+    assert_eq!(PairCreationConfig::default(), config_res.pair_creation);
 }
 
 #[test]
@@ -64,7 +76,7 @@ fn update_config() {
 
     // it worked, let's query the state
     let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    let config_res: ConfigResponse = from_json(&query_res).unwrap();
     assert_eq!(123u64, config_res.token_code_id);
     assert_eq!(321u64, config_res.pair_code_id);
     assert_eq!("addr0001".to_string(), config_res.owner);
@@ -83,7 +95,7 @@ fn update_config() {
 
     // it worked, let's query the state
     let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    let config_res: ConfigResponse = from_json(&query_res).unwrap();
     assert_eq!(200u64, config_res.token_code_id);
     assert_eq!(100u64, config_res.pair_code_id);
     assert_eq!("addr0001".to_string(), config_res.owner);
@@ -147,8 +159,14 @@ fn create_pair() {
             first_asset_minimum: Uint128::zero(),
             second_asset_minimum: Uint128::zero(),
         },
+        liquidity_token_kind: None,
     };
 
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
@@ -165,8 +183,8 @@ fn create_pair() {
             id: 1,
             gas_limit: None,
             reply_on: ReplyOn::Success,
-            msg: WasmMsg::Instantiate {
-                msg: to_binary(&PairInstantiateMsg {
+            msg: WasmMsg::Instantiate2 {
+                msg: to_json_binary(&PairInstantiateMsg {
                     asset_infos: asset_infos.clone(),
                     token_code_id: 123u64,
                     asset_decimals: [6u8, 8u8],
@@ -175,32 +193,267 @@ fn create_pair() {
                         first_asset_minimum: Uint128::zero(),
                         second_asset_minimum: Uint128::zero(),
                     },
+                    liquidity_token_kind: None,
                 })
                 .unwrap(),
                 code_id: 321u64,
                 funds: vec![],
                 label: "pair".to_string(),
                 admin: Some(MOCK_CONTRACT_ADDR.to_string()),
+                salt: Binary::from(pair_salt(&deps.storage, &pair_key(&raw_infos)).unwrap()),
             }
             .into()
         },]
     );
 
-    let raw_infos = [
-        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
-        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
-    ];
-
     assert_eq!(
-        TMP_PAIR_INFO.load(&deps.storage).unwrap(),
+        TMP_PAIR_INFOS.load(&deps.storage, 1u64).unwrap(),
         TmpPairInfo {
             asset_infos: raw_infos.clone(),
             pair_key: pair_key(&raw_infos),
-            asset_decimals: [6u8, 8u8]
+            asset_decimals: [6u8, 8u8],
+            creator: deps.as_ref().api.addr_canonicalize("addr0000").unwrap(),
         }
     );
 }
 
+#[test]
+fn create_pair_with_creation_config() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_halo_factory(&[], &[("uusd".to_string(), 6u8)]);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0001".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::zero())],
+        ),
+        (
+            &"asset0002".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::zero())],
+        ),
+    ]);
+
+    let create_pair_msg = |token: &str| ExecuteMsg::CreatePair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+        ],
+        requirements: CreatePairRequirements {
+            whitelist: vec![],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+        liquidity_token_kind: None,
+    };
+
+    let mut pair_creation = PairCreationConfig {
+        creation_fee: Some(Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        }),
+        fee_collector: None,
+        owner_only: false,
+        allowed_assets: None,
+        denied_assets: vec![AssetInfo::Token {
+            contract_addr: "asset0002".to_string(),
+        }],
+    };
+
+    // only the owner can update the creation config
+    let msg = ExecuteMsg::UpdatePairCreationConfig {
+        pair_creation: pair_creation.clone(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0001", &[]), msg);
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // a fee requires a fee collector
+    let msg = ExecuteMsg::UpdatePairCreationConfig {
+        pair_creation: pair_creation.clone(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "a fee collector is required to charge a creation fee"
+        ))
+    );
+
+    pair_creation.fee_collector = Some("treasury".to_string());
+    let msg = ExecuteMsg::UpdatePairCreationConfig {
+        pair_creation: pair_creation.clone(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_json(&query_res).unwrap();
+    assert_eq!(pair_creation, config_res.pair_creation);
+
+    // denied assets cannot be paired
+    let info = mock_info("addr0001", &[coin(100u128, "uusd")]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0002"),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err("asset asset0002 is not allowed"))
+    );
+
+    // the creation fee must be paid
+    let info = mock_info("addr0001", &[coin(99u128, "uusd")]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0001"),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "a pair creation fee of 100uusd is required"
+        ))
+    );
+
+    // unrelated coins are rejected instead of being kept by the factory
+    let info = mock_info("addr0001", &[coin(100u128, "uusd"), coin(1u128, "uluna")]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0001"),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err("Sent more than one denomination"))
+    );
+
+    // the native fee is forwarded to the fee collector before the pair is instantiated
+    let info = mock_info("addr0001", &[coin(100u128, "uusd")]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0001"),
+    )
+    .unwrap();
+    // the pair context is removed by the reply of the instantiation
+    TMP_PAIR_INFOS.clear(&mut deps.storage);
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![coin(100u128, "uusd")],
+        })
+    );
+
+    // a cw20 fee is transferred from the allowance of the creator
+    pair_creation.creation_fee = Some(Asset {
+        info: AssetInfo::Token {
+            contract_addr: "asset0002".to_string(),
+        },
+        amount: Uint128::from(5u128),
+    });
+    pair_creation.denied_assets = vec![];
+    let msg = ExecuteMsg::UpdatePairCreationConfig {
+        pair_creation: pair_creation.clone(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // no native coin is accepted with a cw20 fee
+    let info = mock_info("addr0001", &[coin(100u128, "uusd")]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0001"),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err("This message does no accept funds"))
+    );
+
+    let info = mock_info("addr0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0001"),
+    )
+    .unwrap();
+    // the pair context is removed by the reply of the instantiation
+    TMP_PAIR_INFOS.clear(&mut deps.storage);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: "asset0002".to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "addr0001".to_string(),
+                recipient: "treasury".to_string(),
+                amount: Uint128::from(5u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // assets outside of the allowlist cannot be paired
+    pair_creation.creation_fee = None;
+    pair_creation.allowed_assets = Some(vec![AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    }]);
+    let msg = ExecuteMsg::UpdatePairCreationConfig {
+        pair_creation: pair_creation.clone(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let info = mock_info("addr0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0001"),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err("asset asset0001 is not allowed"))
+    );
+
+    // the pair creation can be restricted to the owner
+    pair_creation.allowed_assets = None;
+    pair_creation.owner_only = true;
+    let msg = ExecuteMsg::UpdatePairCreationConfig { pair_creation };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let info = mock_info("addr0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0001"),
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_pair_msg("asset0001"),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
+
 #[test]
 fn create_pair_native_token_and_ibc_token() {
     let mut deps = mock_dependencies(&[
@@ -229,8 +482,14 @@ fn create_pair_native_token_and_ibc_token() {
             first_asset_minimum: Uint128::zero(),
             second_asset_minimum: Uint128::zero(),
         },
+        liquidity_token_kind: None,
     };
 
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
@@ -244,8 +503,8 @@ fn create_pair_native_token_and_ibc_token() {
             id: 1,
             gas_limit: None,
             reply_on: ReplyOn::Success,
-            msg: WasmMsg::Instantiate {
-                msg: to_binary(&PairInstantiateMsg {
+            msg: WasmMsg::Instantiate2 {
+                msg: to_json_binary(&PairInstantiateMsg {
                     asset_infos: asset_infos.clone(),
                     token_code_id: 123u64,
                     asset_decimals: [6u8, 6u8],
@@ -254,28 +513,26 @@ fn create_pair_native_token_and_ibc_token() {
                         first_asset_minimum: Uint128::zero(),
                         second_asset_minimum: Uint128::zero(),
                     },
+                    liquidity_token_kind: None,
                 })
                 .unwrap(),
                 code_id: 321u64,
                 funds: vec![],
                 label: "pair".to_string(),
                 admin: Some(MOCK_CONTRACT_ADDR.to_string()),
+                salt: Binary::from(pair_salt(&deps.storage, &pair_key(&raw_infos)).unwrap()),
             }
             .into()
         },]
     );
 
-    let raw_infos = [
-        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
-        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
-    ];
-
     assert_eq!(
-        TMP_PAIR_INFO.load(&deps.storage).unwrap(),
+        TMP_PAIR_INFOS.load(&deps.storage, 1u64).unwrap(),
         TmpPairInfo {
             asset_infos: raw_infos.clone(),
             pair_key: pair_key(&raw_infos),
-            asset_decimals: [6u8, 6u8]
+            asset_decimals: [6u8, 6u8],
+            creator: deps.as_ref().api.addr_canonicalize("addr0000").unwrap(),
         }
     );
 }
@@ -303,6 +560,7 @@ fn fail_to_create_same_pair() {
     let msg = ExecuteMsg::CreatePair {
         asset_infos,
         requirements,
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -333,6 +591,7 @@ fn fail_to_create_pair_with_unactive_denoms() {
     let msg = ExecuteMsg::CreatePair {
         asset_infos,
         requirements,
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -363,6 +622,7 @@ fn fail_to_create_pair_with_invalid_denom() {
     let msg = ExecuteMsg::CreatePair {
         asset_infos,
         requirements,
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -403,6 +663,7 @@ fn fail_to_create_pair_with_unknown_token() {
     let msg = ExecuteMsg::CreatePair {
         asset_infos,
         requirements,
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -443,6 +704,7 @@ fn fail_to_create_pair_with_unknown_ibc_token() {
     let msg = ExecuteMsg::CreatePair {
         asset_infos,
         requirements,
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -453,6 +715,7 @@ fn fail_to_create_pair_with_unknown_ibc_token() {
 #[test]
 fn reply_test() {
     let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
 
     deps.querier.with_token_balances(&[(
         &MOCK_CONTRACT_ADDR.to_string(),
@@ -477,13 +740,15 @@ fn reply_test() {
     ];
 
     let pair_key = pair_key(&raw_infos);
-    TMP_PAIR_INFO
+    TMP_PAIR_INFOS
         .save(
             &mut deps.storage,
+            1u64,
             &TmpPairInfo {
                 asset_infos: raw_infos,
                 pair_key,
                 asset_decimals: [8u8, 8u8],
+                creator: deps.api.addr_canonicalize("addr0000").unwrap(),
             },
         )
         .unwrap();
@@ -512,6 +777,8 @@ fn reply_test() {
                 contract_addr: "0000".to_string(),
                 liquidity_token: "liquidity0000".to_string(),
                 asset_decimals: [8u8, 8u8],
+                asset_symbols: None,
+                incentive_contract: None,
                 requirements: CreatePairRequirements {
                     whitelist: vec![Addr::unchecked("deployer")],
                     first_asset_minimum: Uint128::zero(),
@@ -522,7 +789,14 @@ fn reply_test() {
         &[],
     );
 
-    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg.clone()).unwrap();
+    assert_eq!(TMP_PAIR_INFOS.may_load(&deps.storage, 1u64).unwrap(), None);
+
+    // the pair context is consumed by the reply
+    assert_eq!(
+        reply(deps.as_mut(), mock_env(), reply_msg),
+        Err(StdError::generic_err("unknown reply id: 1"))
+    );
 
     let query_res = query(
         deps.as_ref(),
@@ -533,7 +807,7 @@ fn reply_test() {
     )
     .unwrap();
 
-    let pair_res: PairInfo = from_binary(&query_res).unwrap();
+    let pair_res: PairInfo = from_json(&query_res).unwrap();
     assert_eq!(
         pair_res,
         PairInfo {
@@ -541,6 +815,8 @@ fn reply_test() {
             contract_addr: "0000".to_string(),
             asset_infos,
             asset_decimals: [8u8, 8u8],
+            asset_symbols: Some(["asset0000".to_string(), "asset0001".to_string()]),
+            incentive_contract: None,
             requirements: CreatePairRequirements {
                 whitelist: vec![Addr::unchecked("deployer")],
                 first_asset_minimum: Uint128::zero(),
@@ -550,6 +826,265 @@ fn reply_test() {
     );
 }
 
+#[test]
+fn reply_native_liquidity_token() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let mut pairs: Vec<(String, PairInfo)> = vec![];
+    for (i, pair_contract) in ["0000", "0001"].into_iter().enumerate() {
+        let asset_infos = [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: format!("asset000{}", i),
+            },
+        ];
+        let raw_infos = [
+            asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+            asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+        ];
+        TMP_PAIR_INFOS
+            .save(
+                &mut deps.storage,
+                i as u64 + 1,
+                &TmpPairInfo {
+                    pair_key: pair_key(&raw_infos),
+                    asset_infos: raw_infos,
+                    asset_decimals: [6u8, 8u8],
+                    creator: deps.api.addr_canonicalize("addr0000").unwrap(),
+                },
+            )
+            .unwrap();
+
+        pairs.push((
+            pair_contract.to_string(),
+            PairInfo {
+                asset_infos,
+                contract_addr: pair_contract.to_string(),
+                liquidity_token: format!("factory/{}/uLP", pair_contract),
+                asset_decimals: [6u8, 8u8],
+                asset_symbols: None,
+                incentive_contract: None,
+                requirements: CreatePairRequirements {
+                    whitelist: vec![Addr::unchecked("deployer")],
+                    first_asset_minimum: Uint128::zero(),
+                    second_asset_minimum: Uint128::zero(),
+                },
+            },
+        ));
+    }
+    deps.querier.with_halo_factory(
+        &pairs
+            .iter()
+            .map(|(key, pair_info)| (key, pair_info))
+            .collect::<Vec<(&String, &PairInfo)>>(),
+        &[],
+    );
+
+    // the pairs minting native shares are registered with the denoms of their shares
+    for (i, pair_contract) in ["0000", "0001"].into_iter().enumerate() {
+        let reply_msg = Reply {
+            id: i as u64 + 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some([&[10u8, 4][..], pair_contract.as_bytes()].concat().into()),
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    }
+
+    let res: PairInfo = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByLiquidityToken {
+                liquidity_token: "factory/0001/uLP".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.contract_addr, "0001");
+    assert_eq!(res.liquidity_token, "factory/0001/uLP");
+}
+
+#[test]
+fn create_pairs() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_halo_factory(&[], &[("uusd".to_string(), 6u8)]);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0001".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::zero())],
+        ),
+        (
+            &"asset0002".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::zero())],
+        ),
+    ]);
+
+    let create_pair_request = |token: &str| CreatePairRequest {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+        ],
+        requirements: CreatePairRequirements {
+            whitelist: vec![],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+        liquidity_token_kind: None,
+    };
+
+    let msg = ExecuteMsg::UpdatePairCreationConfig {
+        pair_creation: PairCreationConfig {
+            creation_fee: Some(Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            }),
+            fee_collector: Some("treasury".to_string()),
+            ..PairCreationConfig::default()
+        },
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // the creation fee is charged for each pair
+    let msg = ExecuteMsg::CreatePairs {
+        pairs: vec![
+            create_pair_request("asset0001"),
+            create_pair_request("asset0002"),
+        ],
+    };
+    let info = mock_info("addr0001", &[coin(100u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "a pair creation fee of 200uusd is required"
+        ))
+    );
+
+    let info = mock_info("addr0001", &[coin(200u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "create_pairs"),
+            attr("pair", "uusd-asset0001"),
+            attr("pair", "uusd-asset0002"),
+        ]
+    );
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![coin(200u128, "uusd")],
+        })
+    );
+
+    // each pair is instantiated with its own reply id
+    assert_eq!(
+        res.messages[1..]
+            .iter()
+            .map(|msg| msg.id)
+            .collect::<Vec<u64>>(),
+        vec![1u64, 2u64]
+    );
+    for (reply_id, token) in [(1u64, "asset0001"), (2u64, "asset0002")] {
+        let raw_infos = [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+            AssetInfo::Token {
+                contract_addr: token.to_string(),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+        ];
+        assert_eq!(
+            TMP_PAIR_INFOS.load(&deps.storage, reply_id).unwrap(),
+            TmpPairInfo {
+                pair_key: pair_key(&raw_infos),
+                asset_infos: raw_infos,
+                asset_decimals: [6u8, 8u8],
+                creator: deps.as_ref().api.addr_canonicalize("addr0001").unwrap(),
+            }
+        );
+    }
+
+    // the same pair cannot be created twice in a batch
+    TMP_PAIR_INFOS.clear(&mut deps.storage);
+    let msg = ExecuteMsg::CreatePairs {
+        pairs: vec![
+            create_pair_request("asset0001"),
+            create_pair_request("asset0001"),
+        ],
+    };
+    let info = mock_info("addr0001", &[coin(200u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(
+        res,
+        Err(StdError::generic_err("Pair is already being created"))
+    );
+}
+
+#[test]
+fn reply_with_invalid_data() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let raw_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        }
+        .to_raw(deps.as_ref().api)
+        .unwrap(),
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        }
+        .to_raw(deps.as_ref().api)
+        .unwrap(),
+    ];
+    TMP_PAIR_INFOS
+        .save(
+            &mut deps.storage,
+            1u64,
+            &TmpPairInfo {
+                pair_key: pair_key(&raw_infos),
+                asset_infos: raw_infos,
+                asset_decimals: [8u8, 8u8],
+                creator: deps.api.addr_canonicalize("addr0000").unwrap(),
+            },
+        )
+        .unwrap();
+
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    match reply(deps.as_mut(), mock_env(), reply_msg) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert!(msg.starts_with("invalid pair instantiation reply"))
+        }
+        _ => panic!("Must return an invalid reply error"),
+    }
+}
+
 #[test]
 fn normal_add_allow_native_token() {
     let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
@@ -557,7 +1092,7 @@ fn normal_add_allow_native_token() {
 
     let msg = ExecuteMsg::AddNativeTokenDecimals {
         denom: "uluna".to_string(),
-        decimals: 6u8,
+        decimals: Some(6u8),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -568,6 +1103,7 @@ fn normal_add_allow_native_token() {
             ("action", "add_allow_native_token"),
             ("denom", "uluna"),
             ("decimals", "6"),
+            ("source", "registry"),
         ])
     );
 
@@ -579,7 +1115,7 @@ fn normal_add_allow_native_token() {
         },
     )
     .unwrap();
-    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
+    let res: NativeTokenDecimalsResponse = from_json(&res).unwrap();
     assert_eq!(6u8, res.decimals)
 }
 
@@ -590,7 +1126,7 @@ fn failed_add_allow_native_token_with_non_admin() {
 
     let msg = ExecuteMsg::AddNativeTokenDecimals {
         denom: "uluna".to_string(),
-        decimals: 6u8,
+        decimals: Some(6u8),
     };
 
     let info = mock_info("noadmin", &[]);
@@ -608,7 +1144,7 @@ fn failed_add_allow_native_token_with_zero_factory_balance() {
 
     let msg = ExecuteMsg::AddNativeTokenDecimals {
         denom: "uluna".to_string(),
-        decimals: 6u8,
+        decimals: Some(6u8),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -629,7 +1165,7 @@ fn append_add_allow_native_token_with_already_exist_token() {
     let msg = ExecuteMsg::AddNativeTokenDecimals {
         denom: "uluna".to_string(),
 
-        decimals: 6u8,
+        decimals: Some(6u8),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -644,12 +1180,12 @@ fn append_add_allow_native_token_with_already_exist_token() {
         },
     )
     .unwrap();
-    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
+    let res: NativeTokenDecimalsResponse = from_json(&res).unwrap();
     assert_eq!(6u8, res.decimals);
 
     let msg = ExecuteMsg::AddNativeTokenDecimals {
         denom: "uluna".to_string(),
-        decimals: 7u8,
+        decimals: Some(7u8),
     };
 
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -662,40 +1198,372 @@ fn append_add_allow_native_token_with_already_exist_token() {
         },
     )
     .unwrap();
-    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
+    let res: NativeTokenDecimalsResponse = from_json(&res).unwrap();
     assert_eq!(7u8, res.decimals)
 }
 
 #[test]
-fn normal_migrate_pair() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+fn add_native_token_with_denom_metadata() {
+    let mut deps = mock_dependencies(&[]);
     deps = init(deps);
-
-    let msg = ExecuteMsg::MigratePair {
-        code_id: Some(123u64),
-        contract: "contract0000".to_string(),
-    };
+    deps.querier.with_denom_metadata(&[DenomMetadata {
+        description: "".to_string(),
+        denom_units: vec![
+            DenomUnit {
+                denom: "uatom".to_string(),
+                exponent: 0,
+                aliases: vec![],
+            },
+            DenomUnit {
+                denom: "atom".to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+        ],
+        base: "uatom".to_string(),
+        display: "atom".to_string(),
+        name: "Atom".to_string(),
+        symbol: "ATOM".to_string(),
+        uri: "".to_string(),
+        uri_hash: "".to_string(),
+    }]);
+
+    // the metadata decimals are used without a registration
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokenDecimals {
+            denom: "uatom".to_string(),
+        },
+    )
+    .unwrap();
+    let res: NativeTokenDecimalsResponse = from_json(&res).unwrap();
+    assert_eq!(6u8, res.decimals);
 
     let info = mock_info("addr0000", &[]);
 
+    // decimals must match the metadata
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uatom".to_string(),
+        decimals: Some(8u8),
+    };
     assert_eq!(
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap(),
-        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
-            contract_addr: "contract0000".to_string(),
-            new_code_id: 123u64,
-            msg: to_binary(&PairMigrateMsg {}).unwrap(),
-        })),
+        execute(deps.as_mut(), mock_env(), info.clone(), msg),
+        Err(StdError::generic_err(
+            "decimals mismatch the denom metadata: 6"
+        ))
     );
-}
-
-#[test]
-fn normal_migrate_pair_with_none_code_id_will_config_code_id() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
-    deps = init(deps);
+
+    // no balance is required for a denom with metadata
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uatom".to_string(),
+        decimals: None,
+    };
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap(),
+        Response::new().add_attributes(vec![
+            ("action", "add_allow_native_token"),
+            ("denom", "uatom"),
+            ("decimals", "6"),
+            ("source", "metadata"),
+        ])
+    );
+
+    // decimals are required without metadata
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uluna".to_string(),
+        decimals: None,
+    };
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg),
+        Err(StdError::generic_err(
+            "decimals are required for a denom without metadata"
+        ))
+    );
+}
+
+#[test]
+fn remove_and_list_native_tokens() {
+    let mut deps = mock_dependencies(&[
+        coin(1u128, "uluna".to_string()),
+        coin(1u128, "uusd".to_string()),
+        coin(1u128, "ukrw".to_string()),
+    ]);
+    deps = init(deps);
+
+    let info = mock_info("addr0000", &[]);
+    for (denom, decimals) in [("uluna", 6u8), ("uusd", 6u8), ("ukrw", 8u8)] {
+        let msg = ExecuteMsg::AddNativeTokenDecimals {
+            denom: denom.to_string(),
+            decimals: Some(decimals),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokens {
+            start_after: None,
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    let res: NativeTokensResponse = from_json(&res).unwrap();
+    assert_eq!(
+        res.native_tokens,
+        vec![
+            NativeToken {
+                denom: "ukrw".to_string(),
+                decimals: 8u8,
+            },
+            NativeToken {
+                denom: "uluna".to_string(),
+                decimals: 6u8,
+            },
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokens {
+            start_after: Some("uluna".to_string()),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let res: NativeTokensResponse = from_json(&res).unwrap();
+    assert_eq!(
+        res.native_tokens,
+        vec![NativeToken {
+            denom: "uusd".to_string(),
+            decimals: 6u8,
+        }]
+    );
+
+    let msg = ExecuteMsg::RemoveNativeTokenDecimals {
+        denom: "uluna".to_string(),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("noadmin", &[]),
+            msg.clone()
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap(),
+        Response::new().add_attributes(vec![
+            ("action", "remove_allow_native_token"),
+            ("denom", "uluna"),
+        ])
+    );
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg),
+        Err(StdError::generic_err("Native token is not registered"))
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokenDecimals {
+            denom: "uluna".to_string(),
+        },
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn register_ibc_denom() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let hash = "27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+    let denom = format!("ibc/{}", hash);
+    let info = mock_info("addr0000", &[]);
+
+    let msg = ExecuteMsg::RegisterIbcDenom {
+        denom: denom.to_lowercase(),
+        symbol: "ATOM".to_string(),
+        channel: "channel-0".to_string(),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("noadmin", &[]),
+            msg.clone()
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+
+    // the hash of the denom is normalized
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "register_ibc_denom"),
+            attr("denom", &denom),
+            attr("symbol", "ATOM"),
+            attr("channel", "channel-0"),
+        ]
+    );
+
+    let msg = ExecuteMsg::RegisterIbcDenom {
+        denom: "ibc/HASH".to_string(),
+        symbol: "ATOM".to_string(),
+        channel: "channel-0".to_string(),
+    };
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info.clone(), msg),
+        Err(StdError::generic_err("ibc/HASH is not an ibc denom"))
+    );
+
+    let msg = ExecuteMsg::RegisterIbcDenom {
+        denom: denom.clone(),
+        symbol: "ATOM".to_string(),
+        channel: "transfer/channel-0".to_string(),
+    };
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info.clone(), msg),
+        Err(StdError::generic_err("invalid channel: transfer/channel-0"))
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::IbcDenoms {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let res: IbcDenomsResponse = from_json(&res).unwrap();
+    assert_eq!(
+        res.ibc_denoms,
+        vec![IbcDenomInfo {
+            denom: denom.clone(),
+            symbol: "ATOM".to_string(),
+            channel: "channel-0".to_string(),
+        }]
+    );
+
+    // the pair queries show the symbols of the denoms
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: denom.clone(),
+        },
+        AssetInfo::NativeToken {
+            denom: "factory/creator0000/uhalo".to_string(),
+        },
+    ];
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+    let pair_info = PairInfoRaw {
+        asset_infos: raw_infos.clone(),
+        contract_addr: deps.as_ref().api.addr_canonicalize("pair0000").unwrap(),
+        liquidity_token: deps
+            .as_ref()
+            .api
+            .addr_canonicalize("liquidity0000")
+            .unwrap(),
+        asset_decimals: [6u8, 6u8],
+        requirements: CreatePairRequirements {
+            whitelist: vec![Addr::unchecked("deployer")],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+        creator: None,
+        liquidity_denom: None,
+    };
+    pairs()
+        .save(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info)
+        .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pair {
+            asset_infos: asset_infos.clone(),
+        },
+    )
+    .unwrap();
+    let res: PairInfo = from_json(&res).unwrap();
+    assert_eq!(
+        res.asset_symbols,
+        Some(["ATOM".to_string(), "uhalo".to_string()])
+    );
+
+    let msg = ExecuteMsg::DeregisterIbcDenom {
+        denom: denom.clone(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deregister_ibc_denom"),
+            attr("denom", &denom)
+        ]
+    );
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg),
+        Err(StdError::generic_err("Ibc denom is not registered"))
+    );
+
+    // unregistered ibc denoms are shown as they are
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pairs {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let res: PairsResponse = from_json(&res).unwrap();
+    assert_eq!(
+        res.pairs[0].asset_symbols,
+        Some([denom, "uhalo".to_string()])
+    );
+}
+
+#[test]
+fn normal_migrate_pair() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+    save_token_pair(&mut deps, "asset0001");
+
+    let msg = ExecuteMsg::MigratePair {
+        code_id: Some(123u64),
+        contract: "pairasset0001".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap(),
+        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: "pairasset0001".to_string(),
+            new_code_id: 123u64,
+            msg: to_json_binary(&PairMigrateMsg::default()).unwrap(),
+        })),
+    );
+}
+
+#[test]
+fn normal_migrate_pair_with_none_code_id_will_config_code_id() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+    save_token_pair(&mut deps, "asset0001");
 
     let msg = ExecuteMsg::MigratePair {
         code_id: None,
-        contract: "contract0000".to_string(),
+        contract: "pairasset0001".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -703,13 +1571,32 @@ fn normal_migrate_pair_with_none_code_id_will_config_code_id() {
     assert_eq!(
         execute(deps.as_mut(), mock_env(), info, msg).unwrap(),
         Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
-            contract_addr: "contract0000".to_string(),
+            contract_addr: "pairasset0001".to_string(),
             new_code_id: 321u64,
-            msg: to_binary(&PairMigrateMsg {}).unwrap(),
+            msg: to_json_binary(&PairMigrateMsg::default()).unwrap(),
         })),
     );
 }
 
+#[test]
+fn failed_migrate_unregistered_pair() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::MigratePair {
+        code_id: None,
+        contract: "contract0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg),
+        Err(StdError::generic_err("Pair is not registered")),
+    );
+    assert!(PAIR_CODE_IDS.is_empty(deps.as_ref().storage));
+}
+
 #[test]
 fn failed_migrate_pair_with_no_admin() {
     let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
@@ -727,3 +1614,917 @@ fn failed_migrate_pair_with_no_admin() {
         Err(StdError::generic_err("unauthorized")),
     );
 }
+
+fn token_pair_info(asset_infos: [AssetInfo; 2], contract_addr: &str) -> PairInfo {
+    PairInfo {
+        asset_infos,
+        contract_addr: contract_addr.to_string(),
+        liquidity_token: "liquidity0000".to_string(),
+        asset_decimals: [8u8, 8u8],
+        asset_symbols: None,
+        incentive_contract: None,
+        requirements: CreatePairRequirements {
+            whitelist: vec![Addr::unchecked("deployer")],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+    }
+}
+
+#[test]
+fn deregister_and_replace_pair() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let other_asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0002".to_string(),
+        },
+    ];
+    deps.querier.with_halo_factory(
+        &[
+            (
+                &"pair0001".to_string(),
+                &token_pair_info(asset_infos.clone(), "pair0001"),
+            ),
+            (
+                &"pair0002".to_string(),
+                &token_pair_info([asset_infos[1].clone(), asset_infos[0].clone()], "pair0002"),
+            ),
+            (
+                &"pair0003".to_string(),
+                &token_pair_info(other_asset_infos, "pair0003"),
+            ),
+        ],
+        &[],
+    );
+
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+    let pair_info = PairInfoRaw {
+        asset_infos: raw_infos.clone(),
+        contract_addr: deps.as_ref().api.addr_canonicalize("pair0000").unwrap(),
+        liquidity_token: deps
+            .as_ref()
+            .api
+            .addr_canonicalize("liquidity0000")
+            .unwrap(),
+        asset_decimals: [8u8, 8u8],
+        requirements: CreatePairRequirements {
+            whitelist: vec![Addr::unchecked("deployer")],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+        creator: None,
+        liquidity_denom: None,
+    };
+    pairs()
+        .save(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info)
+        .unwrap();
+
+    // only the owner can deregister a pair
+    let msg = ExecuteMsg::Deregister {
+        asset_infos: asset_infos.clone(),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("noadmin", &[]),
+            msg.clone()
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deregister"),
+            attr("pair", "asset0000-asset0001"),
+            attr("pair_contract_addr", "pair0000"),
+        ]
+    );
+    query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pair {
+            asset_infos: asset_infos.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg),
+        Err(StdError::generic_err("Pair does not exist"))
+    );
+
+    // the replacement must trade the same assets
+    let msg = ExecuteMsg::ReplacePair {
+        asset_infos: asset_infos.clone(),
+        pair_contract: "pair0003".to_string(),
+    };
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg),
+        Err(StdError::generic_err("asset infos mismatch"))
+    );
+
+    // the factory must be the admin of the replacement
+    let msg = ExecuteMsg::ReplacePair {
+        asset_infos: asset_infos.clone(),
+        pair_contract: "pair0001".to_string(),
+    };
+    deps.querier
+        .with_contract_info("pair0001", 5u64, Some("addr0000"));
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            msg.clone()
+        ),
+        Err(StdError::generic_err(
+            "the factory is not the admin of the pair"
+        ))
+    );
+
+    // register a new pair after the deregistration
+    deps.querier
+        .with_contract_info("pair0001", 5u64, Some(MOCK_CONTRACT_ADDR));
+    deps.querier
+        .with_contract_info("pair0002", 6u64, Some(MOCK_CONTRACT_ADDR));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "replace_pair"),
+            attr("pair", "asset0000-asset0001"),
+            attr("pair_contract_addr", "pair0001"),
+        ]
+    );
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg),
+        Err(StdError::generic_err("Pair already registered"))
+    );
+
+    // replace the registered pair
+    let msg = ExecuteMsg::ReplacePair {
+        asset_infos: asset_infos.clone(),
+        pair_contract: "pair0002".to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("deprecated_pair_contract_addr", "pair0001"),
+            attr("action", "replace_pair"),
+            attr("pair", "asset0000-asset0001"),
+            attr("pair_contract_addr", "pair0002"),
+        ]
+    );
+
+    let pair_res: PairInfo = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pair_res.contract_addr, "pair0002".to_string());
+
+    // the code ids of the replacements are recorded
+    let pair_code_id = |contract: &str| {
+        PAIR_CODE_IDS
+            .load(
+                deps.as_ref().storage,
+                deps.as_ref()
+                    .api
+                    .addr_canonicalize(contract)
+                    .unwrap()
+                    .as_slice(),
+            )
+            .unwrap()
+    };
+    assert_eq!(pair_code_id("pair0001"), 5u64);
+    assert_eq!(pair_code_id("pair0002"), 6u64);
+
+    // the deprecated pairs are kept for historical lookups
+    let deprecated_res: PairsResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DeprecatedPairs {
+                asset_infos: asset_infos.clone(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        deprecated_res
+            .pairs
+            .iter()
+            .map(|pair| pair.contract_addr.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["pair0000", "pair0001"]
+    );
+
+    let deprecated_res: PairsResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DeprecatedPairs {
+                asset_infos,
+                start_after: Some("pair0000".to_string()),
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(deprecated_res.pairs.len(), 1);
+    assert_eq!(
+        deprecated_res.pairs[0].contract_addr,
+        "pair0001".to_string()
+    );
+}
+
+fn save_token_pair(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, token: &str) {
+    let raw_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        }
+        .to_raw(deps.as_ref().api)
+        .unwrap(),
+        AssetInfo::Token {
+            contract_addr: token.to_string(),
+        }
+        .to_raw(deps.as_ref().api)
+        .unwrap(),
+    ];
+    let pair_info = PairInfoRaw {
+        asset_infos: raw_infos.clone(),
+        contract_addr: deps
+            .as_ref()
+            .api
+            .addr_canonicalize(&format!("pair{}", token))
+            .unwrap(),
+        liquidity_token: deps
+            .as_ref()
+            .api
+            .addr_canonicalize(&format!("liquidity{}", token))
+            .unwrap(),
+        asset_decimals: [8u8, 8u8],
+        requirements: CreatePairRequirements {
+            whitelist: vec![Addr::unchecked("deployer")],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+        creator: None,
+        liquidity_denom: None,
+    };
+    pairs()
+        .save(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info)
+        .unwrap();
+}
+
+fn query_outdated_pairs(deps: Deps, code_id: Option<u64>) -> Vec<PairInfo> {
+    let res: OutdatedPairsResponse = from_json(
+        query(
+            deps,
+            mock_env(),
+            QueryMsg::OutdatedPairs {
+                code_id,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.start_after, None);
+
+    res.pairs
+}
+
+#[test]
+fn migrate_pairs() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    save_token_pair(&mut deps, "asset0001");
+    save_token_pair(&mut deps, "asset0002");
+    save_token_pair(&mut deps, "asset0003");
+
+    // the pairs are not known to run the config code id
+    let canonical_addr = deps
+        .as_ref()
+        .api
+        .addr_canonicalize("pairasset0001")
+        .unwrap();
+    PAIR_CODE_IDS
+        .save(deps.as_mut().storage, canonical_addr.as_slice(), &321u64)
+        .unwrap();
+    let outdated_pairs = query_outdated_pairs(deps.as_ref(), None);
+    assert_eq!(outdated_pairs.len(), 2);
+    assert!(outdated_pairs
+        .iter()
+        .all(|pair| pair.contract_addr != "pairasset0001"));
+    assert_eq!(query_outdated_pairs(deps.as_ref(), Some(400u64)).len(), 3);
+
+    // the limit bounds the visited pairs, the scan continues from the returned cursor
+    let mut start_after = None;
+    let mut outdated_pairs: Vec<PairInfo> = vec![];
+    let mut pages = 0;
+    loop {
+        let res: OutdatedPairsResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OutdatedPairs {
+                    code_id: None,
+                    start_after,
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(res.pairs.len() <= 1);
+        outdated_pairs.extend(res.pairs);
+        pages += 1;
+
+        start_after = res.start_after;
+        if start_after.is_none() {
+            break;
+        }
+    }
+    assert_eq!(pages, 4);
+    assert_eq!(outdated_pairs.len(), 2);
+
+    // only the owner can migrate the pairs
+    let msg = ExecuteMsg::MigratePairs {
+        start_after: None,
+        limit: Some(2u32),
+        code_id: Some(400u64),
+        msg: Some(to_json_binary(&"migrate").unwrap()),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("noadmin", &[]),
+            msg.clone()
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+
+    let pairs: PairsResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pairs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    // the first page is migrated with the given payload
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        pairs.pairs[..2]
+            .iter()
+            .map(|pair| SubMsg::new(CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: pair.contract_addr.clone(),
+                new_code_id: 400u64,
+                msg: to_json_binary(&"migrate").unwrap(),
+            })))
+            .collect::<Vec<SubMsg>>()
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_pairs"),
+            attr("code_id", "400"),
+            attr("migrated_count", "2"),
+            attr(
+                "last_pair",
+                format!(
+                    "{}-{}",
+                    pairs.pairs[1].asset_infos[0], pairs.pairs[1].asset_infos[1]
+                )
+            ),
+        ]
+    );
+    assert_eq!(query_outdated_pairs(deps.as_ref(), Some(400u64)).len(), 1);
+
+    // the next page defaults to an empty migrate message
+    let msg = ExecuteMsg::MigratePairs {
+        start_after: Some(pairs.pairs[1].asset_infos.clone()),
+        limit: Some(2u32),
+        code_id: Some(400u64),
+        msg: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: pairs.pairs[2].contract_addr.clone(),
+            new_code_id: 400u64,
+            msg: to_json_binary(&PairMigrateMsg::default()).unwrap(),
+        }))]
+    );
+    assert!(query_outdated_pairs(deps.as_ref(), Some(400u64)).is_empty());
+
+    // the pairs already running the code id are skipped
+    let msg = ExecuteMsg::MigratePairs {
+        start_after: None,
+        limit: None,
+        code_id: Some(400u64),
+        msg: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn migrate_records_pair_code_ids() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    save_token_pair(&mut deps, "asset0001");
+    save_token_pair(&mut deps, "asset0002");
+    assert_eq!(query_outdated_pairs(deps.as_ref(), None).len(), 2);
+
+    // cannot downgrade
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-factory", "99.0.0").unwrap();
+    let msg = MigrateMsg {
+        pair_code_id: Some(321u64),
+    };
+    assert_eq!(
+        migrate(deps.as_mut(), mock_env(), msg.clone()),
+        Err(StdError::generic_err(
+            "cannot migrate from a newer version: 99.0.0"
+        ))
+    );
+
+    // the registered pairs are recorded with the given code id
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-factory", "0.1.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("from_version", "0.1.0"),
+            attr("to_version", env!("CARGO_PKG_VERSION")),
+        ]
+    );
+    assert!(query_outdated_pairs(deps.as_ref(), None).is_empty());
+}
+
+#[test]
+fn update_pair_config() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    save_token_pair(&mut deps, "asset0001");
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let requirements = CreatePairRequirements {
+        whitelist: vec![Addr::unchecked("addr0001")],
+        first_asset_minimum: Uint128::from(100u128),
+        second_asset_minimum: Uint128::from(100u128),
+    };
+
+    let msg = ExecuteMsg::UpdatePairConfig {
+        asset_infos: asset_infos.clone(),
+        requirements: Some(requirements.clone()),
+        commission_rate: Some(Decimal::percent(1)),
+        pause: Some(PauseFlags {
+            swap: true,
+            provide_liquidity: false,
+        }),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("noadmin", &[]),
+            msg.clone()
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+
+    let unknown_msg = ExecuteMsg::UpdatePairConfig {
+        asset_infos: [
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0002".to_string(),
+            },
+        ],
+        requirements: None,
+        commission_rate: None,
+        pause: None,
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            unknown_msg
+        ),
+        Err(StdError::generic_err("Pair does not exist"))
+    );
+
+    // the update is forwarded to the pair
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "pairasset0001".to_string(),
+            msg: to_json_binary(&PairExecuteMsg::UpdatePairConfig {
+                requirements: Some(requirements.clone()),
+                commission_rate: Some(Decimal::percent(1)),
+                pause: Some(PauseFlags {
+                    swap: true,
+                    provide_liquidity: false,
+                }),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_pair_config"),
+            attr("pair", "asset0000-asset0001"),
+            attr("pair_contract_addr", "pairasset0001"),
+        ]
+    );
+
+    // the stored requirements follow the pair
+    let res: PairInfo =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Pair { asset_infos }).unwrap())
+            .unwrap();
+    assert_eq!(res.requirements, requirements);
+}
+
+#[test]
+fn set_pair_incentive() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    save_token_pair(&mut deps, "asset0001");
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+
+    let msg = ExecuteMsg::SetPairIncentive {
+        asset_infos: asset_infos.clone(),
+        incentive_contract: Some("staking0000".to_string()),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("noadmin", &[]),
+            msg.clone()
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_pair_incentive"),
+            attr("pair", "asset0000-asset0001"),
+            attr("incentive_contract", "staking0000"),
+        ]
+    );
+
+    // the pair queries show the incentive contract
+    let res: PairInfo = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.incentive_contract, Some("staking0000".to_string()));
+
+    let res: PairInfo = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByLiquidityToken {
+                liquidity_token: "liquidityasset0001".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.incentive_contract, Some("staking0000".to_string()));
+
+    let msg = ExecuteMsg::SetPairIncentive {
+        asset_infos: asset_infos.clone(),
+        incentive_contract: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let res: PairInfo = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.incentive_contract, None);
+
+    // the incentive is cleared when the pair is deregistered
+    let msg = ExecuteMsg::SetPairIncentive {
+        asset_infos: asset_infos.clone(),
+        incentive_contract: Some("staking0000".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Deregister {
+        asset_infos: asset_infos.clone(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+    assert!(!PAIR_INCENTIVES.has(deps.as_ref().storage, &pair_key(&raw_infos)));
+}
+
+#[test]
+fn query_pairs_by_indexes() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    // the pairs are saved without indexes as before 0.3.0
+    let legacy_pairs: Map<&[u8], PairInfoRaw> = Map::new("pair_info");
+    for (first, second, contract, creator) in [
+        ("asset0000", "asset0001", "pair0001", "addr0000"),
+        ("asset0002", "asset0000", "pair0002", "addr0001"),
+        ("asset0001", "asset0002", "pair0003", "addr0000"),
+    ] {
+        let raw_infos = [
+            AssetInfo::Token {
+                contract_addr: first.to_string(),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+            AssetInfo::Token {
+                contract_addr: second.to_string(),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+        ];
+        let pair_info = PairInfoRaw {
+            asset_infos: raw_infos.clone(),
+            contract_addr: deps.api.addr_canonicalize(contract).unwrap(),
+            liquidity_token: deps
+                .api
+                .addr_canonicalize(&contract.replace("pair", "liquidity"))
+                .unwrap(),
+            asset_decimals: [8u8, 8u8],
+            requirements: CreatePairRequirements {
+                whitelist: vec![Addr::unchecked("deployer")],
+                first_asset_minimum: Uint128::zero(),
+                second_asset_minimum: Uint128::zero(),
+            },
+            creator: Some(deps.api.addr_canonicalize(creator).unwrap()),
+            liquidity_denom: None,
+        };
+        legacy_pairs
+            .save(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info)
+            .unwrap();
+    }
+
+    let query_pair_contracts = |deps: Deps, msg: QueryMsg| -> Vec<String> {
+        let res: PairsResponse = from_json(query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.pairs
+            .into_iter()
+            .map(|pair| pair.contract_addr)
+            .collect()
+    };
+    let pairs_by_asset = QueryMsg::PairsByAsset {
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        start_after: None,
+        limit: None,
+    };
+    assert!(query_pair_contracts(deps.as_ref(), pairs_by_asset.clone()).is_empty());
+
+    // the migration builds the indexes
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-factory", "0.2.0").unwrap();
+    migrate(deps.as_mut(), mock_env(), MigrateMsg { pair_code_id: None }).unwrap();
+
+    // the pairs containing the asset on either side
+    let mut contracts = query_pair_contracts(deps.as_ref(), pairs_by_asset);
+    contracts.sort();
+    assert_eq!(contracts, vec!["pair0001", "pair0002"]);
+
+    let res: PairsResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairsByAsset {
+                asset_info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                start_after: None,
+                limit: Some(1u32),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.pairs.len(), 1);
+    let next_contracts = query_pair_contracts(
+        deps.as_ref(),
+        QueryMsg::PairsByAsset {
+            asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            start_after: Some(res.pairs[0].asset_infos.clone()),
+            limit: None,
+        },
+    );
+    assert_eq!(next_contracts.len(), 1);
+    assert_ne!(next_contracts[0], res.pairs[0].contract_addr);
+
+    let res: PairInfo = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByLiquidityToken {
+                liquidity_token: "liquidity0002".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.contract_addr, "pair0002");
+
+    let res: PairInfo = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByContract {
+                contract_addr: "pair0003".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.liquidity_token, "liquidity0003");
+
+    let mut contracts = query_pair_contracts(
+        deps.as_ref(),
+        QueryMsg::PairsByCreator {
+            creator: "addr0000".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    contracts.sort();
+    assert_eq!(contracts, vec!["pair0001", "pair0003"]);
+
+    // a deregistered pair is removed from the indexes
+    let msg = ExecuteMsg::Deregister {
+        asset_infos: [
+            AssetInfo::Token {
+                contract_addr: "asset0002".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert!(query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairByContract {
+            contract_addr: "pair0002".to_string(),
+        },
+    )
+    .is_err());
+    assert!(query_pair_contracts(
+        deps.as_ref(),
+        QueryMsg::PairsByCreator {
+            creator: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .is_empty());
+}
+
+#[test]
+fn predict_pair_addresses_with_instantiate2() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+
+    // the mock checksums repeat the low byte of the code id, 321 for the pair and 123 for the token
+    let factory = deps
+        .as_ref()
+        .api
+        .addr_canonicalize(MOCK_CONTRACT_ADDR)
+        .unwrap();
+    let salt = pair_salt(&deps.storage, &pair_key).unwrap();
+    let expected_pair = instantiate2_address(&[65u8; 32], &factory, &salt).unwrap();
+    let expected_token =
+        instantiate2_address(&[123u8; 32], &expected_pair, LIQUIDITY_TOKEN_SALT).unwrap();
+    assert_eq!(
+        predict_pair_addresses(deps.as_ref(), &mock_env(), &asset_infos).unwrap(),
+        (expected_pair.clone(), expected_token)
+    );
+
+    // a pair created again after the deregistration gets a new address
+    save_token_pair(&mut deps, "asset0001");
+    let msg = ExecuteMsg::Deregister {
+        asset_infos: asset_infos.clone(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    assert_ne!(pair_salt(&deps.storage, &pair_key).unwrap(), salt);
+    let (pair_contract, _) =
+        predict_pair_addresses(deps.as_ref(), &mock_env(), &asset_infos).unwrap();
+    assert_ne!(pair_contract, expected_pair);
+}
@@ -0,0 +1,636 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Coin, CosmosMsg, Decimal256, Deps, DepsMut, Env,
+    MessageInfo, Response, StdResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use crate::error::ContractError;
+use crate::state::{Config, State, BRIDGES, CONFIG, EPOCH_INDEXES, STAKERS, STATE};
+
+use haloswap::asset::{Asset, AssetInfo, AssetInfoRaw};
+use haloswap::fee_distributor::{
+    BridgeResponse, ConfigResponse, ConvertAsset, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, QueryMsg, StakerResponse, StateResponse,
+};
+use haloswap::migration::assert_migrate_version;
+use haloswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:halo-fee-distributor";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The bridge assets of a fee asset, each adding a hop to the conversion
+const MAX_BRIDGE_LENGTH: usize = 3;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.epoch_length == 0 {
+        return Err(ContractError::InvalidEpochLength {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            halo_token: deps.api.addr_canonicalize(&msg.halo_token)?,
+            halo_router: deps.api.addr_canonicalize(&msg.halo_router)?,
+            reward_asset: msg.reward_asset.to_raw(deps.api)?,
+            epoch_length: msg.epoch_length,
+        },
+    )?;
+
+    STATE.save(
+        deps.storage,
+        &State {
+            epoch: 0,
+            epoch_start_time: env.block.time.seconds(),
+            total_staked: Uint128::zero(),
+            next_epoch_staked: Uint128::zero(),
+            global_index: Decimal256::zero(),
+            unclaimed_rewards: Uint128::zero(),
+        },
+    )?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            halo_router,
+            epoch_length,
+        } => update_config(deps, env, info, owner, halo_router, epoch_length),
+        ExecuteMsg::UpdateBridge { asset_info, bridge } => {
+            update_bridge(deps, info, asset_info, bridge)
+        }
+        ExecuteMsg::Convert { assets } => convert(deps, env, info, assets),
+        ExecuteMsg::Unstake { amount } => unstake(deps, env, info, amount),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::Stake {} => {
+            // only the halo token can be staked
+            let config = CONFIG.load(deps.storage)?;
+            if deps.api.addr_canonicalize(info.sender.as_str())? != config.halo_token {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let staker = deps.api.addr_validate(&cw20_msg.sender)?;
+            stake(deps, env, staker, cw20_msg.amount)
+        }
+    }
+}
+
+// Only owner can execute it
+pub fn update_config(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Option<String>,
+    halo_router: Option<String>,
+    epoch_length: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    if let Some(halo_router) = halo_router {
+        // validate address format
+        let _ = deps.api.addr_validate(&halo_router)?;
+
+        config.halo_router = deps.api.addr_canonicalize(&halo_router)?;
+    }
+
+    if let Some(epoch_length) = epoch_length {
+        if epoch_length == 0 {
+            return Err(ContractError::InvalidEpochLength {});
+        }
+
+        // the ended epochs are closed with the length they were run with
+        let state = update_epoch(deps.branch(), &env, &config)?;
+        STATE.save(deps.storage, &state)?;
+        config.epoch_length = epoch_length;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+// Only owner can execute it
+pub fn update_bridge(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    bridge: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let asset_info_raw = asset_info.to_raw(deps.api)?;
+    let bridge = bridge.unwrap_or_default();
+    if bridge.is_empty() {
+        BRIDGES.remove(deps.storage, asset_info_raw.as_bytes());
+
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "update_bridge"),
+            ("asset_info", &asset_info.to_string()),
+            ("bridge", ""),
+        ]));
+    }
+
+    if bridge.len() > MAX_BRIDGE_LENGTH {
+        return Err(ContractError::BridgeTooLong {
+            max: MAX_BRIDGE_LENGTH,
+        });
+    }
+
+    // every hop must swap into a new asset
+    let mut path: Vec<AssetInfoRaw> = vec![asset_info_raw.clone(), config.reward_asset];
+    let mut bridge_raw: Vec<AssetInfoRaw> = vec![];
+    for bridge_asset in bridge.iter() {
+        let bridge_asset = bridge_asset.to_raw(deps.api)?;
+        if path.iter().any(|asset| asset.equal(&bridge_asset)) {
+            return Err(ContractError::InvalidBridge {});
+        }
+
+        path.push(bridge_asset.clone());
+        bridge_raw.push(bridge_asset);
+    }
+
+    BRIDGES.save(deps.storage, asset_info_raw.as_bytes(), &bridge_raw)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_bridge"),
+        ("asset_info", &asset_info.to_string()),
+        (
+            "bridge",
+            &bridge
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        ),
+    ]))
+}
+
+// Only owner can execute it, the swaps are bounded by the minimum amounts to receive
+pub fn convert(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<ConvertAsset>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the rewards collected before the conversion belong to the ended epochs
+    let state = update_epoch(deps.branch(), &env, &config)?;
+    STATE.save(deps.storage, &state)?;
+
+    let halo_router = deps.api.addr_humanize(&config.halo_router)?;
+    let reward_asset = config.reward_asset.to_normal(deps.api)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut converted: Vec<String> = vec![];
+    for ConvertAsset {
+        info: asset_info,
+        minimum_receive,
+    } in assets
+    {
+        if asset_info.equal(&reward_asset) {
+            return Err(ContractError::CannotConvertRewardAsset {});
+        }
+
+        let amount = convertible_amount(deps.as_ref(), &env, &config, &state, &asset_info)?;
+        if amount.is_zero() {
+            continue;
+        }
+
+        let bridge =
+            match BRIDGES.may_load(deps.storage, asset_info.to_raw(deps.api)?.as_bytes())? {
+                Some(bridge) => bridge
+                    .iter()
+                    .map(|asset| asset.to_normal(deps.api))
+                    .collect::<StdResult<Vec<AssetInfo>>>()?,
+                None => vec![],
+            };
+
+        let mut path = vec![asset_info.clone()];
+        path.extend(bridge);
+        path.push(reward_asset.clone());
+        let operations: Vec<SwapOperation> = path
+            .windows(2)
+            .map(|hop| SwapOperation::HaloSwap {
+                offer_asset_info: hop[0].clone(),
+                ask_asset_info: hop[1].clone(),
+            })
+            .collect();
+
+        converted.push(
+            Asset {
+                info: asset_info.clone(),
+                amount,
+            }
+            .to_string(),
+        );
+        messages.push(swap_msg(
+            &halo_router,
+            asset_info,
+            amount,
+            operations,
+            minimum_receive,
+        )?);
+    }
+
+    if messages.is_empty() {
+        return Err(ContractError::NothingToConvert {});
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "convert"),
+        ("converted", &converted.join(",")),
+    ]))
+}
+
+/// The balance of a fee asset, leaving out the staked halo
+fn convertible_amount(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &State,
+    asset_info: &AssetInfo,
+) -> StdResult<Uint128> {
+    let balance = asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+    if is_halo_token(deps, config, asset_info)? {
+        return Ok(balance.saturating_sub(state.total_staked + state.next_epoch_staked));
+    }
+
+    Ok(balance)
+}
+
+fn is_halo_token(deps: Deps, config: &Config, asset_info: &AssetInfo) -> StdResult<bool> {
+    Ok(match asset_info {
+        AssetInfo::Token { contract_addr } => {
+            deps.api.addr_canonicalize(contract_addr)? == config.halo_token
+        }
+        AssetInfo::NativeToken { .. } => false,
+    })
+}
+
+fn swap_msg(
+    halo_router: &Addr,
+    asset_info: AssetInfo,
+    amount: Uint128,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Uint128,
+) -> StdResult<CosmosMsg> {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: halo_router.to_string(),
+                amount,
+                msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: Some(minimum_receive),
+                    to: None,
+                    referral_address: None,
+                    referral_commission: None,
+                    callback: None,
+                })?,
+            })?,
+            funds: vec![],
+        })),
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: halo_router.to_string(),
+            msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: Some(minimum_receive),
+                to: None,
+                referral_address: None,
+                referral_commission: None,
+                callback: None,
+            })?,
+            funds: vec![Coin { denom, amount }],
+        })),
+    }
+}
+
+pub fn stake(
+    mut deps: DepsMut,
+    env: Env,
+    staker: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = update_epoch(deps.branch(), &env, &config)?;
+
+    let staker_raw = deps.api.addr_canonicalize(staker.as_str())?;
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, &staker_raw)?
+        .unwrap_or_default();
+    staker_info.accrue(deps.storage, &state, None)?;
+
+    // the stake shares the fees from the next epoch
+    staker_info.next_epoch_staked = staker_info.next_epoch_staked.checked_add(amount)?;
+    staker_info.stake_epoch = state.epoch;
+    state.next_epoch_staked = state.next_epoch_staked.checked_add(amount)?;
+
+    STAKERS.save(deps.storage, &staker_raw, &staker_info)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "stake"),
+        ("staker", staker.as_str()),
+        ("amount", &amount.to_string()),
+        ("epoch", &state.epoch.to_string()),
+    ]))
+}
+
+pub fn unstake(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = update_epoch(deps.branch(), &env, &config)?;
+
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, &staker_raw)?
+        .unwrap_or_default();
+    staker_info.accrue(deps.storage, &state, None)?;
+
+    if staker_info.staked + staker_info.next_epoch_staked < amount {
+        return Err(ContractError::InsufficientStake {});
+    }
+
+    // the stake waiting for the next epoch is returned first
+    let from_next_epoch = amount.min(staker_info.next_epoch_staked);
+    let from_staked = amount - from_next_epoch;
+    staker_info.next_epoch_staked -= from_next_epoch;
+    staker_info.staked -= from_staked;
+    state.next_epoch_staked = state.next_epoch_staked.checked_sub(from_next_epoch)?;
+    state.total_staked = state.total_staked.checked_sub(from_staked)?;
+
+    if staker_info.is_empty() {
+        STAKERS.remove(deps.storage, &staker_raw);
+    } else {
+        STAKERS.save(deps.storage, &staker_raw, &staker_info)?;
+    }
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: deps.api.addr_humanize(&config.halo_token)?.to_string(),
+                },
+                amount,
+            }
+            .into_msg(info.sender.clone())?,
+        )
+        .add_attributes(vec![
+            ("action", "unstake"),
+            ("staker", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+pub fn claim(mut deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = update_epoch(deps.branch(), &env, &config)?;
+
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, &staker_raw)?
+        .unwrap_or_default();
+    staker_info.accrue(deps.storage, &state, None)?;
+
+    let amount = staker_info.pending_rewards;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    staker_info.pending_rewards = Uint128::zero();
+    state.unclaimed_rewards = state.unclaimed_rewards.checked_sub(amount)?;
+
+    if staker_info.is_empty() {
+        STAKERS.remove(deps.storage, &staker_raw);
+    } else {
+        STAKERS.save(deps.storage, &staker_raw, &staker_info)?;
+    }
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(
+            Asset {
+                info: config.reward_asset.to_normal(deps.api)?,
+                amount,
+            }
+            .into_msg(info.sender.clone())?,
+        )
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("staker", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+/// Close the ended epoch and record its global index for the stake made during it
+fn update_epoch(deps: DepsMut, env: &Env, config: &Config) -> StdResult<State> {
+    let mut state = STATE.load(deps.storage)?;
+    if let Some((epoch, index)) = advance_epoch(deps.as_ref(), env, config, &mut state)? {
+        EPOCH_INDEXES.save(deps.storage, epoch, &index)?;
+    }
+
+    Ok(state)
+}
+
+/// Distribute the rewards collected until the end of the current epoch to its stake
+/// and return the closed epoch with its global index, the epochs ended without
+/// any activity are skipped as their rewards are distributed along
+fn advance_epoch(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &mut State,
+) -> StdResult<Option<(u64, Decimal256)>> {
+    let elapsed_epochs = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(state.epoch_start_time)
+        / config.epoch_length;
+    if elapsed_epochs == 0 {
+        return Ok(None);
+    }
+
+    // the rewards released while nothing is staked are kept for the next epoch
+    let undistributed = undistributed_rewards(deps, env, config, state)?;
+    if !undistributed.is_zero() && !state.total_staked.is_zero() {
+        state.global_index += Decimal256::from_ratio(undistributed, state.total_staked);
+        state.unclaimed_rewards = state.unclaimed_rewards.checked_add(undistributed)?;
+    }
+
+    let closed_epoch = (state.epoch, state.global_index);
+    state.epoch += elapsed_epochs;
+    state.epoch_start_time += elapsed_epochs * config.epoch_length;
+    state.total_staked = state.total_staked.checked_add(state.next_epoch_staked)?;
+    state.next_epoch_staked = Uint128::zero();
+
+    Ok(Some(closed_epoch))
+}
+
+/// The balance of the reward asset, leaving out the unclaimed rewards and the staked halo
+fn undistributed_rewards(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &State,
+) -> StdResult<Uint128> {
+    let reward_asset = config.reward_asset.to_normal(deps.api)?;
+    let mut reserved = state.unclaimed_rewards;
+    if is_halo_token(deps, config, &reward_asset)? {
+        reserved += state.total_staked + state.next_epoch_staked;
+    }
+
+    let balance = reward_asset.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+    Ok(balance.saturating_sub(reserved))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::State {} => to_json_binary(&query_state(deps, env)?),
+        QueryMsg::Bridge { asset_info } => to_json_binary(&query_bridge(deps, asset_info)?),
+        QueryMsg::Staker { address } => to_json_binary(&query_staker(deps, env, address)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        halo_token: deps.api.addr_humanize(&config.halo_token)?.to_string(),
+        halo_router: deps.api.addr_humanize(&config.halo_router)?.to_string(),
+        reward_asset: config.reward_asset.to_normal(deps.api)?,
+        epoch_length: config.epoch_length,
+    })
+}
+
+/// The state with the ended epoch closed
+pub fn query_state(deps: Deps, env: Env) -> StdResult<StateResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+    advance_epoch(deps, &env, &config, &mut state)?;
+
+    Ok(StateResponse {
+        epoch: state.epoch,
+        epoch_start_time: state.epoch_start_time,
+        total_staked: state.total_staked,
+        next_epoch_staked: state.next_epoch_staked,
+        global_index: state.global_index,
+        unclaimed_rewards: state.unclaimed_rewards,
+    })
+}
+
+pub fn query_bridge(deps: Deps, asset_info: AssetInfo) -> StdResult<BridgeResponse> {
+    let bridge = BRIDGES
+        .may_load(deps.storage, asset_info.to_raw(deps.api)?.as_bytes())?
+        .unwrap_or_default()
+        .iter()
+        .map(|asset| asset.to_normal(deps.api))
+        .collect::<StdResult<Vec<AssetInfo>>>()?;
+
+    Ok(BridgeResponse { bridge })
+}
+
+pub fn query_staker(deps: Deps, env: Env, address: String) -> StdResult<StakerResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+    let closed_epoch = advance_epoch(deps, &env, &config, &mut state)?;
+
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, &deps.api.addr_canonicalize(&address)?)?
+        .unwrap_or_default();
+    staker_info.accrue(deps.storage, &state, closed_epoch)?;
+
+    Ok(StakerResponse {
+        address,
+        staked: staker_info.staked,
+        next_epoch_staked: staker_info.next_epoch_staked,
+        pending_rewards: staker_info.pending_rewards,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
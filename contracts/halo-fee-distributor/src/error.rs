@@ -0,0 +1,38 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Epoch length must not be zero")]
+    InvalidEpochLength {},
+
+    #[error("Bridge must not contain the fee asset, the reward asset or a duplicate")]
+    InvalidBridge {},
+
+    #[error("Bridge is longer than {max} assets")]
+    BridgeTooLong { max: usize },
+
+    #[error("The reward asset is not converted")]
+    CannotConvertRewardAsset {},
+
+    #[error("Nothing to convert")]
+    NothingToConvert {},
+
+    #[error("Not enough staked tokens")]
+    InsufficientStake {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
@@ -0,0 +1,80 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, Decimal256, StdResult, Storage, Uint128, Uint256};
+use cw_storage_plus::{Item, Map};
+use haloswap::asset::AssetInfoRaw;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub halo_token: CanonicalAddr,
+    pub halo_router: CanonicalAddr,
+    pub reward_asset: AssetInfoRaw,
+    pub epoch_length: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+pub struct State {
+    pub epoch: u64,
+    pub epoch_start_time: u64,
+    pub total_staked: Uint128,
+    pub next_epoch_staked: Uint128,
+    pub global_index: Decimal256,
+    pub unclaimed_rewards: Uint128,
+}
+
+pub const STATE: Item<State> = Item::new("state");
+
+// key : epoch / value: global index once the rewards of the epoch are distributed
+pub const EPOCH_INDEXES: Map<u64, Decimal256> = Map::new("epoch_index");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct StakerInfo {
+    pub staked: Uint128,
+    /// The stake made in `stake_epoch`, sharing the fees from the following epoch
+    pub next_epoch_staked: Uint128,
+    pub stake_epoch: u64,
+    pub index: Decimal256,
+    pub pending_rewards: Uint128,
+}
+
+impl StakerInfo {
+    /// Accrue the rewards distributed since the last accrual of the staker and activate the stake
+    /// of a closed epoch, `closed_epoch` is the epoch closed but not saved yet, if any
+    pub fn accrue(
+        &mut self,
+        storage: &dyn Storage,
+        state: &State,
+        closed_epoch: Option<(u64, Decimal256)>,
+    ) -> StdResult<()> {
+        let accrued = Uint256::from(self.staked).mul_floor(state.global_index - self.index);
+        self.pending_rewards = self.pending_rewards.checked_add(accrued.try_into()?)?;
+
+        if !self.next_epoch_staked.is_zero() && self.stake_epoch < state.epoch {
+            let epoch_index = match closed_epoch {
+                Some((epoch, index)) if epoch == self.stake_epoch => index,
+                _ => EPOCH_INDEXES.load(storage, self.stake_epoch)?,
+            };
+
+            let accrued =
+                Uint256::from(self.next_epoch_staked).mul_floor(state.global_index - epoch_index);
+            self.pending_rewards = self.pending_rewards.checked_add(accrued.try_into()?)?;
+            self.staked = self.staked.checked_add(self.next_epoch_staked)?;
+            self.next_epoch_staked = Uint128::zero();
+        }
+
+        self.index = state.global_index;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.staked.is_zero() && self.next_epoch_staked.is_zero() && self.pending_rewards.is_zero()
+    }
+}
+
+pub const STAKERS: Map<&[u8], StakerInfo> = Map::new("staker_info");
+
+// key : fee asset / value: assets swapped through before the reward asset
+pub const BRIDGES: Map<&[u8], Vec<AssetInfoRaw>> = Map::new("bridge");
@@ -0,0 +1,457 @@
+use crate::contract::{execute, instantiate, query_bridge, query_staker, query_state};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    to_json_binary, BankMsg, Coin, CosmosMsg, Decimal256, Env, OwnedDeps, SubMsg, Timestamp,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use haloswap::asset::AssetInfo;
+use haloswap::fee_distributor::{
+    ConvertAsset, Cw20HookMsg, ExecuteMsg, InstantiateMsg, StakerResponse,
+};
+use haloswap::mock_querier::{mock_dependencies, WasmMockQuerier};
+use haloswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
+
+const START: u64 = 1_600_000_000;
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+fn token(contract_addr: &str) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: contract_addr.to_string(),
+    }
+}
+
+fn convert_asset(info: AssetInfo, minimum_receive: u128) -> ConvertAsset {
+    ConvertAsset {
+        info,
+        minimum_receive: Uint128::from(minimum_receive),
+    }
+}
+
+fn init(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let msg = InstantiateMsg {
+        halo_token: "halo0000".to_string(),
+        halo_router: "router0000".to_string(),
+        reward_asset: native("uaura"),
+        epoch_length: 100,
+    };
+    instantiate(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+}
+
+fn stake_msg(staker: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: staker.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_json_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    })
+}
+
+fn set_rewards(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, amount: u128) {
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin::new(amount, "uaura")],
+    )]);
+}
+
+#[test]
+fn update_bridge() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps);
+
+    let msg = ExecuteMsg::UpdateBridge {
+        asset_info: token("asset0000"),
+        bridge: Some(vec![native("uluna")]),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+
+    // the bridge cannot loop back to the fee asset or pass through the reward asset
+    for bridge in [
+        vec![token("asset0000")],
+        vec![native("uaura")],
+        vec![native("uluna"), native("uluna")],
+    ] {
+        let msg = ExecuteMsg::UpdateBridge {
+            asset_info: token("asset0000"),
+            bridge: Some(bridge),
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env_at(START),
+                mock_info("owner0000", &[]),
+                msg
+            ),
+            Err(ContractError::InvalidBridge {})
+        );
+    }
+
+    let too_long_msg = ExecuteMsg::UpdateBridge {
+        asset_info: token("asset0000"),
+        bridge: Some(vec![
+            native("uluna"),
+            native("uusd"),
+            native("ukrw"),
+            native("ueur"),
+        ]),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("owner0000", &[]),
+            too_long_msg
+        ),
+        Err(ContractError::BridgeTooLong { max: 3 })
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_bridge(deps.as_ref(), token("asset0000"))
+            .unwrap()
+            .bridge,
+        vec![native("uluna")]
+    );
+
+    let msg = ExecuteMsg::UpdateBridge {
+        asset_info: token("asset0000"),
+        bridge: None,
+    };
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_bridge(deps.as_ref(), token("asset0000"))
+            .unwrap()
+            .bridge,
+        vec![]
+    );
+}
+
+#[test]
+fn convert_fees() {
+    let mut deps = mock_dependencies(&[Coin::new(1000, "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(500u128))],
+        ),
+        (
+            &"halo0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(150u128))],
+        ),
+    ]);
+    init(&mut deps);
+
+    let msg = ExecuteMsg::UpdateBridge {
+        asset_info: token("asset0000"),
+        bridge: Some(vec![native("uluna")]),
+    };
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 100),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Convert {
+        assets: vec![convert_asset(native("uaura"), 1)],
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("owner0000", &[]),
+            msg
+        ),
+        Err(ContractError::CannotConvertRewardAsset {})
+    );
+
+    let msg = ExecuteMsg::Convert {
+        assets: vec![convert_asset(native("ukrw"), 1)],
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("owner0000", &[]),
+            msg
+        ),
+        Err(ContractError::NothingToConvert {})
+    );
+
+    // only the owner converts the fees, the swaps could be sandwiched otherwise
+    let msg = ExecuteMsg::Convert {
+        assets: vec![
+            convert_asset(native("uusd"), 900),
+            convert_asset(token("asset0000"), 450),
+            convert_asset(token("halo0000"), 40),
+        ],
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("anyone", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+
+    // the owner converts with the minimum amounts to receive, the staked halo is left out
+    let res = execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "router0000".to_string(),
+                msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::HaloSwap {
+                        offer_asset_info: native("uusd"),
+                        ask_asset_info: native("uaura"),
+                    }],
+                    minimum_receive: Some(Uint128::from(900u128)),
+                    to: None,
+                    referral_address: None,
+                    referral_commission: None,
+                    callback: None,
+                })
+                .unwrap(),
+                funds: vec![Coin::new(1000, "uusd")],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "router0000".to_string(),
+                    amount: Uint128::from(500u128),
+                    msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: vec![
+                            SwapOperation::HaloSwap {
+                                offer_asset_info: token("asset0000"),
+                                ask_asset_info: native("uluna"),
+                            },
+                            SwapOperation::HaloSwap {
+                                offer_asset_info: native("uluna"),
+                                ask_asset_info: native("uaura"),
+                            },
+                        ],
+                        minimum_receive: Some(Uint128::from(450u128)),
+                        to: None,
+                        referral_address: None,
+                        referral_commission: None,
+                        callback: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "halo0000".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "router0000".to_string(),
+                    amount: Uint128::from(50u128),
+                    msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: vec![SwapOperation::HaloSwap {
+                            offer_asset_info: token("halo0000"),
+                            ask_asset_info: native("uaura"),
+                        }],
+                        minimum_receive: Some(Uint128::from(40u128)),
+                        to: None,
+                        referral_address: None,
+                        referral_commission: None,
+                        callback: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn distribute_by_epoch() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps);
+
+    // only the halo token can be staked
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("asset0000", &[]),
+            stake_msg("addr0000", 100)
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 100),
+    )
+    .unwrap();
+
+    // nothing is staked in the first epoch, its rewards are kept for the next one
+    set_rewards(&mut deps, 1000);
+    let state = query_state(deps.as_ref(), env_at(START + 100)).unwrap();
+    assert_eq!(state.epoch, 1);
+    assert_eq!(state.total_staked, Uint128::from(100u128));
+    assert_eq!(state.global_index, Decimal256::zero());
+
+    execute(
+        deps.as_mut(),
+        env_at(START + 150),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0001", 300),
+    )
+    .unwrap();
+
+    // the stake of the second epoch gets its rewards
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 200),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin::new(1000, "uaura")],
+        }))]
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 200),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim {},
+        ),
+        Err(ContractError::NothingToClaim {})
+    );
+
+    // the third epoch is shared by both stakes
+    set_rewards(&mut deps, 800);
+    assert_eq!(
+        query_staker(deps.as_ref(), env_at(START + 300), "addr0000".to_string()).unwrap(),
+        StakerResponse {
+            address: "addr0000".to_string(),
+            staked: Uint128::from(100u128),
+            next_epoch_staked: Uint128::zero(),
+            pending_rewards: Uint128::from(200u128),
+        }
+    );
+    assert_eq!(
+        query_staker(deps.as_ref(), env_at(START + 300), "addr0001".to_string()).unwrap(),
+        StakerResponse {
+            address: "addr0001".to_string(),
+            staked: Uint128::from(300u128),
+            next_epoch_staked: Uint128::zero(),
+            pending_rewards: Uint128::from(600u128),
+        }
+    );
+
+    // the stake waiting for the next epoch is returned first
+    execute(
+        deps.as_mut(),
+        env_at(START + 300),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 50),
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Unstake {
+        amount: Uint128::from(151u128),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 300),
+            mock_info("addr0000", &[]),
+            msg
+        ),
+        Err(ContractError::InsufficientStake {})
+    );
+
+    let msg = ExecuteMsg::Unstake {
+        amount: Uint128::from(120u128),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 300),
+        mock_info("addr0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "halo0000".to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(120u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let state = query_state(deps.as_ref(), env_at(START + 300)).unwrap();
+    assert_eq!(state.total_staked, Uint128::from(330u128));
+    assert_eq!(state.next_epoch_staked, Uint128::zero());
+    assert_eq!(state.unclaimed_rewards, Uint128::from(800u128));
+}
@@ -0,0 +1,466 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Api, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ExecuteMsg;
+
+use crate::error::ContractError;
+use crate::state::{
+    read_gauges, Config, EpochInfo, GaugeInfo, GaugeVoteRaw, VoteInfo, CONFIG, EPOCH, EPOCHS,
+    GAUGES, VOTES,
+};
+
+use haloswap::asset::{is_token_factory_denom, Asset, AssetInfo};
+use haloswap::gauge::{
+    ConfigResponse, EpochResponse, ExecuteMsg, GaugeResponse, GaugeVote, GaugesResponse,
+    InstantiateMsg, MigrateMsg, QueryMsg, VoteResponse,
+};
+use haloswap::migration::assert_migrate_version;
+use haloswap::querier::{query_staking_pool, query_voting_power_at_height};
+use haloswap::staking::{Cw20HookMsg as StakingCw20HookMsg, ExecuteMsg as StakingExecuteMsg};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:halo-gauge";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The pools a voter can split the voting power across
+const MAX_VOTES: usize = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.epoch_length == 0 {
+        return Err(ContractError::InvalidEpochLength {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            gov_staking: deps.api.addr_canonicalize(&msg.gov_staking)?,
+            lp_staking: deps.api.addr_canonicalize(&msg.lp_staking)?,
+            reward_asset: msg.reward_asset.to_raw(deps.api)?,
+            emission_per_epoch: msg.emission_per_epoch,
+            epoch_length: msg.epoch_length,
+        },
+    )?;
+
+    start_epoch(deps, &env, 0)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            emission_per_epoch,
+            epoch_length,
+        } => update_config(deps, info, owner, emission_per_epoch, epoch_length),
+        ExecuteMsg::Vote { votes } => vote(deps, env, info, votes),
+        ExecuteMsg::Finalize {} => finalize(deps, env),
+    }
+}
+
+// Only owner can execute it
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    emission_per_epoch: Option<Uint128>,
+    epoch_length: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    if let Some(emission_per_epoch) = emission_per_epoch {
+        config.emission_per_epoch = emission_per_epoch;
+    }
+
+    if let Some(epoch_length) = epoch_length {
+        if epoch_length == 0 {
+            return Err(ContractError::InvalidEpochLength {});
+        }
+
+        config.epoch_length = epoch_length;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    votes: Vec<GaugeVote>,
+) -> Result<Response, ContractError> {
+    if votes.len() > MAX_VOTES {
+        return Err(ContractError::TooManyVotes { max: MAX_VOTES });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let epoch = EPOCH.load(deps.storage)?;
+    let mut epoch_info = EPOCHS.load(deps.storage, epoch)?;
+    if env.block.time.seconds() >= epoch_info.start_time + config.epoch_length {
+        return Err(ContractError::EpochEnded {});
+    }
+
+    // only the pools of the LP staking contract, registered for the pairs of the factory
+    let lp_staking = deps.api.addr_humanize(&config.lp_staking)?;
+    let mut total_weight = Decimal::zero();
+    let mut votes_raw: Vec<GaugeVoteRaw> = vec![];
+    for vote in votes.iter() {
+        if vote.weight.is_zero() {
+            return Err(ContractError::InvalidWeight {});
+        }
+
+        let lp_token_raw = Binary::from(gauge_key(deps.api, &vote.lp_token)?);
+        query_staking_pool(&deps.querier, lp_staking.clone(), &vote.lp_token)?;
+
+        if votes_raw.iter().any(|vote| vote.lp_token == lp_token_raw) {
+            return Err(ContractError::DuplicateVote {});
+        }
+
+        total_weight = total_weight.checked_add(vote.weight)?;
+        votes_raw.push(GaugeVoteRaw {
+            lp_token: lp_token_raw,
+            weight: vote.weight,
+        });
+    }
+
+    if total_weight > Decimal::one() {
+        return Err(ContractError::InvalidWeight {});
+    }
+
+    // the power is read at the start of the epoch, so it cannot be moved around during the votes
+    let power = query_voting_power_at_height(
+        &deps.querier,
+        deps.api.addr_humanize(&config.gov_staking)?,
+        &info.sender,
+        epoch_info.start_height,
+    )?;
+    if power.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    // the new votes replace the previous votes of the epoch
+    let voter_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if let Some(previous) = VOTES.may_load(deps.storage, (epoch, &voter_raw))? {
+        for vote in previous.votes {
+            let weight = previous.power.mul_floor(vote.weight);
+            let mut gauge = GAUGES.load(deps.storage, (epoch, &vote.lp_token))?;
+            gauge.weight = gauge.weight.checked_sub(weight)?;
+            epoch_info.total_weight = epoch_info.total_weight.checked_sub(weight)?;
+
+            if gauge.weight.is_zero() {
+                GAUGES.remove(deps.storage, (epoch, &vote.lp_token));
+            } else {
+                GAUGES.save(deps.storage, (epoch, &vote.lp_token), &gauge)?;
+            }
+        }
+    }
+
+    for vote in votes_raw.iter() {
+        let weight = power.mul_floor(vote.weight);
+        let mut gauge = GAUGES
+            .may_load(deps.storage, (epoch, &vote.lp_token))?
+            .unwrap_or_default();
+        gauge.weight = gauge.weight.checked_add(weight)?;
+        epoch_info.total_weight = epoch_info.total_weight.checked_add(weight)?;
+
+        GAUGES.save(deps.storage, (epoch, &vote.lp_token), &gauge)?;
+    }
+
+    if votes_raw.is_empty() {
+        VOTES.remove(deps.storage, (epoch, &voter_raw));
+    } else {
+        VOTES.save(
+            deps.storage,
+            (epoch, &voter_raw),
+            &VoteInfo {
+                power,
+                votes: votes_raw,
+            },
+        )?;
+    }
+    EPOCHS.save(deps.storage, epoch, &epoch_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "vote"),
+        ("voter", info.sender.as_str()),
+        ("epoch", &epoch.to_string()),
+        ("power", &power.to_string()),
+    ]))
+}
+
+/// Anyone can finalize the ended epoch
+pub fn finalize(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let epoch = EPOCH.load(deps.storage)?;
+    let mut epoch_info = EPOCHS.load(deps.storage, epoch)?;
+
+    let now = env.block.time.seconds();
+    if now < epoch_info.start_time + config.epoch_length {
+        return Err(ContractError::EpochNotEnded {});
+    }
+
+    // the emission is capped by the reward left to the gauge
+    let reward_asset = config.reward_asset.to_normal(deps.api)?;
+    let budget = reward_asset
+        .query_pool(&deps.querier, deps.api, env.contract.address.clone())?
+        .min(config.emission_per_epoch);
+
+    let lp_staking = deps.api.addr_humanize(&config.lp_staking)?;
+    let gauges = GAUGES
+        .prefix(epoch)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Vec<u8>, GaugeInfo)>>>()?;
+
+    // the allocations are emitted over the next epoch
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !epoch_info.total_weight.is_zero() {
+        for (lp_token, mut gauge) in gauges {
+            gauge.allocation = budget.multiply_ratio(gauge.weight, epoch_info.total_weight);
+            if gauge.allocation.is_zero() {
+                continue;
+            }
+
+            GAUGES.save(deps.storage, (epoch, &lp_token), &gauge)?;
+            epoch_info.emission = epoch_info.emission.checked_add(gauge.allocation)?;
+            messages.push(add_schedule_msg(
+                &lp_staking,
+                gauge_lp_token(deps.api, &lp_token)?,
+                Asset {
+                    info: reward_asset.clone(),
+                    amount: gauge.allocation,
+                },
+                now,
+                now + config.epoch_length,
+            )?);
+        }
+    }
+
+    epoch_info.finalized = true;
+    EPOCHS.save(deps.storage, epoch, &epoch_info)?;
+    start_epoch(deps, &env, epoch + 1)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "finalize"),
+        ("epoch", &epoch.to_string()),
+        ("emission", &epoch_info.emission.to_string()),
+    ]))
+}
+
+fn start_epoch(deps: DepsMut, env: &Env, epoch: u64) -> StdResult<()> {
+    EPOCH.save(deps.storage, &epoch)?;
+    EPOCHS.save(
+        deps.storage,
+        epoch,
+        &EpochInfo {
+            start_time: env.block.time.seconds(),
+            start_height: env.block.height,
+            total_weight: Uint128::zero(),
+            finalized: false,
+            emission: Uint128::zero(),
+        },
+    )
+}
+
+/// The key of the gauge, the canonical address of the liquidity token or the denom of the
+/// native liquidity shares, as the pool key of the LP staking contract
+fn gauge_key(api: &dyn Api, lp_token: &str) -> StdResult<Vec<u8>> {
+    if is_token_factory_denom(lp_token) {
+        Ok(lp_token.as_bytes().to_vec())
+    } else {
+        Ok(api.addr_canonicalize(lp_token)?.to_vec())
+    }
+}
+
+/// The liquidity token of the gauge key
+fn gauge_lp_token(api: &dyn Api, key: &[u8]) -> StdResult<String> {
+    match std::str::from_utf8(key) {
+        Ok(denom) if is_token_factory_denom(denom) => Ok(denom.to_string()),
+        _ => Ok(api.addr_humanize(&CanonicalAddr::from(key))?.to_string()),
+    }
+}
+
+fn add_schedule_msg(
+    lp_staking: &Addr,
+    lp_token: String,
+    reward: Asset,
+    start_time: u64,
+    end_time: u64,
+) -> StdResult<CosmosMsg> {
+    match reward.info {
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: lp_staking.to_string(),
+                amount: reward.amount,
+                msg: to_json_binary(&StakingCw20HookMsg::AddSchedule {
+                    lp_token: lp_token.clone(),
+                    start_time,
+                    end_time,
+                })?,
+            })?,
+            funds: vec![],
+        })),
+        AssetInfo::NativeToken { ref denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: lp_staking.to_string(),
+            msg: to_json_binary(&StakingExecuteMsg::AddSchedule {
+                lp_token,
+                reward: reward.clone(),
+                start_time,
+                end_time,
+            })?,
+            funds: vec![Coin {
+                denom: denom.to_string(),
+                amount: reward.amount,
+            }],
+        })),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Epoch {} => to_json_binary(&query_epoch(deps)?),
+        QueryMsg::Vote { voter, epoch } => to_json_binary(&query_vote(deps, voter, epoch)?),
+        QueryMsg::Gauges {
+            epoch,
+            start_after,
+            limit,
+        } => to_json_binary(&query_gauges(deps, epoch, start_after, limit)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        gov_staking: deps.api.addr_humanize(&config.gov_staking)?.to_string(),
+        lp_staking: deps.api.addr_humanize(&config.lp_staking)?.to_string(),
+        reward_asset: config.reward_asset.to_normal(deps.api)?,
+        emission_per_epoch: config.emission_per_epoch,
+        epoch_length: config.epoch_length,
+    })
+}
+
+pub fn query_epoch(deps: Deps) -> StdResult<EpochResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let epoch = EPOCH.load(deps.storage)?;
+    let epoch_info = EPOCHS.load(deps.storage, epoch)?;
+
+    Ok(EpochResponse {
+        epoch,
+        start_time: epoch_info.start_time,
+        start_height: epoch_info.start_height,
+        end_time: epoch_info.start_time + config.epoch_length,
+    })
+}
+
+pub fn query_vote(deps: Deps, voter: String, epoch: Option<u64>) -> StdResult<VoteResponse> {
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => EPOCH.load(deps.storage)?,
+    };
+
+    let vote_info = VOTES
+        .may_load(deps.storage, (epoch, &deps.api.addr_canonicalize(&voter)?))?
+        .unwrap_or_default();
+
+    Ok(VoteResponse {
+        voter,
+        epoch,
+        power: vote_info.power,
+        votes: vote_info
+            .votes
+            .into_iter()
+            .map(|vote| {
+                Ok(GaugeVote {
+                    lp_token: gauge_lp_token(deps.api, &vote.lp_token)?,
+                    weight: vote.weight,
+                })
+            })
+            .collect::<StdResult<Vec<GaugeVote>>>()?,
+    })
+}
+
+pub fn query_gauges(
+    deps: Deps,
+    epoch: Option<u64>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<GaugesResponse> {
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => EPOCH.load(deps.storage)?,
+    };
+    let epoch_info = EPOCHS.load(deps.storage, epoch)?;
+
+    let start_after = start_after
+        .map(|lp_token| gauge_key(deps.api, &lp_token))
+        .transpose()?;
+
+    let gauges = read_gauges(deps.storage, epoch, start_after, limit)?
+        .into_iter()
+        .map(|(lp_token, gauge)| {
+            Ok(GaugeResponse {
+                lp_token: gauge_lp_token(deps.api, &lp_token)?,
+                weight: gauge.weight,
+                allocation: gauge.allocation,
+            })
+        })
+        .collect::<StdResult<Vec<GaugeResponse>>>()?;
+
+    Ok(GaugesResponse {
+        epoch,
+        total_weight: epoch_info.total_weight,
+        finalized: epoch_info.finalized,
+        emission: epoch_info.emission,
+        gauges,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
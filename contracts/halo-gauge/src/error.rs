@@ -0,0 +1,35 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Epoch length must not be zero")]
+    InvalidEpochLength {},
+
+    #[error("Cannot vote for more than {max} pools")]
+    TooManyVotes { max: usize },
+
+    #[error("Vote weights must not be zero and must sum up to one at most")]
+    InvalidWeight {},
+
+    #[error("Duplicate pool in the votes")]
+    DuplicateVote {},
+
+    #[error("No voting power at the start of the epoch")]
+    NoVotingPower {},
+
+    #[error("The epoch has ended, it must be finalized")]
+    EpochEnded {},
+
+    #[error("The epoch has not ended")]
+    EpochNotEnded {},
+}
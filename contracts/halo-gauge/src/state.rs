@@ -0,0 +1,77 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, CanonicalAddr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
+use haloswap::asset::AssetInfoRaw;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub gov_staking: CanonicalAddr,
+    pub lp_staking: CanonicalAddr,
+    pub reward_asset: AssetInfoRaw,
+    pub emission_per_epoch: Uint128,
+    pub epoch_length: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// the epoch open to the votes
+pub const EPOCH: Item<u64> = Item::new("epoch");
+
+#[cw_serde]
+pub struct EpochInfo {
+    pub start_time: u64,
+    pub start_height: u64,
+    pub total_weight: Uint128,
+    pub finalized: bool,
+    pub emission: Uint128,
+}
+
+// key : epoch
+pub const EPOCHS: Map<u64, EpochInfo> = Map::new("epoch_info");
+
+#[cw_serde]
+pub struct GaugeVoteRaw {
+    // the key of the pool in the LP staking contract
+    pub lp_token: Binary,
+    pub weight: Decimal,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct VoteInfo {
+    pub power: Uint128,
+    pub votes: Vec<GaugeVoteRaw>,
+}
+
+// key : (epoch, voter)
+pub const VOTES: Map<(u64, &[u8]), VoteInfo> = Map::new("vote_info");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct GaugeInfo {
+    pub weight: Uint128,
+    pub allocation: Uint128,
+}
+
+// key : (epoch, lp token key)
+pub const GAUGES: Map<(u64, &[u8]), GaugeInfo> = Map::new("gauge_info");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_gauges(
+    storage: &dyn Storage,
+    epoch: u64,
+    start_after: Option<Vec<u8>>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(Vec<u8>, GaugeInfo)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::ExclusiveRaw);
+
+    GAUGES
+        .prefix(epoch)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
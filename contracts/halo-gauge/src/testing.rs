@@ -0,0 +1,466 @@
+use crate::contract::{execute, instantiate, query_epoch, query_gauges, query_vote};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+use cosmwasm_std::{
+    from_json, to_json_binary, Coin, ContractResult, CosmosMsg, Decimal, DepsMut, Env,
+    MemoryStorage, OwnedDeps, StdError, SubMsg, SystemError, SystemResult, Timestamp, Uint128,
+    WasmMsg, WasmQuery,
+};
+use haloswap::asset::{Asset, AssetInfo};
+use haloswap::gauge::{
+    EpochResponse, ExecuteMsg, GaugeResponse, GaugeVote, GaugesResponse, InstantiateMsg,
+    VoteResponse,
+};
+use haloswap::gov_staking::{QueryMsg as GovStakingQueryMsg, VotingPowerAtHeightResponse};
+use haloswap::staking::{
+    ExecuteMsg as StakingExecuteMsg, PoolResponse, QueryMsg as StakingQueryMsg,
+};
+
+const START: u64 = 1_600_000_000;
+const HEIGHT: u64 = 100;
+
+fn env_at(height: u64, seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn mock_deps() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+
+    // the voting power at the start of the first epoch and the pools of the staking contract
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } => match contract_addr.as_str() {
+            "gov0000" => match from_json(msg).unwrap() {
+                GovStakingQueryMsg::VotingPowerAtHeight { address, height } => {
+                    let power = match (address.as_str(), height) {
+                        ("addr0000", Some(HEIGHT)) => 100u128,
+                        ("addr0001", Some(HEIGHT)) => 300u128,
+                        _ => 0u128,
+                    };
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&VotingPowerAtHeightResponse {
+                            power: Uint128::from(power),
+                            height: height.unwrap(),
+                        })
+                        .unwrap(),
+                    ))
+                }
+                _ => panic!("DO NOT ENTER HERE"),
+            },
+            "staking0000" => match from_json(msg).unwrap() {
+                StakingQueryMsg::Pool { lp_token } => match lp_token.as_str() {
+                    "liquidity0000" | "liquidity0001" | "factory/pair0002/uLP" => {
+                        SystemResult::Ok(ContractResult::Ok(
+                            to_json_binary(&PoolResponse {
+                                lp_token,
+                                pair_contract: "pair0000".to_string(),
+                                total_bond_amount: Uint128::zero(),
+                                last_updated: START,
+                                rewards: vec![],
+                            })
+                            .unwrap(),
+                        ))
+                    }
+                    _ => SystemResult::Ok(ContractResult::Err("Pool not found".to_string())),
+                },
+                _ => panic!("DO NOT ENTER HERE"),
+            },
+            _ => SystemResult::Err(SystemError::NoSuchContract {
+                addr: contract_addr.to_string(),
+            }),
+        },
+        _ => panic!("DO NOT ENTER HERE"),
+    });
+
+    deps
+}
+
+fn init(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        gov_staking: "gov0000".to_string(),
+        lp_staking: "staking0000".to_string(),
+        reward_asset: AssetInfo::NativeToken {
+            denom: "uaura".to_string(),
+        },
+        emission_per_epoch: Uint128::from(1000u128),
+        epoch_length: 100,
+    };
+    instantiate(
+        deps,
+        env_at(HEIGHT, START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+}
+
+fn vote_msg(votes: &[(&str, &str)]) -> ExecuteMsg {
+    ExecuteMsg::Vote {
+        votes: votes
+            .iter()
+            .map(|(lp_token, weight)| GaugeVote {
+                lp_token: lp_token.to_string(),
+                weight: weight.parse().unwrap(),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn vote_and_replace_votes() {
+    let mut deps = mock_deps();
+    init(deps.as_mut());
+
+    let too_many_votes = ExecuteMsg::Vote {
+        votes: vec![
+            GaugeVote {
+                lp_token: "liquidity0000".to_string(),
+                weight: Decimal::percent(1),
+            };
+            11
+        ],
+    };
+    for (msg, err) in [
+        (too_many_votes, ContractError::TooManyVotes { max: 10 }),
+        (
+            vote_msg(&[("liquidity0000", "0.6"), ("liquidity0001", "0.5")]),
+            ContractError::InvalidWeight {},
+        ),
+        (
+            vote_msg(&[("liquidity0000", "0")]),
+            ContractError::InvalidWeight {},
+        ),
+        (
+            vote_msg(&[("liquidity0000", "0.5"), ("liquidity0000", "0.5")]),
+            ContractError::DuplicateVote {},
+        ),
+    ] {
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env_at(HEIGHT + 1, START + 10),
+                mock_info("addr0000", &[]),
+                msg
+            ),
+            Err(err)
+        );
+    }
+
+    // only the pools of the staking contract can be voted for
+    match execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0000", &[]),
+        vote_msg(&[("liquidity0002", "1")]),
+    ) {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => {
+            assert!(msg.contains("Pool not found"))
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 1, START + 10),
+            mock_info("addr0002", &[]),
+            vote_msg(&[("liquidity0000", "1")])
+        ),
+        Err(ContractError::NoVotingPower {})
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0000", &[]),
+        vote_msg(&[("liquidity0000", "0.5"), ("liquidity0001", "0.5")]),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0001", &[]),
+        vote_msg(&[("liquidity0001", "1")]),
+    )
+    .unwrap();
+
+    // the new votes replace the previous ones
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 2, START + 20),
+        mock_info("addr0000", &[]),
+        vote_msg(&[("liquidity0000", "1")]),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_vote(deps.as_ref(), "addr0000".to_string(), None).unwrap(),
+        VoteResponse {
+            voter: "addr0000".to_string(),
+            epoch: 0,
+            power: Uint128::from(100u128),
+            votes: vec![GaugeVote {
+                lp_token: "liquidity0000".to_string(),
+                weight: Decimal::one(),
+            }],
+        }
+    );
+    assert_eq!(
+        query_gauges(deps.as_ref(), None, None, None).unwrap(),
+        GaugesResponse {
+            epoch: 0,
+            total_weight: Uint128::from(400u128),
+            finalized: false,
+            emission: Uint128::zero(),
+            gauges: vec![
+                GaugeResponse {
+                    lp_token: "liquidity0000".to_string(),
+                    weight: Uint128::from(100u128),
+                    allocation: Uint128::zero(),
+                },
+                GaugeResponse {
+                    lp_token: "liquidity0001".to_string(),
+                    weight: Uint128::from(300u128),
+                    allocation: Uint128::zero(),
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn finalize_epoch() {
+    let mut deps = mock_deps();
+    init(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0000", &[]),
+        vote_msg(&[("liquidity0000", "1")]),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0001", &[]),
+        vote_msg(&[("liquidity0001", "1")]),
+    )
+    .unwrap();
+
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 9, START + 99),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Finalize {}
+        ),
+        Err(ContractError::EpochNotEnded {})
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 10, START + 100),
+            mock_info("addr0000", &[]),
+            vote_msg(&[("liquidity0001", "1")])
+        ),
+        Err(ContractError::EpochEnded {})
+    );
+
+    // the emission is capped by the balance of the gauge
+    deps.querier
+        .update_balance(mock_env().contract.address, vec![Coin::new(800, "uaura")]);
+    let res = execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 10, START + 100),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Finalize {},
+    )
+    .unwrap();
+
+    let schedule_msg = |lp_token: &str, amount: u128| {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "staking0000".to_string(),
+            msg: to_json_binary(&StakingExecuteMsg::AddSchedule {
+                lp_token: lp_token.to_string(),
+                reward: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uaura".to_string(),
+                    },
+                    amount: Uint128::from(amount),
+                },
+                start_time: START + 100,
+                end_time: START + 200,
+            })
+            .unwrap(),
+            funds: vec![Coin::new(amount, "uaura")],
+        }))
+    };
+    assert_eq!(
+        res.messages,
+        vec![
+            schedule_msg("liquidity0000", 200),
+            schedule_msg("liquidity0001", 600),
+        ]
+    );
+
+    // the allocations stay queryable with the finalized epoch
+    let gauges = query_gauges(deps.as_ref(), Some(0), None, None).unwrap();
+    assert!(gauges.finalized);
+    assert_eq!(gauges.emission, Uint128::from(800u128));
+    assert_eq!(
+        gauges
+            .gauges
+            .iter()
+            .map(|gauge| gauge.allocation)
+            .collect::<Vec<Uint128>>(),
+        vec![Uint128::from(200u128), Uint128::from(600u128)]
+    );
+
+    // the next epoch reads the voting power at the block it started in
+    assert_eq!(
+        query_epoch(deps.as_ref()).unwrap(),
+        EpochResponse {
+            epoch: 1,
+            start_time: START + 100,
+            start_height: HEIGHT + 10,
+            end_time: START + 200,
+        }
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 11, START + 110),
+            mock_info("addr0000", &[]),
+            vote_msg(&[("liquidity0000", "1")])
+        ),
+        Err(ContractError::NoVotingPower {})
+    );
+    assert_eq!(
+        query_vote(deps.as_ref(), "addr0000".to_string(), Some(0))
+            .unwrap()
+            .power,
+        Uint128::from(100u128)
+    );
+}
+
+#[test]
+fn vote_for_native_liquidity_pool() {
+    let mut deps = mock_deps();
+    init(deps.as_mut());
+
+    // the pools of the native liquidity shares are keyed by their denom
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0000", &[]),
+        vote_msg(&[("factory/pair0002/uLP", "1")]),
+    )
+    .unwrap();
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 1, START + 10),
+            mock_info("addr0001", &[]),
+            vote_msg(&[("factory/pair0003/uLP", "1")])
+        ),
+        Err(ContractError::Std(StdError::generic_err(
+            "Querier contract error: Pool not found"
+        )))
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 1, START + 10),
+            mock_info("addr0001", &[]),
+            vote_msg(&[
+                ("factory/pair0002/uLP", "0.5"),
+                ("factory/pair0002/uLP", "0.5")
+            ])
+        ),
+        Err(ContractError::DuplicateVote {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0001", &[]),
+        vote_msg(&[("factory/pair0002/uLP", "0.5"), ("liquidity0000", "0.5")]),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_vote(deps.as_ref(), "addr0001".to_string(), None)
+            .unwrap()
+            .votes,
+        vec![
+            GaugeVote {
+                lp_token: "factory/pair0002/uLP".to_string(),
+                weight: Decimal::percent(50),
+            },
+            GaugeVote {
+                lp_token: "liquidity0000".to_string(),
+                weight: Decimal::percent(50),
+            },
+        ]
+    );
+
+    let gauges = query_gauges(deps.as_ref(), None, None, None).unwrap();
+    assert_eq!(gauges.total_weight, Uint128::from(400u128));
+    assert_eq!(
+        gauges.gauges,
+        vec![
+            GaugeResponse {
+                lp_token: "liquidity0000".to_string(),
+                weight: Uint128::from(150u128),
+                allocation: Uint128::zero(),
+            },
+            GaugeResponse {
+                lp_token: "factory/pair0002/uLP".to_string(),
+                weight: Uint128::from(250u128),
+                allocation: Uint128::zero(),
+            },
+        ]
+    );
+    assert_eq!(
+        query_gauges(deps.as_ref(), None, Some("liquidity0000".to_string()), None)
+            .unwrap()
+            .gauges,
+        vec![GaugeResponse {
+            lp_token: "factory/pair0002/uLP".to_string(),
+            weight: Uint128::from(250u128),
+            allocation: Uint128::zero(),
+        }]
+    );
+
+    // the schedule is added to the pool of the denom
+    deps.querier
+        .update_balance(mock_env().contract.address, vec![Coin::new(800, "uaura")]);
+    let res = execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 10, START + 100),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Finalize {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[1],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "staking0000".to_string(),
+            msg: to_json_binary(&StakingExecuteMsg::AddSchedule {
+                lp_token: "factory/pair0002/uLP".to_string(),
+                reward: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uaura".to_string(),
+                    },
+                    amount: Uint128::from(500u128),
+                },
+                start_time: START + 100,
+                end_time: START + 200,
+            })
+            .unwrap(),
+            funds: vec![Coin::new(500, "uaura")],
+        }))
+    );
+}
@@ -0,0 +1,466 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::state::{Config, StakerInfo, BOOSTS, CONFIG, STAKERS, TOTAL_STAKE, VOTING_POWER};
+
+use haloswap::asset::{Asset, AssetInfo};
+use haloswap::gov_staking::{
+    Claim, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    StakerResponse, TotalPowerAtHeightResponse, VotingPowerAtHeightResponse,
+};
+use haloswap::migration::assert_migrate_version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:halo-gov-staking";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            halo_token: deps.api.addr_canonicalize(&msg.halo_token)?,
+            unbonding_period: msg.unbonding_period,
+            max_lock_duration: msg.max_lock_duration,
+        },
+    )?;
+    TOTAL_STAKE.save(deps.storage, &Uint128::zero(), env.block.height)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            unbonding_period,
+            max_lock_duration,
+        } => update_config(deps, info, owner, unbonding_period, max_lock_duration),
+        ExecuteMsg::ExtendLock { lock_duration } => extend_lock(deps, env, info, lock_duration),
+        ExecuteMsg::ExpireLock { staker } => expire_lock(deps, env, staker),
+        ExecuteMsg::Unstake { amount } => unstake(deps, env, info, amount),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::Stake { lock_duration } => {
+            // only the halo token can be staked
+            let config = CONFIG.load(deps.storage)?;
+            if deps.api.addr_canonicalize(info.sender.as_str())? != config.halo_token {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let staker = deps.api.addr_validate(&cw20_msg.sender)?;
+            stake(deps, env, staker, cw20_msg.amount, lock_duration)
+        }
+    }
+}
+
+// Only owner can execute it
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    unbonding_period: Option<u64>,
+    max_lock_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+
+    // the boosts already set are kept, the new locks are boosted against the new duration
+    if let Some(max_lock_duration) = max_lock_duration {
+        config.max_lock_duration = max_lock_duration;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn stake(
+    deps: DepsMut,
+    env: Env,
+    staker: Addr,
+    amount: Uint128,
+    lock_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    let staker_raw = deps.api.addr_canonicalize(staker.as_str())?;
+    let mut staker_info = load_staker(deps.as_ref(), &staker_raw, now)?;
+
+    match lock_duration {
+        Some(lock_duration) => {
+            assert_lock_duration(&config, lock_duration)?;
+
+            // a new stake joins the lock and never shortens it
+            staker_info.locked = staker_info.locked.checked_add(amount)?;
+            staker_info.lock_end = staker_info.lock_end.max(now + lock_duration);
+            staker_info.set_boost(now, config.max_lock_duration);
+        }
+        None => staker_info.unlocked = staker_info.unlocked.checked_add(amount)?,
+    }
+
+    let voting_power = save_staker(deps, &env, &staker_raw, &staker_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "stake"),
+        ("staker", staker.as_str()),
+        ("amount", &amount.to_string()),
+        ("lock_end", &staker_info.lock_end.to_string()),
+        ("voting_power", &voting_power.to_string()),
+    ]))
+}
+
+pub fn extend_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lock_duration: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_lock_duration(&config, lock_duration)?;
+
+    let now = env.block.time.seconds();
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut staker_info = load_staker(deps.as_ref(), &staker_raw, now)?;
+    if staker_info.locked.is_zero() {
+        return Err(ContractError::NoLock {});
+    }
+
+    staker_info.lock_end = staker_info.lock_end.max(now + lock_duration);
+    staker_info.set_boost(now, config.max_lock_duration);
+    let voting_power = save_staker(deps, &env, &staker_raw, &staker_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "extend_lock"),
+        ("staker", info.sender.as_str()),
+        ("lock_end", &staker_info.lock_end.to_string()),
+        ("voting_power", &voting_power.to_string()),
+    ]))
+}
+
+/// Anyone can unlock the stake of an ended lock, its boost already stopped counting
+pub fn expire_lock(deps: DepsMut, env: Env, staker: String) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let staker_raw = deps.api.addr_canonicalize(&staker)?;
+    let staker_info = STAKERS
+        .may_load(deps.storage, &staker_raw)?
+        .unwrap_or_default();
+    if staker_info.locked.is_zero() || staker_info.lock_end > now {
+        return Err(ContractError::LockNotEnded {});
+    }
+
+    let staker_info = load_staker(deps.as_ref(), &staker_raw, now)?;
+    let voting_power = save_staker(deps, &env, &staker_raw, &staker_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "expire_lock"),
+        ("staker", staker.as_str()),
+        ("voting_power", &voting_power.to_string()),
+    ]))
+}
+
+pub fn unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut staker_info = load_staker(deps.as_ref(), &staker_raw, now)?;
+    if staker_info.unlocked < amount {
+        return Err(ContractError::InsufficientStake {});
+    }
+
+    // the unstaked amount stops voting at once and is claimable after the unbonding period
+    let release_at = now + config.unbonding_period;
+    staker_info.unlocked -= amount;
+    staker_info.claims.push(Claim { amount, release_at });
+    let voting_power = save_staker(deps, &env, &staker_raw, &staker_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "unstake"),
+        ("staker", info.sender.as_str()),
+        ("amount", &amount.to_string()),
+        ("release_at", &release_at.to_string()),
+        ("voting_power", &voting_power.to_string()),
+    ]))
+}
+
+pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut staker_info = load_staker(deps.as_ref(), &staker_raw, now)?;
+
+    let mut amount = Uint128::zero();
+    for claim in staker_info.claims.iter() {
+        if claim.release_at <= now {
+            amount = amount.checked_add(claim.amount)?;
+        }
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    staker_info.claims.retain(|claim| claim.release_at > now);
+
+    let halo_token = deps.api.addr_humanize(&config.halo_token)?;
+    save_staker(deps, &env, &staker_raw, &staker_info)?;
+
+    Ok(Response::new()
+        .add_message(
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: halo_token.to_string(),
+                },
+                amount,
+            }
+            .into_msg(info.sender.clone())?,
+        )
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("staker", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+fn assert_lock_duration(config: &Config, lock_duration: u64) -> Result<(), ContractError> {
+    if config.max_lock_duration == 0 {
+        return Err(ContractError::LocksDisabled {});
+    }
+
+    if lock_duration == 0 || lock_duration > config.max_lock_duration {
+        return Err(ContractError::InvalidLockDuration {
+            max: config.max_lock_duration,
+        });
+    }
+
+    Ok(())
+}
+
+/// Load the staker with the stake of an ended lock unlocked
+fn load_staker(deps: Deps, staker: &[u8], now: u64) -> StdResult<StakerInfo> {
+    let mut staker_info = STAKERS.may_load(deps.storage, staker)?.unwrap_or_default();
+    if !staker_info.locked.is_zero() && staker_info.lock_end <= now {
+        staker_info.unlocked = staker_info.unlocked.checked_add(staker_info.locked)?;
+        staker_info.locked = Uint128::zero();
+        staker_info.lock_end = 0;
+        staker_info.boost = Uint128::zero();
+    }
+
+    Ok(staker_info)
+}
+
+/// Save the staker and record its voting power, the total stake
+/// and the boosts by lock end at the current block
+fn save_staker(
+    deps: DepsMut,
+    env: &Env,
+    staker: &[u8],
+    staker_info: &StakerInfo,
+) -> StdResult<Uint128> {
+    let height = env.block.height;
+    let voting_power = staker_info.voting_power()?;
+    let previous_power = VOTING_POWER
+        .may_load(deps.storage, staker)?
+        .unwrap_or_default();
+
+    if voting_power != previous_power {
+        let total_stake = TOTAL_STAKE.may_load(deps.storage)?.unwrap_or_default();
+        TOTAL_STAKE.save(
+            deps.storage,
+            &total_stake
+                .checked_sub(previous_power.stake)?
+                .checked_add(voting_power.stake)?,
+            height,
+        )?;
+
+        // the boost is moved from the previous lock end to the new one
+        if !previous_power.boost.is_zero() {
+            let boost = BOOSTS.load(deps.storage, previous_power.lock_end)?;
+            BOOSTS.save(
+                deps.storage,
+                previous_power.lock_end,
+                &boost.checked_sub(previous_power.boost)?,
+                height,
+            )?;
+        }
+        if !voting_power.boost.is_zero() {
+            let boost = BOOSTS
+                .may_load(deps.storage, voting_power.lock_end)?
+                .unwrap_or_default();
+            BOOSTS.save(
+                deps.storage,
+                voting_power.lock_end,
+                &boost.checked_add(voting_power.boost)?,
+                height,
+            )?;
+        }
+
+        VOTING_POWER.save(deps.storage, staker, &voting_power, height)?;
+    }
+
+    if staker_info.is_empty() {
+        STAKERS.remove(deps.storage, staker);
+    } else {
+        STAKERS.save(deps.storage, staker, staker_info)?;
+    }
+
+    voting_power.at(env.block.time.seconds())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Staker { address } => to_json_binary(&query_staker(deps, env, address)?),
+        QueryMsg::VotingPowerAtHeight { address, height } => {
+            to_json_binary(&query_voting_power_at_height(deps, env, address, height)?)
+        }
+        QueryMsg::TotalPowerAtHeight { height } => {
+            to_json_binary(&query_total_power_at_height(deps, env, height)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        halo_token: deps.api.addr_humanize(&config.halo_token)?.to_string(),
+        unbonding_period: config.unbonding_period,
+        max_lock_duration: config.max_lock_duration,
+    })
+}
+
+/// The staker as stored, the voting power is the recorded one without the boost of an ended lock
+pub fn query_staker(deps: Deps, env: Env, address: String) -> StdResult<StakerResponse> {
+    let staker_raw = deps.api.addr_canonicalize(&address)?;
+    let staker_info = STAKERS
+        .may_load(deps.storage, &staker_raw)?
+        .unwrap_or_default();
+
+    Ok(StakerResponse {
+        address,
+        unlocked: staker_info.unlocked,
+        locked: staker_info.locked,
+        lock_end: staker_info.lock_end,
+        voting_power: VOTING_POWER
+            .may_load(deps.storage, &staker_raw)?
+            .unwrap_or_default()
+            .at(env.block.time.seconds())?,
+        claims: staker_info.claims,
+    })
+}
+
+pub fn query_voting_power_at_height(
+    deps: Deps,
+    env: Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerAtHeightResponse> {
+    let height = height.unwrap_or(env.block.height);
+    let power = VOTING_POWER
+        .may_load_at_height(deps.storage, &deps.api.addr_canonicalize(&address)?, height)?
+        .unwrap_or_default()
+        .at(env.block.time.seconds())?;
+
+    Ok(VotingPowerAtHeightResponse { power, height })
+}
+
+pub fn query_total_power_at_height(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalPowerAtHeightResponse> {
+    let height = height.unwrap_or(env.block.height);
+    let mut power = TOTAL_STAKE
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default();
+
+    // only the locks not ended at the query time are boosted
+    let now = env.block.time.seconds();
+    for lock_end in BOOSTS.keys(
+        deps.storage,
+        Some(Bound::exclusive(now)),
+        None,
+        Order::Ascending,
+    ) {
+        let boost = BOOSTS
+            .may_load_at_height(deps.storage, lock_end?, height)?
+            .unwrap_or_default();
+        power = power.checked_add(boost)?;
+    }
+
+    Ok(TotalPowerAtHeightResponse { power, height })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
@@ -0,0 +1,35 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Locks are disabled")]
+    LocksDisabled {},
+
+    #[error("Lock duration must be between one second and {max} seconds")]
+    InvalidLockDuration { max: u64 },
+
+    #[error("Lock has not ended")]
+    LockNotEnded {},
+
+    #[error("No lock to extend")]
+    NoLock {},
+
+    #[error("Not enough unlocked stake")]
+    InsufficientStake {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
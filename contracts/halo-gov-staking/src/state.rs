@@ -0,0 +1,94 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, StdResult, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+use haloswap::gov_staking::Claim;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub halo_token: CanonicalAddr,
+    pub unbonding_period: u64,
+    pub max_lock_duration: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct StakerInfo {
+    pub unlocked: Uint128,
+    pub locked: Uint128,
+    pub lock_end: u64,
+    /// The boost of the lock, set when the stake is locked or the lock extended
+    /// and counted until the lock ends, it does not decay over time
+    pub boost: Uint128,
+    pub claims: Vec<Claim>,
+}
+
+impl StakerInfo {
+    /// Set the boost of the locked stake to the share of the longest lock it has left
+    pub fn set_boost(&mut self, now: u64, max_lock_duration: u64) {
+        let remaining = self.lock_end.saturating_sub(now).min(max_lock_duration);
+        self.boost = self.locked.multiply_ratio(remaining, max_lock_duration);
+    }
+
+    pub fn voting_power(&self) -> StdResult<VotingPower> {
+        Ok(VotingPower {
+            stake: self.unlocked.checked_add(self.locked)?,
+            boost: self.boost,
+            lock_end: self.lock_end,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unlocked.is_zero() && self.locked.is_zero() && self.claims.is_empty()
+    }
+}
+
+// key : staker
+pub const STAKERS: Map<&[u8], StakerInfo> = Map::new("staker_info");
+
+/// The voting power of a staker, the boost is kept apart as it ends with the lock
+#[cw_serde]
+#[derive(Default)]
+pub struct VotingPower {
+    pub stake: Uint128,
+    pub boost: Uint128,
+    pub lock_end: u64,
+}
+
+impl VotingPower {
+    /// The stake plus the boost of the lock if it has not ended at `now`,
+    /// an ended lock is not boosted even before it is expired
+    pub fn at(&self, now: u64) -> StdResult<Uint128> {
+        if self.lock_end <= now {
+            return Ok(self.stake);
+        }
+
+        Ok(self.stake.checked_add(self.boost)?)
+    }
+}
+
+// the voting power recorded at every block it changes in
+pub const VOTING_POWER: SnapshotMap<&[u8], VotingPower> = SnapshotMap::new(
+    "voting_power",
+    "voting_power__checkpoints",
+    "voting_power__changelog",
+    Strategy::EveryBlock,
+);
+
+pub const TOTAL_STAKE: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_stake",
+    "total_stake__checkpoints",
+    "total_stake__changelog",
+    Strategy::EveryBlock,
+);
+
+// key : lock end / value: the total boost of the locks ending at this time,
+// the ends left without boost are kept at zero for the past heights
+pub const BOOSTS: SnapshotMap<u64, Uint128> = SnapshotMap::new(
+    "boosts",
+    "boosts__checkpoints",
+    "boosts__changelog",
+    Strategy::EveryBlock,
+);
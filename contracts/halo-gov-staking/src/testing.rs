@@ -0,0 +1,421 @@
+use crate::contract::{
+    execute, instantiate, query_staker, query_total_power_at_height, query_voting_power_at_height,
+};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{to_json_binary, CosmosMsg, DepsMut, Env, SubMsg, Timestamp, Uint128, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use haloswap::gov_staking::{Claim, Cw20HookMsg, ExecuteMsg, InstantiateMsg, StakerResponse};
+
+const START: u64 = 1_600_000_000;
+const HEIGHT: u64 = 100;
+
+fn env_at(height: u64, seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn init(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        halo_token: "halo0000".to_string(),
+        unbonding_period: 100,
+        max_lock_duration: 1000,
+    };
+    instantiate(
+        deps,
+        env_at(HEIGHT, START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+}
+
+fn stake_msg(staker: &str, amount: u128, lock_duration: Option<u64>) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: staker.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_json_binary(&Cw20HookMsg::Stake { lock_duration }).unwrap(),
+    })
+}
+
+#[test]
+fn voting_power_snapshots() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    // only the halo token can be staked
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT, START),
+            mock_info("asset0000", &[]),
+            stake_msg("addr0000", 100, None)
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT, START),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 100, None),
+    )
+    .unwrap();
+
+    // a lock of half the longest duration boosts the locked stake by half
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 5),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 100, Some(500)),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 5),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0001", 300, None),
+    )
+    .unwrap();
+
+    // the power is recorded at the beginning of the blocks following the changes
+    for (height, power, total_power) in [
+        (HEIGHT, 0u128, 0u128),
+        (HEIGHT + 1, 100, 100),
+        (HEIGHT + 2, 250, 550),
+    ] {
+        assert_eq!(
+            query_voting_power_at_height(
+                deps.as_ref(),
+                env_at(HEIGHT + 2, START + 10),
+                "addr0000".to_string(),
+                Some(height),
+            )
+            .unwrap()
+            .power,
+            Uint128::from(power)
+        );
+        assert_eq!(
+            query_total_power_at_height(
+                deps.as_ref(),
+                env_at(HEIGHT + 2, START + 10),
+                Some(height)
+            )
+            .unwrap()
+            .power,
+            Uint128::from(total_power)
+        );
+    }
+
+    assert_eq!(
+        query_staker(
+            deps.as_ref(),
+            env_at(HEIGHT + 2, START + 10),
+            "addr0000".to_string()
+        )
+        .unwrap(),
+        StakerResponse {
+            address: "addr0000".to_string(),
+            unlocked: Uint128::from(100u128),
+            locked: Uint128::from(100u128),
+            lock_end: START + 505,
+            voting_power: Uint128::from(250u128),
+            claims: vec![],
+        }
+    );
+}
+
+#[test]
+fn lock_boost_does_not_decay() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT, START),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 100, Some(500)),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT, START),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0001", 100, Some(1000)),
+    )
+    .unwrap();
+
+    // a later stake and a new longest lock keep the boost set by the lock
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT, START),
+        mock_info("owner0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            owner: None,
+            unbonding_period: None,
+            max_lock_duration: Some(2000),
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 400),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 10, None),
+    )
+    .unwrap();
+    for (height, power, total_power) in [(HEIGHT + 1, 150u128, 350u128), (HEIGHT + 2, 160, 360)] {
+        assert_eq!(
+            query_voting_power_at_height(
+                deps.as_ref(),
+                env_at(HEIGHT + 2, START + 450),
+                "addr0000".to_string(),
+                Some(height),
+            )
+            .unwrap()
+            .power,
+            Uint128::from(power)
+        );
+        assert_eq!(
+            query_total_power_at_height(
+                deps.as_ref(),
+                env_at(HEIGHT + 2, START + 450),
+                Some(height)
+            )
+            .unwrap()
+            .power,
+            Uint128::from(total_power)
+        );
+    }
+
+    // the boost stops counting at the end of the lock, at any height, without expiring it
+    for height in [HEIGHT + 1, HEIGHT + 2] {
+        assert_eq!(
+            query_voting_power_at_height(
+                deps.as_ref(),
+                env_at(HEIGHT + 3, START + 500),
+                "addr0000".to_string(),
+                Some(height),
+            )
+            .unwrap()
+            .power,
+            Uint128::from(if height == HEIGHT + 1 { 100u128 } else { 110 })
+        );
+    }
+    assert_eq!(
+        query_total_power_at_height(deps.as_ref(), env_at(HEIGHT + 3, START + 500), None)
+            .unwrap()
+            .power,
+        Uint128::from(310u128)
+    );
+    assert_eq!(
+        query_staker(
+            deps.as_ref(),
+            env_at(HEIGHT + 3, START + 500),
+            "addr0000".to_string()
+        )
+        .unwrap()
+        .voting_power,
+        Uint128::from(110u128)
+    );
+
+    // expiring the lock unlocks the stake and keeps the power
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 3, START + 600),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::ExpireLock {
+            staker: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_voting_power_at_height(
+            deps.as_ref(),
+            env_at(HEIGHT + 4, START + 600),
+            "addr0000".to_string(),
+            None,
+        )
+        .unwrap()
+        .power,
+        Uint128::from(110u128)
+    );
+    assert_eq!(
+        query_total_power_at_height(deps.as_ref(), env_at(HEIGHT + 4, START + 600), None)
+            .unwrap()
+            .power,
+        Uint128::from(310u128)
+    );
+}
+
+#[test]
+fn lock_unstake_and_claim() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    for lock_duration in [0u64, 1001] {
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env_at(HEIGHT, START),
+                mock_info("halo0000", &[]),
+                stake_msg("addr0000", 100, Some(lock_duration))
+            ),
+            Err(ContractError::InvalidLockDuration { max: 1000 })
+        );
+    }
+
+    let msg = ExecuteMsg::ExtendLock { lock_duration: 500 };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT, START),
+            mock_info("addr0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::NoLock {})
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT, START),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 100, Some(200)),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT, START),
+        mock_info("halo0000", &[]),
+        stake_msg("addr0000", 50, None),
+    )
+    .unwrap();
+
+    // the locked stake cannot be unstaked
+    let unstake_msg = ExecuteMsg::Unstake {
+        amount: Uint128::from(60u128),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 1, START + 100),
+            mock_info("addr0000", &[]),
+            unstake_msg.clone()
+        ),
+        Err(ContractError::InsufficientStake {})
+    );
+
+    // the extended lock boosts the stake again
+    let res = execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 100),
+        mock_info("addr0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[2..],
+        [
+            ("lock_end", (START + 600).to_string()),
+            ("voting_power", "200".to_string()),
+        ]
+    );
+
+    let expire_msg = ExecuteMsg::ExpireLock {
+        staker: "addr0000".to_string(),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 2, START + 599),
+            mock_info("anyone", &[]),
+            expire_msg.clone()
+        ),
+        Err(ContractError::LockNotEnded {})
+    );
+
+    // anyone removes the boost of the ended lock
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 2, START + 600),
+        mock_info("anyone", &[]),
+        expire_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_voting_power_at_height(
+            deps.as_ref(),
+            env_at(HEIGHT + 3, START + 600),
+            "addr0000".to_string(),
+            None,
+        )
+        .unwrap()
+        .power,
+        Uint128::from(150u128)
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 3, START + 600),
+        mock_info("addr0000", &[]),
+        unstake_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_staker(
+            deps.as_ref(),
+            env_at(HEIGHT + 4, START + 600),
+            "addr0000".to_string()
+        )
+        .unwrap(),
+        StakerResponse {
+            address: "addr0000".to_string(),
+            unlocked: Uint128::from(90u128),
+            locked: Uint128::zero(),
+            lock_end: 0,
+            voting_power: Uint128::from(90u128),
+            claims: vec![Claim {
+                amount: Uint128::from(60u128),
+                release_at: START + 700,
+            }],
+        }
+    );
+    assert_eq!(
+        query_total_power_at_height(deps.as_ref(), env_at(HEIGHT + 4, START + 600), None)
+            .unwrap()
+            .power,
+        Uint128::from(90u128)
+    );
+
+    // the unstaked amount is claimable after the unbonding period
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 4, START + 699),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::Claim {}
+        ),
+        Err(ContractError::NothingToClaim {})
+    );
+    let res = execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 4, START + 700),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "halo0000".to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(60u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
@@ -0,0 +1,510 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Response, StdResult, Timestamp, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::Expiration;
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::state::{Ballot, Config, Proposal, BALLOTS, CONFIG, PROPOSALS, PROPOSAL_COUNT};
+
+use haloswap::governance::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, ProposalListResponse, ProposalResponse,
+    QueryMsg, Status, ThresholdResponse, Vote, VoteInfo, VoteListResponse, VoteResponse,
+    VoterResponse, Votes,
+};
+use haloswap::migration::assert_migrate_version;
+use haloswap::querier::{query_total_power_at_height, query_voting_power_at_height};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:halo-governance";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    assert_threshold(msg.threshold, msg.quorum)?;
+    if msg.voting_period == 0 {
+        return Err(ContractError::InvalidVotingPeriod {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            gov_staking: deps.api.addr_canonicalize(&msg.gov_staking)?,
+            threshold: msg.threshold,
+            quorum: msg.quorum,
+            voting_period: msg.voting_period,
+            timelock_period: msg.timelock_period,
+        },
+    )?;
+    PROPOSAL_COUNT.save(deps.storage, &0)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Propose {
+            title,
+            description,
+            msgs,
+        } => propose(deps, env, info, title, description, msgs),
+        ExecuteMsg::Vote { proposal_id, vote } => execute_vote(deps, env, info, proposal_id, vote),
+        ExecuteMsg::Execute { proposal_id } => execute_proposal(deps, env, proposal_id),
+        ExecuteMsg::Close { proposal_id } => close(deps, env, proposal_id),
+        ExecuteMsg::UpdateConfig {
+            threshold,
+            quorum,
+            voting_period,
+            timelock_period,
+        } => update_config(
+            deps,
+            env,
+            info,
+            threshold,
+            quorum,
+            voting_period,
+            timelock_period,
+        ),
+    }
+}
+
+fn assert_threshold(threshold: Decimal, quorum: Decimal) -> Result<(), ContractError> {
+    if threshold.is_zero()
+        || threshold > Decimal::one()
+        || quorum.is_zero()
+        || quorum > Decimal::one()
+    {
+        return Err(ContractError::InvalidThreshold {});
+    }
+
+    Ok(())
+}
+
+// Only the contract itself can execute it, through a proposal
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    threshold: Option<Decimal>,
+    quorum: Option<Decimal>,
+    voting_period: Option<u64>,
+    timelock_period: Option<u64>,
+) -> Result<Response, ContractError> {
+    // permission check
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(threshold) = threshold {
+        config.threshold = threshold;
+    }
+
+    if let Some(quorum) = quorum {
+        config.quorum = quorum;
+    }
+
+    if let Some(voting_period) = voting_period {
+        if voting_period == 0 {
+            return Err(ContractError::InvalidVotingPeriod {});
+        }
+
+        config.voting_period = voting_period;
+    }
+
+    if let Some(timelock_period) = timelock_period {
+        config.timelock_period = timelock_period;
+    }
+
+    assert_threshold(config.threshold, config.quorum)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn propose(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    msgs: Vec<CosmosMsg<Empty>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // the votes are weighted by the power at the beginning of the block the proposal is made in
+    let gov_staking = deps.api.addr_humanize(&config.gov_staking)?;
+    let start_height = env.block.height;
+    let power = query_voting_power_at_height(
+        &deps.querier,
+        gov_staking.clone(),
+        &info.sender,
+        start_height,
+    )?;
+    if power.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    let now = env.block.time.seconds();
+    let expires = now + config.voting_period;
+    let mut proposal = Proposal {
+        title,
+        description,
+        msgs,
+        proposer: deps.api.addr_canonicalize(info.sender.as_str())?,
+        status: Status::Open,
+        start_height,
+        expires,
+        executable_at: expires + config.timelock_period,
+        total_weight: query_total_power_at_height(&deps.querier, gov_staking, start_height)?,
+        threshold: config.threshold,
+        quorum: config.quorum,
+        votes: Votes {
+            yes: Uint128::zero(),
+            no: Uint128::zero(),
+            abstain: Uint128::zero(),
+            veto: Uint128::zero(),
+        },
+    };
+
+    // the proposer votes yes
+    proposal.add_vote(Vote::Yes, power)?;
+
+    let proposal_id = PROPOSAL_COUNT.load(deps.storage)? + 1;
+    PROPOSAL_COUNT.save(deps.storage, &proposal_id)?;
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    BALLOTS.save(
+        deps.storage,
+        (proposal_id, &proposal.proposer),
+        &Ballot {
+            vote: Vote::Yes,
+            weight: power,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "propose"),
+        ("sender", info.sender.as_str()),
+        ("proposal_id", &proposal_id.to_string()),
+        ("status", &format!("{:?}", proposal.status)),
+    ]))
+}
+
+pub fn execute_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    vote: Vote,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    if proposal.current_status(env.block.time.seconds()) != Status::Open {
+        return Err(ContractError::NotOpen {});
+    }
+
+    let voter_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if BALLOTS.has(deps.storage, (proposal_id, &voter_raw)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    let weight = query_voting_power_at_height(
+        &deps.querier,
+        deps.api.addr_humanize(&config.gov_staking)?,
+        &info.sender,
+        proposal.start_height,
+    )?;
+    if weight.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    proposal.add_vote(vote, weight)?;
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    BALLOTS.save(
+        deps.storage,
+        (proposal_id, &voter_raw),
+        &Ballot { vote, weight },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "vote"),
+        ("sender", info.sender.as_str()),
+        ("proposal_id", &proposal_id.to_string()),
+        ("vote", &format!("{:?}", vote)),
+        ("weight", &weight.to_string()),
+    ]))
+}
+
+/// Anyone can execute a passed proposal once the timelock has ended
+pub fn execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let mut proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    let now = env.block.time.seconds();
+    if proposal.current_status(now) != Status::Passed {
+        return Err(ContractError::NotPassed {});
+    }
+
+    if now < proposal.executable_at {
+        return Err(ContractError::Timelocked {
+            executable_at: proposal.executable_at,
+        });
+    }
+
+    proposal.status = Status::Executed;
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new()
+        .add_messages(proposal.msgs)
+        .add_attributes(vec![
+            ("action", "execute"),
+            ("proposal_id", &proposal_id.to_string()),
+        ]))
+}
+
+/// Anyone can close a proposal failed at the end of the votes
+pub fn close(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Response, ContractError> {
+    let mut proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    if proposal.status != Status::Open {
+        return Err(ContractError::WrongCloseStatus {});
+    }
+
+    match proposal.current_status(env.block.time.seconds()) {
+        Status::Open => return Err(ContractError::NotExpired {}),
+        Status::Rejected => {}
+        _ => return Err(ContractError::WrongCloseStatus {}),
+    }
+
+    proposal.status = Status::Rejected;
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "close"),
+        ("proposal_id", &proposal_id.to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Threshold {} => to_json_binary(&query_threshold(deps, env)?),
+        QueryMsg::Proposal { proposal_id } => {
+            to_json_binary(&query_proposal(deps, env, proposal_id)?)
+        }
+        QueryMsg::ListProposals { start_after, limit } => {
+            to_json_binary(&query_list_proposals(deps, env, start_after, limit)?)
+        }
+        QueryMsg::ReverseProposals {
+            start_before,
+            limit,
+        } => to_json_binary(&query_reverse_proposals(deps, env, start_before, limit)?),
+        QueryMsg::Vote { proposal_id, voter } => {
+            to_json_binary(&query_vote(deps, proposal_id, voter)?)
+        }
+        QueryMsg::ListVotes {
+            proposal_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query_list_votes(deps, proposal_id, start_after, limit)?),
+        QueryMsg::Voter { address } => to_json_binary(&query_voter(deps, env, address)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        gov_staking: deps.api.addr_humanize(&config.gov_staking)?.to_string(),
+        threshold: config.threshold,
+        quorum: config.quorum,
+        voting_period: config.voting_period,
+        timelock_period: config.timelock_period,
+    })
+}
+
+/// The threshold with the total power at the current block
+pub fn query_threshold(deps: Deps, env: Env) -> StdResult<ThresholdResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ThresholdResponse::ThresholdQuorum {
+        threshold: config.threshold,
+        quorum: config.quorum,
+        total_weight: query_total_power_at_height(
+            &deps.querier,
+            deps.api.addr_humanize(&config.gov_staking)?,
+            env.block.height,
+        )?,
+    })
+}
+
+pub fn query_proposal(deps: Deps, env: Env, proposal_id: u64) -> StdResult<ProposalResponse> {
+    let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    proposal_response(deps, &env, proposal_id, proposal)
+}
+
+pub fn query_list_proposals(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let proposals = PROPOSALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (proposal_id, proposal) = item?;
+            proposal_response(deps, &env, proposal_id, proposal)
+        })
+        .collect::<StdResult<Vec<ProposalResponse>>>()?;
+
+    Ok(ProposalListResponse { proposals })
+}
+
+pub fn query_reverse_proposals(
+    deps: Deps,
+    env: Env,
+    start_before: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_before.map(Bound::exclusive);
+
+    let proposals = PROPOSALS
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| {
+            let (proposal_id, proposal) = item?;
+            proposal_response(deps, &env, proposal_id, proposal)
+        })
+        .collect::<StdResult<Vec<ProposalResponse>>>()?;
+
+    Ok(ProposalListResponse { proposals })
+}
+
+fn proposal_response(
+    deps: Deps,
+    env: &Env,
+    proposal_id: u64,
+    proposal: Proposal,
+) -> StdResult<ProposalResponse> {
+    Ok(ProposalResponse {
+        id: proposal_id,
+        status: proposal.current_status(env.block.time.seconds()),
+        title: proposal.title,
+        description: proposal.description,
+        msgs: proposal.msgs,
+        expires: Expiration::AtTime(Timestamp::from_seconds(proposal.expires)),
+        threshold: ThresholdResponse::ThresholdQuorum {
+            threshold: proposal.threshold,
+            quorum: proposal.quorum,
+            total_weight: proposal.total_weight,
+        },
+        proposer: deps.api.addr_humanize(&proposal.proposer)?,
+        votes: proposal.votes,
+        executable_at: proposal.executable_at,
+    })
+}
+
+pub fn query_vote(deps: Deps, proposal_id: u64, voter: String) -> StdResult<VoteResponse> {
+    let vote = BALLOTS
+        .may_load(
+            deps.storage,
+            (proposal_id, &deps.api.addr_canonicalize(&voter)?),
+        )?
+        .map(|ballot| VoteInfo {
+            proposal_id,
+            voter,
+            vote: ballot.vote,
+            weight: ballot.weight,
+        });
+
+    Ok(VoteResponse { vote })
+}
+
+pub fn query_list_votes(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VoteListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|voter| deps.api.addr_canonicalize(&voter))
+        .transpose()?
+        .map(|voter| Bound::ExclusiveRaw(voter.to_vec()));
+
+    let votes = BALLOTS
+        .prefix(proposal_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (voter, ballot) = item?;
+            Ok(VoteInfo {
+                proposal_id,
+                voter: deps
+                    .api
+                    .addr_humanize(&CanonicalAddr::from(voter))?
+                    .to_string(),
+                vote: ballot.vote,
+                weight: ballot.weight,
+            })
+        })
+        .collect::<StdResult<Vec<VoteInfo>>>()?;
+
+    Ok(VoteListResponse { votes })
+}
+
+pub fn query_voter(deps: Deps, env: Env, address: String) -> StdResult<VoterResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let weight = query_voting_power_at_height(
+        &deps.querier,
+        deps.api.addr_humanize(&config.gov_staking)?,
+        &deps.api.addr_validate(&address)?,
+        env.block.height,
+    )?;
+
+    Ok(VoterResponse {
+        weight: if weight.is_zero() { None } else { Some(weight) },
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
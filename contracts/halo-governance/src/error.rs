@@ -0,0 +1,41 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Threshold and quorum must be greater than zero and at most one")]
+    InvalidThreshold {},
+
+    #[error("Voting period must not be zero")]
+    InvalidVotingPeriod {},
+
+    #[error("No voting power at the start of the votes")]
+    NoVotingPower {},
+
+    #[error("Proposal is not open")]
+    NotOpen {},
+
+    #[error("Already voted on this proposal")]
+    AlreadyVoted {},
+
+    #[error("Proposal has not passed")]
+    NotPassed {},
+
+    #[error("Proposal is timelocked until {executable_at}")]
+    Timelocked { executable_at: u64 },
+
+    #[error("Proposal voting period has not ended")]
+    NotExpired {},
+
+    #[error("Cannot close a passed, executed or rejected proposal")]
+    WrongCloseStatus {},
+}
@@ -0,0 +1,84 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, CosmosMsg, Decimal, Empty, StdResult, Uint128};
+use cw_storage_plus::{Item, Map};
+use haloswap::governance::{Status, Vote, Votes};
+
+#[cw_serde]
+pub struct Config {
+    pub gov_staking: CanonicalAddr,
+    pub threshold: Decimal,
+    pub quorum: Decimal,
+    pub voting_period: u64,
+    pub timelock_period: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+
+#[cw_serde]
+pub struct Proposal {
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub proposer: CanonicalAddr,
+    pub status: Status,
+    /// The voting power is read at the beginning of this block
+    pub start_height: u64,
+    pub expires: u64,
+    pub executable_at: u64,
+    pub total_weight: Uint128,
+    pub threshold: Decimal,
+    pub quorum: Decimal,
+    pub votes: Votes,
+}
+
+impl Proposal {
+    /// The status with the votes counted once they have ended
+    pub fn current_status(&self, now: u64) -> Status {
+        if self.status == Status::Open && now >= self.expires {
+            if self.is_passed() {
+                return Status::Passed;
+            }
+
+            return Status::Rejected;
+        }
+
+        self.status
+    }
+
+    /// Enough of the total power voted and enough of the votes, abstentions left out, are yes
+    fn is_passed(&self) -> bool {
+        let total_votes = self.votes.yes + self.votes.no + self.votes.abstain + self.votes.veto;
+        if total_votes.is_zero() || total_votes < self.total_weight.mul_ceil(self.quorum) {
+            return false;
+        }
+
+        let opinions = total_votes - self.votes.abstain;
+        !self.votes.yes.is_zero() && self.votes.yes >= opinions.mul_ceil(self.threshold)
+    }
+
+    pub fn add_vote(&mut self, vote: Vote, weight: Uint128) -> StdResult<()> {
+        let count = match vote {
+            Vote::Yes => &mut self.votes.yes,
+            Vote::No => &mut self.votes.no,
+            Vote::Abstain => &mut self.votes.abstain,
+            Vote::Veto => &mut self.votes.veto,
+        };
+        *count = count.checked_add(weight)?;
+
+        Ok(())
+    }
+}
+
+// key : proposal id
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+
+#[cw_serde]
+pub struct Ballot {
+    pub vote: Vote,
+    pub weight: Uint128,
+}
+
+// key : (proposal id, voter)
+pub const BALLOTS: Map<(u64, &[u8]), Ballot> = Map::new("ballots");
@@ -0,0 +1,295 @@
+use crate::contract::{execute, instantiate, query_config, query_list_votes, query_proposal};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+use cosmwasm_std::{
+    from_json, to_json_binary, BankMsg, Coin, ContractResult, CosmosMsg, Decimal, DepsMut, Env,
+    MemoryStorage, OwnedDeps, SubMsg, SystemResult, Timestamp, Uint128, WasmQuery,
+};
+use haloswap::gov_staking::{
+    QueryMsg as GovStakingQueryMsg, TotalPowerAtHeightResponse, VotingPowerAtHeightResponse,
+};
+use haloswap::governance::{ExecuteMsg, InstantiateMsg, Status, Vote, Votes};
+
+const START: u64 = 1_600_000_000;
+const HEIGHT: u64 = 100;
+
+fn env_at(height: u64, seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn mock_deps() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+
+    // the voting power of the stakers of the governance staking contract
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { msg, .. } => match from_json(msg).unwrap() {
+            GovStakingQueryMsg::VotingPowerAtHeight { address, height } => {
+                let power = match address.as_str() {
+                    "addr0000" => 600u128,
+                    "addr0001" => 400u128,
+                    _ => 0u128,
+                };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&VotingPowerAtHeightResponse {
+                        power: Uint128::from(power),
+                        height: height.unwrap(),
+                    })
+                    .unwrap(),
+                ))
+            }
+            GovStakingQueryMsg::TotalPowerAtHeight { height } => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&TotalPowerAtHeightResponse {
+                        power: Uint128::from(1000u128),
+                        height: height.unwrap(),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => panic!("DO NOT ENTER HERE"),
+        },
+        _ => panic!("DO NOT ENTER HERE"),
+    });
+
+    deps
+}
+
+fn init(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        gov_staking: "gov0000".to_string(),
+        threshold: Decimal::percent(50),
+        quorum: Decimal::percent(30),
+        voting_period: 100,
+        timelock_period: 50,
+    };
+    instantiate(deps, env_at(HEIGHT, START), mock_info("addr0000", &[]), msg).unwrap();
+}
+
+fn propose_msg(msgs: Vec<CosmosMsg>) -> ExecuteMsg {
+    ExecuteMsg::Propose {
+        title: "title".to_string(),
+        description: "description".to_string(),
+        msgs,
+    }
+}
+
+#[test]
+fn propose_vote_and_execute() {
+    let mut deps = mock_deps();
+    init(deps.as_mut());
+
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT, START),
+            mock_info("addr0002", &[]),
+            propose_msg(vec![])
+        ),
+        Err(ContractError::NoVotingPower {})
+    );
+
+    // the first proposal is rejected by the no votes
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT, START),
+        mock_info("addr0001", &[]),
+        propose_msg(vec![]),
+    )
+    .unwrap();
+    let vote_msg = |vote: Vote| ExecuteMsg::Vote {
+        proposal_id: 1,
+        vote,
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 1, START + 10),
+            mock_info("addr0001", &[]),
+            vote_msg(Vote::No)
+        ),
+        Err(ContractError::AlreadyVoted {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0000", &[]),
+        vote_msg(Vote::No),
+    )
+    .unwrap();
+
+    // the second proposal passes with the abstentions left out of the threshold
+    let transfer_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: "addr0002".to_string(),
+        amount: vec![Coin::new(100, "uaura")],
+    });
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT, START),
+        mock_info("addr0000", &[]),
+        propose_msg(vec![transfer_msg.clone()]),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 1, START + 10),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Vote {
+            proposal_id: 2,
+            vote: Vote::Abstain,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 2, START + 99),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Close { proposal_id: 1 }
+        ),
+        Err(ContractError::NotExpired {})
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 2, START + 99),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Execute { proposal_id: 2 }
+        ),
+        Err(ContractError::NotPassed {})
+    );
+
+    // the votes end with the voting period
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 3, START + 100),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 2,
+                vote: Vote::No,
+            }
+        ),
+        Err(ContractError::NotOpen {})
+    );
+    let proposal = query_proposal(deps.as_ref(), env_at(HEIGHT + 3, START + 100), 2).unwrap();
+    assert_eq!(proposal.status, Status::Passed);
+    assert_eq!(
+        proposal.votes,
+        Votes {
+            yes: Uint128::from(600u128),
+            no: Uint128::zero(),
+            abstain: Uint128::from(400u128),
+            veto: Uint128::zero(),
+        }
+    );
+
+    // a passed proposal waits for the timelock
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 3, START + 149),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Execute { proposal_id: 2 }
+        ),
+        Err(ContractError::Timelocked {
+            executable_at: START + 150
+        })
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT + 3, START + 149),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Close { proposal_id: 2 }
+        ),
+        Err(ContractError::WrongCloseStatus {})
+    );
+    let res = execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 4, START + 150),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Execute { proposal_id: 2 },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(transfer_msg)]);
+    assert_eq!(
+        query_proposal(deps.as_ref(), env_at(HEIGHT + 4, START + 150), 2)
+            .unwrap()
+            .status,
+        Status::Executed
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(HEIGHT + 4, START + 150),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Close { proposal_id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        query_proposal(deps.as_ref(), env_at(HEIGHT + 4, START + 150), 1)
+            .unwrap()
+            .status,
+        Status::Rejected
+    );
+    assert_eq!(
+        query_list_votes(deps.as_ref(), 1, None, None)
+            .unwrap()
+            .votes
+            .into_iter()
+            .map(|vote| (vote.voter, vote.vote))
+            .collect::<Vec<(String, Vote)>>(),
+        vec![
+            ("addr0000".to_string(), Vote::No),
+            ("addr0001".to_string(), Vote::Yes),
+        ]
+    );
+}
+
+#[test]
+fn update_config_through_proposal() {
+    let mut deps = mock_deps();
+    init(deps.as_mut());
+
+    let msg = ExecuteMsg::UpdateConfig {
+        threshold: Some(Decimal::percent(60)),
+        quorum: None,
+        voting_period: None,
+        timelock_period: Some(0),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(HEIGHT, START),
+            mock_info("addr0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+
+    let env = env_at(HEIGHT, START);
+    let contract = env.contract.address.to_string();
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(&contract, &[]),
+            ExecuteMsg::UpdateConfig {
+                threshold: None,
+                quorum: Some(Decimal::zero()),
+                voting_period: None,
+                timelock_period: None,
+            }
+        ),
+        Err(ContractError::InvalidThreshold {})
+    );
+    execute(deps.as_mut(), env, mock_info(&contract, &[]), msg).unwrap();
+
+    let config = query_config(deps.as_ref()).unwrap();
+    assert_eq!(config.threshold, Decimal::percent(60));
+    assert_eq!(config.timelock_period, 0);
+}
@@ -1,25 +1,34 @@
 use crate::error::ContractError;
-use crate::state::PAIR_INFO;
+use crate::state::{Config, CONFIG, PAIR_INFO, PERMIT_NONCES};
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    from_json, to_json_binary, Addr, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut,
     Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
 
 use bignumber::{Decimal256, Uint256};
 use cw2::set_contract_version;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
-use cw_utils::parse_reply_instantiate_data;
-use haloswap::asset::{Asset, AssetInfo, PairInfo, PairInfoRaw};
+use cw_utils::{must_pay, parse_reply_instantiate_data};
+use haloswap::asset::{
+    Asset, AssetInfo, CreatePairRequirements, PairInfo, PairInfoRaw, SwapCallback,
+};
+use haloswap::migration::{assert_migrate_version, Version};
 use haloswap::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse, QueryMsg,
-    ReverseSimulationResponse, SimulationResponse,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, LiquidityPermit, LiquidityTokenKind,
+    MigrateMsg, PauseFlags, PoolResponse, ProvideLiquidityResponseData, QueryMsg,
+    ReverseSimulationResponse, SimulationResponse, SwapResponseData, WithdrawLiquidityResponseData,
+    LIQUIDITY_SUBDENOM, LIQUIDITY_TOKEN_SALT,
 };
 use haloswap::querier::query_token_info;
-use haloswap::token::InstantiateMsg as TokenInstantiateMsg;
+use haloswap::token::{
+    verify_permit_signature, ExecuteMsg as TokenExecuteMsg, InstantiateMarketingInfo,
+    InstantiateMsg as TokenInstantiateMsg, Permit, PermitInfoResponse,
+};
+use haloswap::token_factory::{burn_msg, create_denom_msg, mint_msg, token_factory_denom};
 use integer_sqrt::IntegerSquareRoot;
 use std::cmp::Ordering;
 use std::str::FromStr;
@@ -30,17 +39,39 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const INSTANTIATE_REPLY_ID: u64 = 1;
 
-/// Commission rate == 0.3%
+/// Default commission rate == 0.3%
 const COMMISSION_RATE: &str = "0.003";
+
+/// The names of the cw20 tokens are 3-50 bytes
+const LP_NAME_MAX_LENGTH: usize = 50;
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    // the instantiator is the factory of the pair
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            factory: deps.api.addr_canonicalize(info.sender.as_str())?,
+            commission_rate: Decimal::from_str(COMMISSION_RATE)?,
+            pause: PauseFlags::default(),
+        },
+    )?;
+
+    // the native liquidity shares are minted by the pair without a liquidity token contract
+    let liquidity_denom = match msg.liquidity_token_kind.unwrap_or_default() {
+        LiquidityTokenKind::Cw20 => None,
+        LiquidityTokenKind::Native => Some(token_factory_denom(
+            env.contract.address.as_str(),
+            LIQUIDITY_SUBDENOM,
+        )),
+    };
+
     let pair_info: &PairInfoRaw = &PairInfoRaw {
         contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
         liquidity_token: CanonicalAddr::from(vec![]),
@@ -50,17 +81,39 @@ pub fn instantiate(
         ],
         asset_decimals: msg.asset_decimals,
         requirements: msg.requirements,
+        creator: None,
+        liquidity_denom: liquidity_denom.clone(),
     };
 
     PAIR_INFO.save(deps.storage, pair_info)?;
 
+    if let Some(liquidity_denom) = liquidity_denom {
+        return Ok(Response::new()
+            .add_message(create_denom_msg(
+                env.contract.address.as_str(),
+                LIQUIDITY_SUBDENOM,
+            ))
+            .add_attribute("liquidity_denom", liquidity_denom));
+    }
+
+    // name the LP token after the assets of the pair
+    let pair_symbol = format!(
+        "{}-{}",
+        msg.asset_infos[0].query_symbol(&deps.querier)?,
+        msg.asset_infos[1].query_symbol(&deps.querier)?
+    );
+    let mut lp_name = format!("{}-LP", pair_symbol);
+    while lp_name.len() > LP_NAME_MAX_LENGTH {
+        lp_name.pop();
+    }
+
     Ok(Response::new().add_submessage(SubMsg {
-        // Create LP token
-        msg: WasmMsg::Instantiate {
+        // Create LP token at an address predictable from the pair address
+        msg: WasmMsg::Instantiate2 {
             admin: None,
             code_id: msg.token_code_id,
-            msg: to_binary(&TokenInstantiateMsg {
-                name: "halo liquidity token".to_string(),
+            msg: to_json_binary(&TokenInstantiateMsg {
+                name: lp_name,
                 symbol: "uLP".to_string(),
                 decimals: 6,
                 initial_balances: vec![],
@@ -68,9 +121,19 @@ pub fn instantiate(
                     minter: env.contract.address.to_string(),
                     cap: None,
                 }),
+                marketing: Some(InstantiateMarketingInfo {
+                    project: None,
+                    description: Some(format!(
+                        "Haloswap liquidity token of the {} pair",
+                        pair_symbol
+                    )),
+                    marketing: None,
+                    logo: None,
+                }),
             })?,
             funds: vec![],
             label: "lp".to_string(),
+            salt: Binary::from(LIQUIDITY_TOKEN_SALT),
         }
         .into(),
         gas_limit: None,
@@ -92,7 +155,38 @@ pub fn execute(
             assets,
             slippage_tolerance,
             receiver,
-        } => provide_liquidity(deps, env, info, assets, slippage_tolerance, receiver),
+        } => {
+            let provider = info.sender.clone();
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                provider,
+                assets,
+                slippage_tolerance,
+                receiver,
+                None,
+            )
+        }
+        ExecuteMsg::ProvideLiquidityWithPermits {
+            liquidity_permit,
+            permits,
+        } => provide_liquidity_with_permits(deps, env, info, liquidity_permit, permits),
+        ExecuteMsg::WithdrawLiquidity {} => {
+            let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+            let liquidity_denom = pair_info.liquidity_denom.ok_or_else(|| {
+                StdError::generic_err("the liquidity token is withdrawn with the cw20 hook")
+            })?;
+
+            let amount = must_pay(&info, &liquidity_denom)?;
+            let sender_addr = info.sender.clone();
+            withdraw_liquidity(deps, env, info, sender_addr, amount)
+        }
+        ExecuteMsg::UpdatePairConfig {
+            requirements,
+            commission_rate,
+            pause,
+        } => update_pair_config(deps, info, requirements, commission_rate, pause),
         // ExecuteMsg::Swap {
         //     offer_asset,
         //     belief_price,
@@ -131,11 +225,12 @@ pub fn receive_cw20(
 ) -> Result<Response, ContractError> {
     let contract_addr = info.sender.clone();
 
-    match from_binary(&cw20_msg.msg) {
+    match from_json(&cw20_msg.msg) {
         Ok(Cw20HookMsg::Swap {
             belief_price,
             max_spread,
             to,
+            callback,
         }) => {
             // only asset contract can execute this message
             let mut authorized: bool = false;
@@ -160,6 +255,13 @@ pub fn receive_cw20(
                 None
             };
 
+            if let Some(callback) = &callback {
+                if to_addr.is_some() {
+                    return Err(ContractError::ReceiverWithCallback {});
+                }
+                deps.api.addr_validate(&callback.contract)?;
+            }
+
             swap(
                 deps,
                 env,
@@ -174,6 +276,7 @@ pub fn receive_cw20(
                 belief_price,
                 max_spread,
                 to_addr,
+                callback,
             )
         }
         Ok(Cw20HookMsg::WithdrawLiquidity {}) => {
@@ -189,6 +292,51 @@ pub fn receive_cw20(
     }
 }
 
+// Only the factory can execute it
+pub fn update_pair_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    requirements: Option<CreatePairRequirements>,
+    commission_rate: Option<Decimal>,
+    pause: Option<PauseFlags>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.factory {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(requirements) = requirements {
+        PAIR_INFO.update(deps.storage, |mut pair_info| -> StdResult<_> {
+            pair_info.requirements = requirements;
+            Ok(pair_info)
+        })?;
+    }
+
+    if let Some(commission_rate) = commission_rate {
+        if commission_rate >= Decimal::one() {
+            return Err(ContractError::InvalidCommissionRate {});
+        }
+
+        config.commission_rate = commission_rate;
+    }
+
+    if let Some(pause) = pause {
+        config.pause = pause;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_pair_config"),
+        ("commission_rate", &config.commission_rate.to_string()),
+        ("swap_paused", &config.pause.swap.to_string()),
+        (
+            "provide_liquidity_paused",
+            &config.pause.provide_liquidity.to_string(),
+        ),
+    ]))
+}
+
 /// This just stores the result for future query
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
@@ -205,19 +353,27 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
 }
 
 /// CONTRACT - should approve contract to use the amount of token
+/// The provider deposits the tokens, the native tokens are sent with the message
+#[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    provider: Addr,
     assets: [Asset; 2],
     slippage_tolerance: Option<Decimal>,
     receiver: Option<String>,
+    min_share: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     for asset in assets.iter() {
         // check the balance of native token is sent with the message
         asset.assert_sent_native_token_balance(&info)?;
     }
 
+    if CONFIG.load(deps.storage)?.pause.provide_liquidity {
+        return Err(ContractError::ProvideLiquidityPaused {});
+    }
+
     // get information of the pair
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
 
@@ -250,8 +406,8 @@ pub fn provide_liquidity(
         if let AssetInfo::Token { contract_addr, .. } = &pool.info {
             messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: contract_addr.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
-                    owner: info.sender.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: provider.to_string(),
                     recipient: env.contract.address.to_string(),
                     amount: deposits[i],
                 })?,
@@ -267,17 +423,14 @@ pub fn provide_liquidity(
     // if the user provides the slippage tolerance, we should check it
     assert_slippage_tolerance(&slippage_tolerance, &deposits, &pools)?;
 
-    // get the address of the LP token
-    let liquidity_token = deps.api.addr_humanize(&pair_info.liquidity_token)?;
-
     // get total supply of the LP token
-    let total_share = query_token_info(&deps.querier, liquidity_token)?.total_supply;
+    let total_share = query_total_share(deps.as_ref(), &pair_info)?;
 
     // calculate the amount of LP token is minted to the user
     let share = if total_share == Uint128::zero() {
         // when pool is empty
-        // if the sender is not in whitelist of requirements, then return error
-        if !pair_info.requirements.whitelist.contains(&info.sender) {
+        // if the provider is not in whitelist of requirements, then return error
+        if !pair_info.requirements.whitelist.contains(&provider) {
             return Err(ContractError::Std(StdError::generic_err(
                 "the sender is not in whitelist",
             )));
@@ -313,30 +466,126 @@ pub fn provide_liquidity(
         return Err(ContractError::InvalidZeroAmount {});
     }
 
-    // mint LP token to sender
-    // if the user provides the receiver, mint LP token to the receiver else mint to the sender
-    let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
+    if min_share.is_some_and(|min_share| share < min_share) {
+        return Err(ContractError::MinimumShareAssertion {});
+    }
+
+    // mint LP token to provider
+    // if the user provides the receiver, mint LP token to the receiver else mint to the provider
+    let receiver = receiver.unwrap_or_else(|| provider.to_string());
 
     // mint amount of 'share' LP token to the receiver
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: deps
-            .api
-            .addr_humanize(&pair_info.liquidity_token)?
-            .to_string(),
-        msg: to_binary(&Cw20ExecuteMsg::Mint {
-            recipient: receiver.to_string(),
-            amount: share,
-        })?,
-        funds: vec![],
-    }));
-
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "provide_liquidity"),
-        ("sender", info.sender.as_str()),
-        ("receiver", receiver.as_str()),
-        ("assets", &format!("{}, {}", assets[0], assets[1])),
-        ("share", &share.to_string()),
-    ]))
+    messages.push(mint_share_msg(
+        deps.as_ref(),
+        &env,
+        &pair_info,
+        &receiver,
+        share,
+    )?);
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "provide_liquidity"),
+            ("sender", info.sender.as_str()),
+            ("receiver", receiver.as_str()),
+            ("assets", &format!("{}, {}", assets[0], assets[1])),
+            ("share", &share.to_string()),
+        ])
+        .set_data(to_json_binary(&ProvideLiquidityResponseData {
+            assets,
+            share,
+            receiver,
+        })?))
+}
+
+/// The deposit signed by the owner in the liquidity permit is provided from its tokens, the token
+/// permits signed by the owner set the allowances before the tokens are transferred from it.
+/// Anyone can relay them, the liquidity is minted to the receiver signed in the liquidity permit,
+/// else to the owner
+pub fn provide_liquidity_with_permits(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    liquidity_permit: LiquidityPermit,
+    permits: Vec<Permit>,
+) -> Result<Response, ContractError> {
+    let params = liquidity_permit.params;
+    if params.chain_id != env.block.chain_id || params.pair != env.contract.address.as_str() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Permit is not valid on this pair",
+        )));
+    }
+
+    if env.block.time.seconds() >= params.expires {
+        return Err(ContractError::Std(StdError::generic_err("Permit expired")));
+    }
+
+    let owner = deps.api.addr_validate(&params.owner)?;
+    let nonce = PERMIT_NONCES
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_default();
+    if params.nonce != nonce {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Invalid permit nonce, expected {}",
+            nonce
+        ))));
+    }
+
+    verify_permit_signature(
+        deps.api,
+        &params,
+        &params.owner,
+        &liquidity_permit.pubkey,
+        &liquidity_permit.signature,
+    )?;
+
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let asset_infos = [
+        pair_info.asset_infos[0].to_normal(deps.api)?,
+        pair_info.asset_infos[1].to_normal(deps.api)?,
+    ];
+
+    let mut permit_messages: Vec<CosmosMsg> = vec![];
+    for permit in permits {
+        // only the permits of the owner to the pair on the tokens of the pair are relayed
+        let token = AssetInfo::Token {
+            contract_addr: permit.params.token.clone(),
+        };
+        if permit.params.owner != owner.as_str()
+            || permit.params.spender != env.contract.address.as_str()
+            || !asset_infos
+                .iter()
+                .any(|asset_info| asset_info.equal(&token))
+        {
+            return Err(ContractError::InvalidPermit {});
+        }
+
+        permit_messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: permit.params.token.clone(),
+            msg: to_json_binary(&TokenExecuteMsg::Permit(permit))?,
+            funds: vec![],
+        }));
+    }
+
+    PERMIT_NONCES.save(deps.storage, &owner, &(nonce + 1))?;
+    let mut res = provide_liquidity(
+        deps,
+        env,
+        info,
+        owner,
+        params.assets,
+        None,
+        params.receiver,
+        Some(params.min_share),
+    )?;
+    res.messages = permit_messages
+        .into_iter()
+        .map(SubMsg::new)
+        .chain(res.messages)
+        .collect();
+
+    Ok(res.add_attribute("permit_nonce", nonce.to_string()))
 }
 
 pub fn withdraw_liquidity(
@@ -347,19 +596,22 @@ pub fn withdraw_liquidity(
     amount: Uint128,
 ) -> Result<Response, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
-    let liquidity_addr: Addr = deps.api.addr_humanize(&pair_info.liquidity_token)?;
 
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
-    let total_share: Uint128 = query_token_info(&deps.querier, liquidity_addr)?.total_supply;
+    let pools: [Asset; 2] =
+        pair_info.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
+    let total_share: Uint128 = query_total_share(deps.as_ref(), &pair_info)?;
 
     let share_ratio: Decimal = Decimal::from_ratio(amount, total_share);
-    let refund_assets: Vec<Asset> = pools
-        .iter()
-        .map(|a| Asset {
-            info: a.info.clone(),
-            amount: a.amount * share_ratio,
-        })
-        .collect();
+    let refund_assets: [Asset; 2] = [
+        Asset {
+            info: pools[0].info.clone(),
+            amount: pools[0].amount * share_ratio,
+        },
+        Asset {
+            info: pools[1].info.clone(),
+            amount: pools[1].amount * share_ratio,
+        },
+    ];
 
     // update pool info
     Ok(Response::new()
@@ -367,14 +619,7 @@ pub fn withdraw_liquidity(
             refund_assets[0].clone().into_msg(sender.clone())?,
             refund_assets[1].clone().into_msg(sender.clone())?,
             // burn liquidity token
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: deps
-                    .api
-                    .addr_humanize(&pair_info.liquidity_token)?
-                    .to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
-                funds: vec![],
-            }),
+            burn_share_msg(deps.as_ref(), &env, &pair_info, amount)?,
         ])
         .add_attributes(vec![
             ("action", "withdraw_liquidity"),
@@ -384,7 +629,75 @@ pub fn withdraw_liquidity(
                 "refund_assets",
                 &format!("{}, {}", refund_assets[0], refund_assets[1]),
             ),
-        ]))
+        ])
+        .set_data(to_json_binary(&WithdrawLiquidityResponseData {
+            withdrawn_share: amount,
+            refund_assets,
+            receiver: sender.to_string(),
+        })?))
+}
+
+/// The total supply of the liquidity token or of the native liquidity shares
+fn query_total_share(deps: Deps, pair_info: &PairInfoRaw) -> StdResult<Uint128> {
+    match &pair_info.liquidity_denom {
+        Some(denom) => Ok(deps.querier.query_supply(denom)?.amount),
+        None => Ok(query_token_info(
+            &deps.querier,
+            deps.api.addr_humanize(&pair_info.liquidity_token)?,
+        )?
+        .total_supply),
+    }
+}
+
+fn mint_share_msg(
+    deps: Deps,
+    env: &Env,
+    pair_info: &PairInfoRaw,
+    recipient: &str,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    match &pair_info.liquidity_denom {
+        Some(denom) => Ok(mint_msg(
+            env.contract.address.as_str(),
+            &Coin::new(amount.u128(), denom),
+            recipient,
+        )),
+        None => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps
+                .api
+                .addr_humanize(&pair_info.liquidity_token)?
+                .to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Mint {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        })),
+    }
+}
+
+/// Burn the shares withdrawn, which are held by the pair
+fn burn_share_msg(
+    deps: Deps,
+    env: &Env,
+    pair_info: &PairInfoRaw,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    match &pair_info.liquidity_denom {
+        Some(denom) => Ok(burn_msg(
+            env.contract.address.as_str(),
+            &Coin::new(amount.u128(), denom),
+            env.contract.address.as_str(),
+        )),
+        None => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps
+                .api
+                .addr_humanize(&pair_info.liquidity_token)?
+                .to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            funds: vec![],
+        })),
+    }
 }
 
 // CONTRACT - a user must do token approval
@@ -398,9 +711,15 @@ pub fn swap(
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
     to: Option<Addr>,
+    callback: Option<SwapCallback>,
 ) -> Result<Response, ContractError> {
     offer_asset.assert_sent_native_token_balance(&info)?;
 
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.pause.swap {
+        return Err(ContractError::SwapPaused {});
+    }
+
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
 
     let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
@@ -435,8 +754,12 @@ pub fn swap(
     }
 
     let offer_amount = offer_asset.amount;
-    let (return_amount, spread_amount, commission_amount) =
-        compute_swap(offer_pool.amount, ask_pool.amount, offer_amount);
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_amount,
+        config.commission_rate.into(),
+    );
 
     let return_asset = Asset {
         info: ask_pool.info.clone(),
@@ -454,39 +777,59 @@ pub fn swap(
         ask_decimal,
     )?;
 
-    let receiver = to.unwrap_or_else(|| sender.clone());
+    // the callback contract receives the return asset along with the callback message
+    let receiver = match &callback {
+        Some(callback) => Addr::unchecked(callback.contract.clone()),
+        None => to.unwrap_or_else(|| sender.clone()),
+    };
 
     let mut messages: Vec<CosmosMsg> = vec![];
     if !return_amount.is_zero() {
-        messages.push(return_asset.into_msg(receiver.clone())?);
+        messages.push(match callback {
+            Some(callback) => return_asset.into_callback_msg(callback)?,
+            None => return_asset.into_msg(receiver.clone())?,
+        });
     }
 
     // 1. send collateral token from the contract to a user
     // 2. send inactive commission to collector
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "swap"),
-        ("sender", sender.as_str()),
-        ("receiver", receiver.as_str()),
-        ("offer_asset", &offer_asset.info.to_string()),
-        ("ask_asset", &ask_pool.info.to_string()),
-        ("offer_amount", &offer_amount.to_string()),
-        ("return_amount", &return_amount.to_string()),
-        ("spread_amount", &spread_amount.to_string()),
-        ("commission_amount", &commission_amount.to_string()),
-    ]))
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "swap"),
+            ("sender", sender.as_str()),
+            ("receiver", receiver.as_str()),
+            ("offer_asset", &offer_asset.info.to_string()),
+            ("ask_asset", &ask_pool.info.to_string()),
+            ("offer_amount", &offer_amount.to_string()),
+            ("return_amount", &return_amount.to_string()),
+            ("spread_amount", &spread_amount.to_string()),
+            ("commission_amount", &commission_amount.to_string()),
+        ])
+        .set_data(to_json_binary(&SwapResponseData {
+            offer_asset: offer_asset.info,
+            ask_asset: ask_pool.info,
+            offer_amount,
+            return_amount,
+            spread_amount,
+            commission_amount,
+            receiver: receiver.to_string(),
+        })?))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::Pair {} => Ok(to_binary(&query_pair_info(deps)?)?),
-        QueryMsg::Pool {} => Ok(to_binary(&query_pool(deps)?)?),
+        QueryMsg::Pair {} => Ok(to_json_binary(&query_pair_info(deps)?)?),
+        QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
+        QueryMsg::Pool {} => Ok(to_json_binary(&query_pool(deps)?)?),
         QueryMsg::Simulation { offer_asset } => {
-            Ok(to_binary(&query_simulation(deps, offer_asset)?)?)
+            Ok(to_json_binary(&query_simulation(deps, offer_asset)?)?)
         }
         QueryMsg::ReverseSimulation { ask_asset } => {
-            Ok(to_binary(&query_reverse_simulation(deps, ask_asset)?)?)
+            Ok(to_json_binary(&query_reverse_simulation(deps, ask_asset)?)?)
         }
+        QueryMsg::PermitInfo { owner } => Ok(to_json_binary(&query_permit_info(deps, owner)?)?),
     }
 }
 
@@ -497,15 +840,31 @@ pub fn query_pair_info(deps: Deps) -> Result<PairInfo, ContractError> {
     Ok(pair_info)
 }
 
+pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        factory: deps.api.addr_humanize(&config.factory)?.to_string(),
+        commission_rate: config.commission_rate,
+        pause: config.pause,
+    })
+}
+
+pub fn query_permit_info(deps: Deps, owner: String) -> Result<PermitInfoResponse, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+
+    Ok(PermitInfoResponse {
+        nonce: PERMIT_NONCES
+            .may_load(deps.storage, &owner)?
+            .unwrap_or_default(),
+    })
+}
+
 pub fn query_pool(deps: Deps) -> Result<PoolResponse, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
     let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
     let assets: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
-    let total_share: Uint128 = query_token_info(
-        &deps.querier,
-        deps.api.addr_humanize(&pair_info.liquidity_token)?,
-    )?
-    .total_supply;
+    let total_share: Uint128 = query_total_share(deps, &pair_info)?;
 
     let resp = PoolResponse {
         assets,
@@ -536,8 +895,13 @@ pub fn query_simulation(
         return Err(ContractError::AssetMismatch {});
     }
 
-    let (return_amount, spread_amount, commission_amount) =
-        compute_swap(offer_pool.amount, ask_pool.amount, offer_asset.amount);
+    let commission_rate = CONFIG.load(deps.storage)?.commission_rate;
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_asset.amount,
+        commission_rate.into(),
+    );
 
     Ok(SimulationResponse {
         return_amount,
@@ -572,8 +936,13 @@ pub fn query_reverse_simulation(
     }
 
     // compute offer amount, spread amount, commission amount when user provide ask amount
-    let (offer_amount, spread_amount, commission_amount) =
-        compute_offer_amount(offer_pool.amount, ask_pool.amount, ask_asset.amount);
+    let commission_rate = CONFIG.load(deps.storage)?.commission_rate;
+    let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
+        offer_pool.amount,
+        ask_pool.amount,
+        ask_asset.amount,
+        commission_rate.into(),
+    )?;
 
     Ok(ReverseSimulationResponse {
         offer_amount,
@@ -596,14 +965,12 @@ fn compute_swap(
     offer_pool: Uint128,
     ask_pool: Uint128,
     offer_amount: Uint128,
+    commission_rate: Decimal256,
 ) -> (Uint128, Uint128, Uint128) {
     let offer_pool: Uint256 = Uint256::from(offer_pool);
     let ask_pool: Uint256 = ask_pool.into();
     let offer_amount: Uint256 = offer_amount.into();
 
-    // Commission rate OR Fee amount for framework
-    let commission_rate = Decimal256::from_str(COMMISSION_RATE).unwrap();
-
     // offer => ask
     // hoanm: EQUATION - B = (R_B - \frac{K}{R_A + A}) * (1 - F)
     // ask_amount = (ask_pool - cp / (offer_pool + offer_amount)) * (1 - commission_rate)
@@ -639,23 +1006,33 @@ fn test_compute_swap_with_huge_pool_variance() {
     let ask_pool = Uint128::from(317u128);
 
     assert_eq!(
-        compute_swap(offer_pool, ask_pool, Uint128::from(1u128)).0,
+        compute_swap(
+            offer_pool,
+            ask_pool,
+            Uint128::from(1u128),
+            Decimal256::from_str(COMMISSION_RATE).unwrap()
+        )
+        .0,
         Uint128::zero()
     );
 }
 
 // The function to get the offer_amount when user provide ask_amount
+// the ask amount before commission must stay below the ask pool
 fn compute_offer_amount(
     offer_pool: Uint128,
     ask_pool: Uint128,
     ask_amount: Uint128,
-) -> (Uint128, Uint128, Uint128) {
+    commission_rate: Decimal256,
+) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+    if offer_pool.is_zero() || ask_pool.is_zero() {
+        return Err(ContractError::InsufficientLiquidity {});
+    }
+
     let offer_pool: Uint256 = offer_pool.into();
     let ask_pool: Uint256 = ask_pool.into();
     let ask_amount: Uint256 = ask_amount.into();
 
-    let commission_rate = Decimal256::from_str(COMMISSION_RATE).unwrap();
-
     // EQUATION: A = \frac{K}{R_B - (B * (1-P))} - R_A
     // ask => offer
     // offer_amount = cp / (ask_pool - ask_amount / (1 - commission_rate)) - offer_pool
@@ -664,11 +1041,22 @@ fn compute_offer_amount(
     let one_minus_commission = Decimal256::one() - commission_rate;
     let inv_one_minus_commission = Decimal256::one() / one_minus_commission;
 
-    let offer_amount: Uint256 = Uint256::one()
-        .multiply_ratio(cp, ask_pool - ask_amount * inv_one_minus_commission)
-        - offer_pool;
+    // round up so that swapping the offer amount returns at least the ask amount
+    let mut before_commission_deduction: Uint256 = ask_amount * inv_one_minus_commission;
+    while before_commission_deduction * one_minus_commission < ask_amount {
+        before_commission_deduction += Uint256::one();
+    }
+    if before_commission_deduction >= ask_pool {
+        return Err(ContractError::InsufficientLiquidity {});
+    }
+
+    let remaining_ask_pool = ask_pool - before_commission_deduction;
+    let mut offer_amount: Uint256 = Uint256::one().multiply_ratio(cp, remaining_ask_pool);
+    if offer_amount * remaining_ask_pool < cp {
+        offer_amount += Uint256::one();
+    }
+    let offer_amount = offer_amount - offer_pool;
 
-    let before_commission_deduction: Uint256 = ask_amount * inv_one_minus_commission;
     let before_spread_deduction: Uint256 =
         offer_amount * Decimal256::from_ratio(ask_pool, offer_pool);
 
@@ -680,11 +1068,11 @@ fn compute_offer_amount(
 
     let commission_amount = before_commission_deduction * commission_rate;
 
-    (
+    Ok((
         offer_amount.into(),
         spread_amount.into(),
         commission_amount.into(),
-    )
+    ))
 }
 
 /// If `belief_price` and `max_spread` both are given,
@@ -791,8 +1179,136 @@ fn assert_slippage_tolerance(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // the migration steps run in the order of the versions introducing them,
+    // the pair storage is unchanged up to 0.2.0
+    if stored_version < Version::new(0, 3, 0) {
+        // the admin of the pairs created by the factory is the factory
+        let factory = match msg.factory {
+            Some(factory) => deps.api.addr_validate(&factory)?.to_string(),
+            None => deps
+                .querier
+                .query_wasm_contract_info(env.contract.address)?
+                .admin
+                .ok_or_else(|| {
+                    StdError::generic_err("the pair has no admin to record as its factory")
+                })?,
+        };
+        let commission_rate = match msg.commission_rate {
+            Some(commission_rate) => commission_rate,
+            None => Decimal::from_str(COMMISSION_RATE)?,
+        };
+        if commission_rate >= Decimal::one() {
+            return Err(ContractError::InvalidCommissionRate {});
+        }
+
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                factory: deps.api.addr_canonicalize(&factory)?,
+                commission_rate,
+                pause: PauseFlags::default(),
+            },
+        )?;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    Ok(Response::default())
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
+
+#[cfg(test)]
+mod simulation_round_trip {
+    use super::{compute_offer_amount, compute_swap, COMMISSION_RATE};
+    use crate::error::ContractError;
+    use bignumber::Decimal256;
+    use cosmwasm_std::Uint128;
+    use proptest::prelude::*;
+    use std::str::FromStr;
+
+    fn commission_rate() -> Decimal256 {
+        Decimal256::from_str(COMMISSION_RATE).unwrap()
+    }
+
+    proptest! {
+        // offering the reverse simulated amount returns at least the ask amount
+        #[test]
+        fn reverse_then_forward(
+            offer_pool in 1_000_000u128..1_000_000_000_000_000_000u128,
+            ask_pool in 1_000_000u128..1_000_000_000_000_000_000u128,
+            ask_permille in 1u128..900u128,
+        ) {
+            let ask_amount = Uint128::from(ask_pool * ask_permille / 1000);
+            let (offer_amount, _, _) = compute_offer_amount(
+                Uint128::from(offer_pool),
+                Uint128::from(ask_pool),
+                ask_amount,
+                commission_rate(),
+            )
+            .unwrap();
+
+            let (return_amount, _, _) = compute_swap(
+                Uint128::from(offer_pool),
+                Uint128::from(ask_pool),
+                offer_amount,
+                commission_rate(),
+            );
+            prop_assert!(return_amount >= ask_amount);
+        }
+
+        // reverse simulating the returned amount asks for the offer again,
+        // give or take the rounding of a couple of ask units at the pool price
+        #[test]
+        fn forward_then_reverse(
+            offer_pool in 1_000_000u128..1_000_000_000_000_000_000u128,
+            ask_pool in 1_000_000u128..1_000_000_000_000_000_000u128,
+            offer_permille in 1u128..10_000u128,
+        ) {
+            let offer_amount = Uint128::from(offer_pool * offer_permille / 1000);
+            let (return_amount, _, _) = compute_swap(
+                Uint128::from(offer_pool),
+                Uint128::from(ask_pool),
+                offer_amount,
+                commission_rate(),
+            );
+
+            let (reverse_offer_amount, _, _) = compute_offer_amount(
+                Uint128::from(offer_pool),
+                Uint128::from(ask_pool),
+                return_amount,
+                commission_rate(),
+            )
+            .unwrap();
+            let rounding = Uint128::from(2u128).multiply_ratio(
+                offer_pool + offer_amount.u128(),
+                ask_pool - return_amount.u128(),
+            ) + Uint128::from(2u128);
+            prop_assert!(reverse_offer_amount <= offer_amount + rounding);
+            prop_assert!(reverse_offer_amount + rounding >= offer_amount);
+        }
+
+        // asking for the whole ask pool or more is rejected
+        #[test]
+        fn reverse_exceeding_pool(
+            offer_pool in 0u128..1_000_000_000_000_000_000u128,
+            ask_pool in 0u128..1_000_000_000_000_000_000u128,
+            excess in 0u128..1_000_000_000u128,
+        ) {
+            prop_assert_eq!(
+                compute_offer_amount(
+                    Uint128::from(offer_pool),
+                    Uint128::from(ask_pool),
+                    Uint128::from(ask_pool + excess),
+                    commission_rate(),
+                ),
+                Err(ContractError::InsufficientLiquidity {})
+            );
+        }
+    }
 }
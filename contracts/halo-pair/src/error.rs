@@ -1,4 +1,5 @@
 use cosmwasm_std::{OverflowError, StdError};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -9,6 +10,9 @@ pub enum ContractError {
     #[error("{0}")]
     OverflowError(#[from] OverflowError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -23,4 +27,25 @@ pub enum ContractError {
 
     #[error("Asset mismatch")]
     AssetMismatch {},
+
+    #[error("Insufficient liquidity")]
+    InsufficientLiquidity {},
+
+    #[error("Cannot set both a receiver and a callback")]
+    ReceiverWithCallback {},
+
+    #[error("Commission rate must be lower than one")]
+    InvalidCommissionRate {},
+
+    #[error("Swap is paused")]
+    SwapPaused {},
+
+    #[error("Providing liquidity is paused")]
+    ProvideLiquidityPaused {},
+
+    #[error("Invalid permit")]
+    InvalidPermit {},
+
+    #[error("Minimum share assertion")]
+    MinimumShareAssertion {},
 }
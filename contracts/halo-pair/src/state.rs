@@ -1,4 +1,20 @@
-use cw_storage_plus::Item;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, CanonicalAddr, Decimal};
+use cw_storage_plus::{Item, Map};
 use haloswap::asset::PairInfoRaw;
+use haloswap::pair::PauseFlags;
 
 pub const PAIR_INFO: Item<PairInfoRaw> = Item::new("pair_info");
+
+#[cw_serde]
+pub struct Config {
+    /// The factory which is allowed to update the config
+    pub factory: CanonicalAddr,
+    pub commission_rate: Decimal,
+    pub pause: PauseFlags,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// key : owner, the nonce of the next liquidity permit of the owner
+pub const PERMIT_NONCES: Map<&Addr, u64> = Map::new("permit_nonce");
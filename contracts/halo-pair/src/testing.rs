@@ -1,23 +1,40 @@
 use crate::contract::{
-    assert_max_spread, execute, instantiate, query_pair_info, query_pool, reply, query_simulation, query_reverse_simulation
+    assert_max_spread, execute, instantiate, migrate, query_pair_info, query_pool, reply, query_simulation, query_reverse_simulation,
+    query_config, query_permit_info,
 };
 // use crate::contract::{query_reverse_simulation, query_simulation};
 use crate::error::ContractError;
+use crate::state::PAIR_INFO;
 use haloswap::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Reply, ReplyOn, Response, StdError,
+    attr, from_json, to_json_binary, to_json_vec, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Reply, ReplyOn, Response, StdError,
     SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
-use haloswap::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo};
-use haloswap::pair::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse};
-use haloswap::pair::{ReverseSimulationResponse, SimulationResponse};
-use haloswap::token::InstantiateMsg as TokenInstantiateMsg;
+use haloswap::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo, SwapCallback};
+use cw2::set_contract_version;
+use haloswap::pair::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, LiquidityPermit, LiquidityPermitParams,
+    LiquidityTokenKind, MigrateMsg, PauseFlags, PoolResponse,
+};
+use haloswap::pair::{
+    ProvideLiquidityResponseData, ReverseSimulationResponse, SimulationResponse,
+    SwapResponseData, WithdrawLiquidityResponseData, LIQUIDITY_TOKEN_SALT,
+};
+use haloswap::token::{
+    ExecuteMsg as TokenExecuteMsg, InstantiateMarketingInfo, InstantiateMsg as TokenInstantiateMsg, Permit,
+    PermitInfoResponse, PermitParams,
+};
+use haloswap::token_factory::{burn_msg, create_denom_msg, mint_msg};
+use cw_utils::PaymentError;
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use sha2::{Digest, Sha256};
 
 #[test]
 fn proper_initialization() {
     let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(&"asset0000".to_string(), &[])]);
 
     let msg = InstantiateMsg {
         asset_infos: [
@@ -35,6 +52,7 @@ fn proper_initialization() {
             first_asset_minimum: Uint128::zero(),
             second_asset_minimum: Uint128::zero(),
         },
+        liquidity_token_kind: None,
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -44,10 +62,10 @@ fn proper_initialization() {
     assert_eq!(
         res.messages,
         vec![SubMsg {
-            msg: WasmMsg::Instantiate {
+            msg: WasmMsg::Instantiate2 {
                 code_id: 10u64,
-                msg: to_binary(&TokenInstantiateMsg {
-                    name: "halo liquidity token".to_string(),
+                msg: to_json_binary(&TokenInstantiateMsg {
+                    name: "uusd-mAAPL-LP".to_string(),
                     symbol: "uLP".to_string(),
                     decimals: 6,
                     initial_balances: vec![],
@@ -55,11 +73,20 @@ fn proper_initialization() {
                         minter: MOCK_CONTRACT_ADDR.to_string(),
                         cap: None,
                     }),
+                    marketing: Some(InstantiateMarketingInfo {
+                        project: None,
+                        description: Some(
+                            "Haloswap liquidity token of the uusd-mAAPL pair".to_string()
+                        ),
+                        marketing: None,
+                        logo: None,
+                    }),
                 })
                 .unwrap(),
                 funds: vec![],
                 label: "lp".to_string(),
                 admin: None,
+                salt: Binary::from(LIQUIDITY_TOKEN_SALT),
             }
             .into(),
             gas_limit: None,
@@ -162,7 +189,7 @@ fn proper_initialization() {
 //     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
 //         sender: "addr0000".to_string(),
 //         amount: Uint128::zero(),
-//         msg:to_binary(&Cw20HookMsg::Swap {
+//         msg:to_json_binary(&Cw20HookMsg::Swap {
 //                 belief_price: None,
 //                 max_spread: None,
 //                 to: None,
@@ -185,7 +212,7 @@ fn proper_initialization() {
 //     assert_eq!(
 //         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
 //             contract_addr: "asset0000".to_string(),
-//             msg: to_binary(&Cw20ExecuteMsg::Transfer {
+//             msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
 //                 recipient: "addr0000".to_string(),
 //                 amount: Uint128::from(100u128),
 //             })
@@ -229,6 +256,7 @@ fn provide_liquidity() {
             first_asset_minimum: Uint128::zero(),
             second_asset_minimum: Uint128::zero(),
         },
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -288,7 +316,7 @@ fn provide_liquidity() {
         transfer_from_msg,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "asset0000".to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+            msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
                 owner: "addr0000".to_string(),
                 recipient: MOCK_CONTRACT_ADDR.to_string(),
                 amount: Uint128::from(100u128),
@@ -301,7 +329,7 @@ fn provide_liquidity() {
         mint_msg,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "liquidity0000".to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Mint {
+            msg: to_json_binary(&Cw20ExecuteMsg::Mint {
                 recipient: "addr0000".to_string(),
                 amount: Uint128::from(100u128),
             })
@@ -310,6 +338,10 @@ fn provide_liquidity() {
         }))
     );
 
+    let data: ProvideLiquidityResponseData = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(data.share, Uint128::from(100u128));
+    assert_eq!(data.receiver, "addr0000".to_string());
+
     // provide more liquidity 1:2, which is not proportional to 1:1,
     // then it must accept 1:1 and treat left amount as donation
     deps.querier.with_balance(&[(
@@ -369,7 +401,7 @@ fn provide_liquidity() {
         transfer_from_msg,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "asset0000".to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+            msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
                 owner: "addr0000".to_string(),
                 recipient: MOCK_CONTRACT_ADDR.to_string(),
                 amount: Uint128::from(100u128),
@@ -382,7 +414,7 @@ fn provide_liquidity() {
         mint_msg,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "liquidity0000".to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Mint {
+            msg: to_json_binary(&Cw20ExecuteMsg::Mint {
                 recipient: "staking0000".to_string(), // LP tokens sent to specified receiver
                 amount: Uint128::from(50u128),
             })
@@ -608,6 +640,268 @@ fn provide_liquidity() {
     let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 }
 
+#[test]
+fn provide_liquidity_with_permits() {
+    // the address of the secp256k1 key of the bytes [1u8; 32]
+    let owner = "aura10xcqpzrky6eff2g52qdye53xkk9jxkvr6kd7z6";
+
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+        ),
+        (&"asset0000".to_string(), &[]),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 8u8],
+        requirements: CreatePairRequirements {
+            whitelist: vec![Addr::unchecked(owner)],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+        liquidity_token_kind: None,
+    };
+
+    let _res = instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    // binary message which is converted from "liquidity0000" string.
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // the owner signs the deposit of 100 asset0000 and 100 uusd, minting 100 liquidity at least
+    let signing_key = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+    let pubkey = Binary::from(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes(),
+    );
+    let params = LiquidityPermitParams {
+        chain_id: mock_env().block.chain_id,
+        pair: MOCK_CONTRACT_ADDR.to_string(),
+        owner: owner.to_string(),
+        assets: [
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+        ],
+        min_share: Uint128::from(100u128),
+        receiver: Some("addr0002".to_string()),
+        nonce: 0,
+        expires: mock_env().block.time.seconds() + 100,
+    };
+    let sign = |params: &LiquidityPermitParams| -> LiquidityPermit {
+        let hash = Sha256::digest(to_json_vec(params).unwrap());
+        let signature: Signature = signing_key.sign_prehash(&hash).unwrap();
+        LiquidityPermit {
+            params: params.clone(),
+            pubkey: pubkey.clone(),
+            signature: Binary::from(signature.to_bytes().as_slice()),
+        }
+    };
+    let permit = |owner: &str, token: &str, spender: &str| Permit {
+        params: PermitParams {
+            chain_id: mock_env().block.chain_id,
+            token: token.to_string(),
+            owner: owner.to_string(),
+            spender: spender.to_string(),
+            amount: Uint128::from(100u128),
+            nonce: 0,
+            expires: mock_env().block.time.seconds() + 100,
+        },
+        pubkey: Binary::from(vec![0u8; 33]),
+        signature: Binary::from(vec![0u8; 64]),
+    };
+    let msg = |liquidity_permit: LiquidityPermit, permits: Vec<Permit>| {
+        ExecuteMsg::ProvideLiquidityWithPermits {
+            liquidity_permit,
+            permits,
+        }
+    };
+    let info = mock_info(
+        "relayer0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+
+    // the liquidity permit must be signed by the owner for the pair with its current nonce
+    // before it expires
+    for (invalid_params, err) in [
+        (
+            LiquidityPermitParams {
+                pair: "pair0001".to_string(),
+                ..params.clone()
+            },
+            "Permit is not valid on this pair",
+        ),
+        (
+            LiquidityPermitParams {
+                expires: mock_env().block.time.seconds(),
+                ..params.clone()
+            },
+            "Permit expired",
+        ),
+        (
+            LiquidityPermitParams {
+                nonce: 1,
+                ..params.clone()
+            },
+            "Invalid permit nonce, expected 0",
+        ),
+    ] {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            msg(sign(&invalid_params), vec![]),
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::Std(StdError::generic_err(err))
+        );
+    }
+
+    // the relayer cannot change the signed deposit, minimum share or receiver
+    let mut tampered = sign(&params);
+    tampered.params.receiver = Some("relayer0000".to_string());
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        msg(tampered, vec![]),
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        ContractError::Std(StdError::generic_err("Invalid permit signature"))
+    );
+
+    // the token permits must be signed by the owner to the pair on a token of the pair
+    for invalid_permit in [
+        permit(owner, "asset0001", MOCK_CONTRACT_ADDR),
+        permit(owner, "asset0000", "addr0001"),
+        permit("addr0001", "asset0000", MOCK_CONTRACT_ADDR),
+    ] {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            msg(sign(&params), vec![invalid_permit]),
+        );
+        assert_eq!(res.unwrap_err(), ContractError::InvalidPermit {});
+    }
+
+    // the relayed permit is executed before the signed deposit is transferred from the owner,
+    // the liquidity goes to the signed receiver
+    let valid_permit = permit(owner, "asset0000", MOCK_CONTRACT_ADDR);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        msg(sign(&params), vec![valid_permit.clone()]),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_json_binary(&TokenExecuteMsg::Permit(valid_permit)).unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: owner.to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "liquidity0000".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Mint {
+                    recipient: "addr0002".to_string(),
+                    amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // the liquidity permit is used once
+    assert_eq!(
+        query_permit_info(deps.as_ref(), owner.to_string()).unwrap(),
+        PermitInfoResponse { nonce: 1 }
+    );
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        msg(sign(&params), vec![]),
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        ContractError::Std(StdError::generic_err("Invalid permit nonce, expected 1"))
+    );
+
+    // the deposit fails when it mints less than the signed minimum share
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg(
+            sign(&LiquidityPermitParams {
+                min_share: Uint128::from(101u128),
+                nonce: 1,
+                ..params.clone()
+            }),
+            vec![],
+        ),
+    );
+    assert_eq!(res.unwrap_err(), ContractError::MinimumShareAssertion {});
+}
+
 #[test]
 fn withdraw_liquidity() {
     let mut deps = mock_dependencies(&[Coin {
@@ -642,6 +936,7 @@ fn withdraw_liquidity() {
             first_asset_minimum: Uint128::zero(),
             second_asset_minimum: Uint128::zero(),
         },
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -669,7 +964,7 @@ fn withdraw_liquidity() {
     // withdraw liquidity
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
         amount: Uint128::from(100u128),
     });
 
@@ -695,7 +990,7 @@ fn withdraw_liquidity() {
         msg_refund_1,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "asset0000".to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: "addr0000".to_string(),
                 amount: Uint128::from(100u128),
             })
@@ -707,7 +1002,7 @@ fn withdraw_liquidity() {
         msg_burn_liquidity,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "liquidity0000".to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Burn {
+            msg: to_json_binary(&Cw20ExecuteMsg::Burn {
                 amount: Uint128::from(100u128),
             })
             .unwrap(),
@@ -723,6 +1018,29 @@ fn withdraw_liquidity() {
         log_refund_assets,
         &attr("refund_assets", "100uusd, 100asset0000")
     );
+
+    let data: WithdrawLiquidityResponseData = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        WithdrawLiquidityResponseData {
+            withdrawn_share: Uint128::from(100u128),
+            refund_assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(100u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    amount: Uint128::from(100u128),
+                },
+            ],
+            receiver: "addr0000".to_string(),
+        }
+    );
 }
 
 // #[test]
@@ -793,7 +1111,7 @@ fn withdraw_liquidity() {
 //    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
 //         sender: "addr0000".to_string(),
 //         amount: offer_amount,
-//         msg:to_binary(&Cw20HookMsg::Swap {
+//         msg:to_json_binary(&Cw20HookMsg::Swap {
 //                 belief_price: None,
 //                 max_spread: None,
 //                 to: None,
@@ -905,7 +1223,7 @@ fn withdraw_liquidity() {
 //     assert_eq!(
 //         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
 //             contract_addr: "asset0000".to_string(),
-//             msg: to_binary(&Cw20ExecuteMsg::Transfer {
+//             msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
 //                 recipient: "addr0000".to_string(),
 //                 amount: expected_return_amount,
 //             })
@@ -916,6 +1234,142 @@ fn withdraw_liquidity() {
 //     );
 // }
 
+#[test]
+fn native_liquidity_token() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+
+    deps.querier.with_token_balances(&[(&"asset0000".to_string(), &[])]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 8u8],
+        requirements: CreatePairRequirements {
+            whitelist: vec![Addr::unchecked("addr0000")],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+        liquidity_token_kind: Some(LiquidityTokenKind::Native),
+    };
+
+    // the pair creates the denom of its shares instead of a liquidity token contract
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(create_denom_msg(MOCK_CONTRACT_ADDR, "uLP"))]
+    );
+
+    let liquidity_denom = format!("factory/{}/uLP", MOCK_CONTRACT_ADDR);
+    let pair_info: PairInfo = query_pair_info(deps.as_ref()).unwrap();
+    assert_eq!(pair_info.liquidity_token, liquidity_denom);
+
+    // the shares are minted to the provider
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+        ],
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages.get(1).expect("no message"),
+        &SubMsg::new(mint_msg(
+            MOCK_CONTRACT_ADDR,
+            &Coin::new(100u128, liquidity_denom.clone()),
+            "addr0000",
+        ))
+    );
+
+    // the pool holds the assets and the total share is the supply of the denom
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    deps.querier.with_balance(&[
+        (
+            &MOCK_CONTRACT_ADDR.to_string(),
+            vec![Coin::new(100u128, "uusd"), Coin::new(40u128, liquidity_denom.clone())],
+        ),
+        (
+            &"addr0000".to_string(),
+            vec![Coin::new(60u128, liquidity_denom.clone())],
+        ),
+    ]);
+    let pool: PoolResponse = query_pool(deps.as_ref()).unwrap();
+    assert_eq!(pool.total_share, Uint128::from(100u128));
+
+    // the shares are withdrawn as funds, not with the cw20 hook
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        amount: Uint128::from(40u128),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("liquidity0000", &[]), msg);
+    assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+    let info = mock_info("addr0000", &[Coin::new(40u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::WithdrawLiquidity {});
+    assert_eq!(
+        res.unwrap_err(),
+        ContractError::Payment(PaymentError::MissingDenom(liquidity_denom.clone()))
+    );
+
+    let info = mock_info("addr0000", &[Coin::new(40u128, liquidity_denom.clone())]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::WithdrawLiquidity {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![Coin::new(40u128, "uusd")],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(40u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(burn_msg(
+                MOCK_CONTRACT_ADDR,
+                &Coin::new(40u128, liquidity_denom),
+                MOCK_CONTRACT_ADDR,
+            )),
+        ]
+    );
+}
+
 #[test]
 fn try_token_to_native() {
     let total_share = Uint128::from(20_000_000_000u128);
@@ -958,6 +1412,7 @@ fn try_token_to_native() {
             first_asset_minimum: Uint128::zero(),
             second_asset_minimum: Uint128::zero(),
         },
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -986,10 +1441,11 @@ fn try_token_to_native() {
   let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::zero(),
-        msg:to_binary(&Cw20HookMsg::Swap {
+        msg:to_json_binary(&Cw20HookMsg::Swap {
                 belief_price: None,
                 max_spread: None,
                 to: None,
+                callback: None,
             })
             .unwrap(),
     });
@@ -1016,10 +1472,11 @@ fn try_token_to_native() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: offer_amount,
-        msg: to_binary(&Cw20HookMsg::Swap {
+        msg: to_json_binary(&Cw20HookMsg::Swap {
             belief_price: None,
             max_spread: None,
             to: None,
+            callback: None,
         })
         .unwrap(),
     });
@@ -1094,6 +1551,21 @@ fn try_token_to_native() {
             < 3i128
     );
 
+    // the ask pool cannot cover the requested amount
+    let reverse_simulation_err = query_reverse_simulation(
+        deps.as_ref(),
+        Asset {
+            amount: collateral_pool_amount,
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+    );
+    assert_eq!(
+        reverse_simulation_err,
+        Err(ContractError::InsufficientLiquidity {})
+    );
+
     assert_eq!(
         res.attributes,
         vec![
@@ -1109,6 +1581,24 @@ fn try_token_to_native() {
         ]
     );
 
+    let data: SwapResponseData = from_json(res.data.as_ref().unwrap()).unwrap();
+    assert_eq!(
+        data,
+        SwapResponseData {
+            offer_asset: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            offer_amount,
+            return_amount: expected_return_amount,
+            spread_amount: expected_spread_amount,
+            commission_amount: expected_commission_amount,
+            receiver: "addr0000".to_string(),
+        }
+    );
+
     assert_eq!(
         &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
             to_address: "addr0000".to_string(),
@@ -1120,14 +1610,75 @@ fn try_token_to_native() {
         msg_transfer,
     );
 
+    // sell with a callback, the return asset is attached to the callback message
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &total_share)],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &(asset_pool_amount + offer_amount),
+            )],
+        ),
+    ]);
+    let callback = SwapCallback {
+        contract: "vault0000".to_string(),
+        msg: to_json_binary(&"deposit").unwrap(),
+    };
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: offer_amount,
+        msg: to_json_binary(&Cw20HookMsg::Swap {
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            callback: Some(callback.clone()),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vault0000".to_string(),
+            msg: callback.msg.clone(),
+            funds: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: expected_return_amount,
+            }],
+        }))]
+    );
+    assert_eq!(res.attributes[2], attr("receiver", "vault0000"));
+
+    // a callback cannot be combined with a receiver
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: offer_amount,
+        msg: to_json_binary(&Cw20HookMsg::Swap {
+            belief_price: None,
+            max_spread: None,
+            to: Some("addr0001".to_string()),
+            callback: Some(callback),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::ReceiverWithCallback {});
+
     // failed due to non asset token contract try to execute sell
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: offer_amount,
-        msg: to_binary(&Cw20HookMsg::Swap {
+        msg: to_json_binary(&Cw20HookMsg::Swap {
             belief_price: None,
             max_spread: None,
             to: None,
+            callback: None,
         })
         .unwrap(),
     });
@@ -1350,6 +1901,7 @@ fn test_query_pool() {
             first_asset_minimum: Uint128::zero(),
             second_asset_minimum: Uint128::zero(),
         },
+        liquidity_token_kind: None,
     };
 
     let env = mock_env();
@@ -1395,3 +1947,235 @@ fn test_query_pool() {
     );
     assert_eq!(res.total_share, total_share_amount);
 }
+
+#[test]
+fn update_pair_config() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1_000_000u128),
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1_000_000u128))],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1_000_000u128))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 8u8],
+        requirements: CreatePairRequirements {
+            whitelist: vec![Addr::unchecked("addr0000")],
+            first_asset_minimum: Uint128::zero(),
+            second_asset_minimum: Uint128::zero(),
+        },
+        liquidity_token_kind: None,
+    };
+
+    // the instantiator is recorded as the factory
+    let info = mock_info("factory0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        query_config(deps.as_ref()).unwrap(),
+        ConfigResponse {
+            factory: "factory0000".to_string(),
+            commission_rate: Decimal::permille(3),
+            pause: PauseFlags::default(),
+        }
+    );
+
+    let msg = ExecuteMsg::UpdatePairConfig {
+        requirements: Some(CreatePairRequirements {
+            whitelist: vec![Addr::unchecked("addr0001")],
+            first_asset_minimum: Uint128::from(100u128),
+            second_asset_minimum: Uint128::from(100u128),
+        }),
+        commission_rate: Some(Decimal::percent(1)),
+        pause: Some(PauseFlags {
+            swap: true,
+            provide_liquidity: true,
+        }),
+    };
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg.clone()),
+        Err(ContractError::Unauthorized {})
+    );
+
+    let invalid_msg = ExecuteMsg::UpdatePairConfig {
+        requirements: None,
+        commission_rate: Some(Decimal::one()),
+        pause: None,
+    };
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info.clone(), invalid_msg),
+        Err(ContractError::InvalidCommissionRate {})
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_pair_config"),
+            attr("commission_rate", "0.01"),
+            attr("swap_paused", "true"),
+            attr("provide_liquidity_paused", "true"),
+        ]
+    );
+    assert_eq!(
+        PAIR_INFO.load(&deps.storage).unwrap().requirements,
+        CreatePairRequirements {
+            whitelist: vec![Addr::unchecked("addr0001")],
+            first_asset_minimum: Uint128::from(100u128),
+            second_asset_minimum: Uint128::from(100u128),
+        }
+    );
+
+    // the simulation charges the updated commission rate
+    let res = query_simulation(
+        deps.as_ref(),
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.commission_amount, Uint128::from(9u128));
+    assert_eq!(res.return_amount, Uint128::from(990u128));
+
+    // the paused operations are rejected
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_json_binary(&Cw20HookMsg::Swap {
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), mock_info("asset0000", &[]), msg),
+        Err(ContractError::SwapPaused {})
+    );
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+        ],
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg),
+        Err(ContractError::ProvideLiquidityPaused {})
+    );
+}
+
+#[test]
+fn migrate_version_checks() {
+    let mut deps = mock_dependencies(&[]);
+
+    // the contract name must match
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-router", "0.1.0").unwrap();
+    assert_eq!(
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()),
+        Err(ContractError::Std(StdError::generic_err(
+            "cannot migrate from a different contract: crates.io:halo-router"
+        )))
+    );
+
+    // cannot downgrade
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-pair", "99.0.0").unwrap();
+    assert_eq!(
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()),
+        Err(ContractError::Std(StdError::generic_err(
+            "cannot migrate from a newer version: 99.0.0"
+        )))
+    );
+
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-pair", "0.1.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("from_version", "0.1.0"),
+            attr("to_version", env!("CARGO_PKG_VERSION")),
+        ]
+    );
+}
+
+#[test]
+fn migrate_to_v0_3_with_parameters() {
+    let mut deps = mock_dependencies(&[]);
+
+    // the config is recorded from the admin of the pair by default
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-pair", "0.2.0").unwrap();
+    migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+    assert_eq!(
+        query_config(deps.as_ref()).unwrap(),
+        ConfigResponse {
+            factory: "factory".to_string(),
+            commission_rate: Decimal::permille(3),
+            pause: PauseFlags::default(),
+        }
+    );
+
+    // the commission rate must be less than 1
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-pair", "0.2.0").unwrap();
+    let msg = MigrateMsg {
+        factory: Some("factory0000".to_string()),
+        commission_rate: Some(Decimal::one()),
+    };
+    assert_eq!(
+        migrate(deps.as_mut(), mock_env(), msg),
+        Err(ContractError::InvalidCommissionRate {})
+    );
+
+    let msg = MigrateMsg {
+        factory: Some("factory0000".to_string()),
+        commission_rate: Some(Decimal::percent(1)),
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        query_config(deps.as_ref()).unwrap(),
+        ConfigResponse {
+            factory: "factory0000".to_string(),
+            commission_rate: Decimal::percent(1),
+            pause: PauseFlags::default(),
+        }
+    );
+}
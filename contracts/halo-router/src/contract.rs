@@ -2,21 +2,23 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Uint128, WasmMsg,
+    from_json, to_json_binary, Addr, Api, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Reply, Response, StdError, StdResult, Uint128,
 };
 use cw2::set_contract_version;
 
-use crate::operations::execute_swap_operation;
-use crate::state::{Config, CONFIG};
+use crate::migration::migrate_to_v0_2;
+use crate::operations::{query_ask_balance, swap_operation_msg, SWAP_OPERATION_REPLY_ID};
+use crate::state::{Config, SwapContext, CONFIG, SWAP_CONTEXT};
 
 use cw20::Cw20ReceiveMsg;
-use haloswap::asset::{Asset, AssetInfo, PairInfo};
+use haloswap::asset::{Asset, AssetInfo, PairInfo, SwapCallback};
+use haloswap::migration::{assert_migrate_version, Version};
 use haloswap::pair::SimulationResponse;
 use haloswap::querier::{query_pair_info, reverse_simulate, simulate};
 use haloswap::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, ExecuteSwapOperationsResponse, InstantiateMsg,
+    MigrateMsg, QueryMsg, SimulateSwapOperationsResponse, SwapOperation,
 };
 use std::collections::HashMap;
 
@@ -33,10 +35,17 @@ pub fn instantiate(
 ) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    if msg.max_referral_commission >= Decimal::one() {
+        return Err(StdError::generic_err(
+            "max_referral_commission must be less than 1",
+        ));
+    }
+
     CONFIG.save(
         deps.storage,
         &Config {
             halo_factory: deps.api.addr_canonicalize(&msg.halo_factory)?,
+            max_referral_commission: msg.max_referral_commission,
         },
     )?;
 
@@ -51,39 +60,28 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             operations,
             minimum_receive,
             to,
+            referral_address,
+            referral_commission,
+            callback,
         } => {
             let api = deps.api;
+            let offer_amount = match operations.first().map(|op| op.get_offer_asset_info()) {
+                Some(AssetInfo::NativeToken { denom }) => amount_of(&info.funds, denom),
+                _ => Uint128::zero(),
+            };
+
             execute_swap_operations(
                 deps,
                 env,
                 info.sender,
+                offer_amount,
                 operations,
                 minimum_receive,
                 optional_addr_validate(api, to)?,
+                optional_referral_validate(api, referral_address, referral_commission)?,
+                optional_callback_validate(api, callback)?,
             )
         }
-        ExecuteMsg::ExecuteSwapOperation { operation, to } => {
-            let api = deps.api;
-            execute_swap_operation(
-                deps,
-                env,
-                info,
-                operation,
-                optional_addr_validate(api, to)?.map(|v| v.to_string()),
-            )
-        }
-        ExecuteMsg::AssertMinimumReceive {
-            asset_info,
-            prev_balance,
-            minimum_receive,
-            receiver,
-        } => assert_minium_receive(
-            deps.as_ref(),
-            asset_info,
-            prev_balance,
-            minimum_receive,
-            deps.api.addr_validate(&receiver)?,
-        ),
     }
 }
 
@@ -97,6 +95,53 @@ fn optional_addr_validate(api: &dyn Api, addr: Option<String>) -> StdResult<Opti
     Ok(addr)
 }
 
+fn optional_referral_validate(
+    api: &dyn Api,
+    referral_address: Option<String>,
+    referral_commission: Option<Decimal>,
+) -> StdResult<Option<(Addr, Decimal)>> {
+    match (referral_address, referral_commission) {
+        (Some(referral_address), Some(referral_commission)) => Ok(Some((
+            api.addr_validate(&referral_address)?,
+            referral_commission,
+        ))),
+        (None, None) => Ok(None),
+        _ => Err(StdError::generic_err(
+            "referral_address and referral_commission must be provided together",
+        )),
+    }
+}
+
+fn optional_callback_validate(
+    api: &dyn Api,
+    callback: Option<SwapCallback>,
+) -> StdResult<Option<SwapCallback>> {
+    if let Some(callback) = &callback {
+        api.addr_validate(&callback.contract)?;
+    }
+
+    Ok(callback)
+}
+
+fn amount_of(coins: &[Coin], denom: String) -> Uint128 {
+    match coins.iter().find(|x| x.denom == denom) {
+        Some(coin) => coin.amount,
+        None => Uint128::zero(),
+    }
+}
+
+fn assert_referral_commission(deps: Deps, referral_commission: Decimal) -> StdResult<()> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if referral_commission > config.max_referral_commission {
+        return Err(StdError::generic_err(format!(
+            "referral_commission exceeds the maximum; max referral commission: {}",
+            config.max_referral_commission
+        )));
+    }
+
+    Ok(())
+}
+
 pub fn receive_cw20(
     deps: DepsMut,
     env: Env,
@@ -104,116 +149,210 @@ pub fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> StdResult<Response> {
     let sender = deps.api.addr_validate(&cw20_msg.sender)?;
-    match from_binary(&cw20_msg.msg)? {
+    match from_json(&cw20_msg.msg)? {
         Cw20HookMsg::ExecuteSwapOperations {
             operations,
             minimum_receive,
             to,
+            referral_address,
+            referral_commission,
+            callback,
         } => {
             let api = deps.api;
             execute_swap_operations(
                 deps,
                 env,
                 sender,
+                cw20_msg.amount,
                 operations,
                 minimum_receive,
                 optional_addr_validate(api, to)?,
+                optional_referral_validate(api, referral_address, referral_commission)?,
+                optional_callback_validate(api, callback)?,
             )
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_swap_operations(
     deps: DepsMut,
     env: Env,
     sender: Addr,
+    offer_amount: Uint128,
     operations: Vec<SwapOperation>,
     minimum_receive: Option<Uint128>,
     to: Option<Addr>,
+    referral: Option<(Addr, Decimal)>,
+    callback: Option<SwapCallback>,
 ) -> StdResult<Response> {
     let operations_len = operations.len();
     if operations_len == 0 {
         return Err(StdError::generic_err("must provide operations"));
     }
 
+    // a hop cannot start another swap, e.g. from a cw20 hook, as it would overwrite the context
+    if SWAP_CONTEXT.may_load(deps.storage)?.is_some() {
+        return Err(StdError::generic_err("a swap is already in progress"));
+    }
+
     // Assert the operations are properly set
     assert_operations(&operations)?;
 
-    let to = if let Some(to) = to { to } else { sender };
-    let target_asset_info = operations.last().unwrap().get_target_asset_info();
-
-    let mut operation_index = 0;
-    let mut messages: Vec<CosmosMsg> = operations
-        .into_iter()
-        .map(|op| {
-            operation_index += 1;
-            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: op,
-                    to: if operation_index == operations_len {
-                        Some(to.to_string())
-                    } else {
-                        None
-                    },
-                })?,
-            }))
-        })
-        .collect::<StdResult<Vec<CosmosMsg>>>()?;
-
-    // Execute minimum amount assertion
-    if let Some(minimum_receive) = minimum_receive {
-        let receiver_balance = target_asset_info.query_pool(&deps.querier, deps.api, to.clone())?;
-
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: env.contract.address.to_string(),
-            funds: vec![],
-            msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
-                asset_info: target_asset_info,
-                prev_balance: receiver_balance,
-                minimum_receive,
-                receiver: to.to_string(),
-            })?,
-        }))
+    // the callback contract receives the return asset along with the callback message
+    let to = match (&callback, to) {
+        (Some(_), Some(_)) => {
+            return Err(StdError::generic_err(
+                "cannot set both a receiver and a callback",
+            ))
+        }
+        (Some(callback), None) => Addr::unchecked(callback.contract.clone()),
+        (None, Some(to)) => to,
+        (None, None) => sender,
+    };
+    let offer_asset_info = operations.first().unwrap().get_offer_asset_info();
+
+    // The referral commission is paid out of the offer asset before the first hop
+    let mut response = Response::new();
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut referral_amount = Uint128::zero();
+    if let Some((referral_address, referral_commission)) = referral {
+        assert_referral_commission(deps.as_ref(), referral_commission)?;
+
+        referral_amount = offer_amount * referral_commission;
+        if !referral_amount.is_zero() {
+            messages.push(
+                Asset {
+                    info: offer_asset_info.clone(),
+                    amount: referral_amount,
+                }
+                .into_msg(referral_address.clone())?,
+            );
+        }
+
+        response = response.add_attributes(vec![
+            ("referral_address", referral_address.to_string()),
+            ("referral_amount", referral_amount.to_string()),
+        ]);
     }
 
-    Ok(Response::new().add_messages(messages))
+    let offer_asset = Asset {
+        info: offer_asset_info,
+        amount: offer_amount.checked_sub(referral_amount)?,
+    };
+    if offer_asset.amount.is_zero() {
+        return Err(StdError::generic_err("must provide offer asset"));
+    }
+
+    // The hops are executed one by one as submessages, the router holds
+    // the intermediate assets and forwards the returned amount to the next hop in `reply`
+    let swap_msg = swap_operation_msg(deps.as_ref(), operations[0].clone(), offer_asset.amount)?;
+    let ask_balance = query_ask_balance(deps.as_ref(), &env, &operations[0])?;
+    SWAP_CONTEXT.save(
+        deps.storage,
+        &SwapContext {
+            operations,
+            ask_balance,
+            offer_asset,
+            minimum_receive,
+            receiver: to,
+            callback,
+        },
+    )?;
+
+    Ok(response.add_messages(messages).add_submessage(swap_msg))
 }
 
-fn assert_minium_receive(
-    deps: Deps,
-    asset_info: AssetInfo,
-    prev_balance: Uint128,
-    minium_receive: Uint128,
-    receiver: Addr,
-) -> StdResult<Response> {
-    let receiver_balance = asset_info.query_pool(&deps.querier, deps.api, receiver)?;
-    let swap_amount = receiver_balance.checked_sub(prev_balance)?;
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    if msg.id != SWAP_OPERATION_REPLY_ID {
+        return Err(StdError::generic_err("invalid reply id"));
+    }
 
-    if swap_amount < minium_receive {
-        return Err(StdError::generic_err(format!(
-            "assertion failed; minimum receive amount: {}, swap amount: {}",
-            minium_receive, swap_amount
-        )));
+    msg.result.into_result().map_err(StdError::generic_err)?;
+
+    let mut swap_context: SwapContext = SWAP_CONTEXT.load(deps.storage)?;
+    let operation = swap_context.operations.remove(0);
+
+    // the returned amount is measured on the router balance, the events and the data of
+    // the hop can be emitted by any contract it calls, e.g. a cw20 token of the path
+    let return_amount = query_ask_balance(deps.as_ref(), &env, &operation)?
+        .checked_sub(swap_context.ask_balance)?;
+
+    // forward the returned amount to the next hop
+    if let Some(next_operation) = swap_context.operations.first() {
+        let swap_msg = swap_operation_msg(deps.as_ref(), next_operation.clone(), return_amount)?;
+        swap_context.ask_balance = query_ask_balance(deps.as_ref(), &env, next_operation)?;
+        SWAP_CONTEXT.save(deps.storage, &swap_context)?;
+
+        return Ok(Response::new().add_submessage(swap_msg));
     }
 
-    Ok(Response::default())
+    SWAP_CONTEXT.remove(deps.storage);
+
+    if let Some(minimum_receive) = swap_context.minimum_receive {
+        if return_amount < minimum_receive {
+            return Err(StdError::generic_err(format!(
+                "assertion failed; minimum receive amount: {}, swap amount: {}",
+                minimum_receive, return_amount
+            )));
+        }
+    }
+
+    // deliver exactly the amount returned by the last hop
+    let return_asset = Asset {
+        info: operation.get_target_asset_info(),
+        amount: return_amount,
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !return_asset.amount.is_zero() {
+        messages.push(match swap_context.callback {
+            Some(callback) => return_asset.clone().into_callback_msg(callback)?,
+            None => return_asset
+                .clone()
+                .into_msg(swap_context.receiver.clone())?,
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "execute_swap_operations"),
+            ("receiver", swap_context.receiver.as_str()),
+            ("offer_asset", &swap_context.offer_asset.to_string()),
+            ("return_asset", &return_asset.to_string()),
+        ])
+        .set_data(to_json_binary(&ExecuteSwapOperationsResponse {
+            offer_asset: swap_context.offer_asset,
+            return_asset,
+            receiver: swap_context.receiver.to_string(),
+        })?))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
         QueryMsg::SimulateSwapOperations {
             offer_amount,
             operations,
-        } => to_binary(&simulate_swap_operations(deps, offer_amount, operations)?),
+            referral_commission,
+        } => to_json_binary(&simulate_swap_operations(
+            deps,
+            offer_amount,
+            operations,
+            referral_commission,
+        )?),
         QueryMsg::ReverseSimulateSwapOperations {
             ask_amount,
             operations,
-        } => to_binary(&reverse_simulate_swap_operations(
-            deps, ask_amount, operations,
+            referral_commission,
+        } => to_json_binary(&reverse_simulate_swap_operations(
+            deps,
+            ask_amount,
+            operations,
+            referral_commission,
         )?),
     }
 }
@@ -222,6 +361,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = CONFIG.load(deps.storage)?;
     let resp = ConfigResponse {
         halo_factory: deps.api.addr_humanize(&state.halo_factory)?.to_string(),
+        max_referral_commission: state.max_referral_commission,
     };
 
     Ok(resp)
@@ -231,6 +371,7 @@ fn simulate_swap_operations(
     deps: Deps,
     offer_amount: Uint128,
     operations: Vec<SwapOperation>,
+    referral_commission: Option<Decimal>,
 ) -> StdResult<SimulateSwapOperationsResponse> {
     let config: Config = CONFIG.load(deps.storage)?;
     let halo_factory = deps.api.addr_humanize(&config.halo_factory)?;
@@ -240,7 +381,14 @@ fn simulate_swap_operations(
         return Err(StdError::generic_err("must provide operations"));
     }
 
-    let mut offer_amount = offer_amount;
+    // deduct the referral commission before the first hop
+    let mut offer_amount = if let Some(referral_commission) = referral_commission {
+        assert_referral_commission(deps, referral_commission)?;
+        offer_amount.checked_sub(offer_amount * referral_commission)?
+    } else {
+        offer_amount
+    };
+
     for operation in operations.into_iter() {
         match operation {
             SwapOperation::HaloSwap {
@@ -276,6 +424,7 @@ fn reverse_simulate_swap_operations(
     deps: Deps,
     ask_amount: Uint128,
     operations: Vec<SwapOperation>,
+    referral_commission: Option<Decimal>,
 ) -> StdResult<SimulateSwapOperationsResponse> {
     let config: Config = CONFIG.load(deps.storage)?;
 
@@ -299,15 +448,37 @@ fn reverse_simulate_swap_operations(
                     ask_amount,
                     offer_asset_info,
                     ask_asset_info,
-                )
-                .unwrap()
+                )?
             }
         }
     }
 
+    // add the referral commission on top of the offer amount required by the first hop
+    if let Some(referral_commission) = referral_commission {
+        assert_referral_commission(deps, referral_commission)?;
+        ask_amount = add_referral_commission(ask_amount, referral_commission)?;
+    }
+
     Ok(SimulateSwapOperationsResponse { amount: ask_amount })
 }
 
+/// Returns the smallest offer amount which still leaves `amount`
+/// after the referral commission is deducted
+fn add_referral_commission(amount: Uint128, referral_commission: Decimal) -> StdResult<Uint128> {
+    let mut offer_amount = amount
+        .checked_multiply_ratio(
+            Decimal::one().atomics(),
+            (Decimal::one() - referral_commission).atomics(),
+        )
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    while offer_amount.checked_sub(offer_amount * referral_commission)? < amount {
+        offer_amount = offer_amount.checked_add(Uint128::one())?;
+    }
+
+    Ok(offer_amount)
+}
+
 fn reverse_simulate_return_amount(
     deps: Deps,
     factory: Addr,
@@ -413,7 +584,19 @@ fn test_invalid_operations() {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // the migration steps run in the order of the versions introducing them
+    if stored_version < Version::new(0, 2, 0) {
+        migrate_to_v0_2(deps.storage, msg.max_referral_commission)?;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::default())
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
 }
@@ -1,6 +1,7 @@
 pub mod contract;
 pub mod state;
 
+mod migration;
 mod operations;
 
 #[cfg(test)]
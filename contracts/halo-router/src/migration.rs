@@ -0,0 +1,37 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, Decimal, StdError, StdResult, Storage};
+use cw_storage_plus::Item;
+
+use crate::state::{Config, CONFIG};
+
+/// The config layout before 0.2.0
+#[cw_serde]
+struct ConfigV0_1 {
+    halo_factory: CanonicalAddr,
+}
+
+const CONFIG_V0_1: Item<ConfigV0_1> = Item::new("config");
+
+/// 0.2.0 adds the maximum referral commission to the config
+pub fn migrate_to_v0_2(
+    storage: &mut dyn Storage,
+    max_referral_commission: Option<Decimal>,
+) -> StdResult<()> {
+    let max_referral_commission = max_referral_commission.ok_or_else(|| {
+        StdError::generic_err("max_referral_commission is required to migrate to 0.2.0")
+    })?;
+    if max_referral_commission >= Decimal::one() {
+        return Err(StdError::generic_err(
+            "max_referral_commission must be less than 1",
+        ));
+    }
+
+    let config = CONFIG_V0_1.load(storage)?;
+    CONFIG.save(
+        storage,
+        &Config {
+            halo_factory: config.halo_factory,
+            max_referral_commission,
+        },
+    )
+}
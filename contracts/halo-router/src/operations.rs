@@ -1,6 +1,5 @@
 use cosmwasm_std::{
-    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult, WasmMsg,
+    to_json_binary, Addr, Coin, CosmosMsg, Decimal, Deps, Env, StdResult, SubMsg, Uint128, WasmMsg,
 };
 
 use crate::state::{Config, CONFIG};
@@ -8,28 +7,25 @@ use crate::state::{Config, CONFIG};
 use cw20::Cw20ExecuteMsg;
 use haloswap::asset::{Asset, AssetInfo, PairInfo};
 use haloswap::pair::Cw20HookMsg as PairHookMsg;
-use haloswap::querier::{query_balance, query_pair_info, query_token_balance};
+use haloswap::querier::query_pair_info;
 use haloswap::router::SwapOperation;
 
-/// Execute swap operation
-/// swap all offer asset to ask asset
-pub fn execute_swap_operation(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    operation: SwapOperation,
-    to: Option<String>,
-) -> StdResult<Response> {
-    if env.contract.address != info.sender {
-        return Err(StdError::generic_err("unauthorized"));
-    }
+pub const SWAP_OPERATION_REPLY_ID: u64 = 1;
 
-    let messages: Vec<CosmosMsg> = match operation {
+/// Build the swap submessage of a swap operation
+/// the return asset is sent back to the router and the returned amount is measured in `reply`,
+/// the pair is resolved by the factory so deregistered pairs are never used
+pub fn swap_operation_msg(
+    deps: Deps,
+    operation: SwapOperation,
+    amount: Uint128,
+) -> StdResult<SubMsg> {
+    match operation {
         SwapOperation::HaloSwap {
             offer_asset_info,
             ask_asset_info,
         } => {
-            let config: Config = CONFIG.load(deps.as_ref().storage)?;
+            let config: Config = CONFIG.load(deps.storage)?;
             let halo_factory = deps.api.addr_humanize(&config.halo_factory)?;
             let pair_info: PairInfo = query_pair_info(
                 &deps.querier,
@@ -37,32 +33,33 @@ pub fn execute_swap_operation(
                 &[offer_asset_info.clone(), ask_asset_info],
             )?;
 
-            let amount = match offer_asset_info.clone() {
-                AssetInfo::NativeToken { denom } => {
-                    query_balance(&deps.querier, env.contract.address, denom)?
-                }
-                AssetInfo::Token { contract_addr } => query_token_balance(
-                    &deps.querier,
-                    deps.api.addr_validate(contract_addr.as_str())?,
-                    env.contract.address,
-                )?,
-            };
             let offer_asset: Asset = Asset {
                 info: offer_asset_info,
                 amount,
             };
 
-            vec![asset_into_swap_msg(
-                deps.as_ref(),
-                Addr::unchecked(pair_info.contract_addr),
-                offer_asset,
-                None,
-                to,
-            )?]
+            Ok(SubMsg::reply_on_success(
+                asset_into_swap_msg(
+                    deps,
+                    Addr::unchecked(pair_info.contract_addr),
+                    offer_asset,
+                    None,
+                    None,
+                )?,
+                SWAP_OPERATION_REPLY_ID,
+            ))
         }
-    };
+    }
+}
 
-    Ok(Response::new().add_messages(messages))
+/// Query the balance of the ask asset of an operation held by the router,
+/// the returned amount of a hop is the increase of this balance over the hop
+pub fn query_ask_balance(deps: Deps, env: &Env, operation: &SwapOperation) -> StdResult<Uint128> {
+    operation.get_target_asset_info().query_pool(
+        &deps.querier,
+        deps.api,
+        env.contract.address.clone(),
+    )
 }
 
 pub fn asset_into_swap_msg(
@@ -79,22 +76,24 @@ pub fn asset_into_swap_msg(
                 denom,
                 amount: offer_asset.amount,
             }],
-            msg: to_binary(&PairHookMsg::Swap {
+            msg: to_json_binary(&PairHookMsg::Swap {
                 belief_price: None,
                 max_spread,
                 to,
+                callback: None,
             })?,
         })),
         AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr,
             funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Send {
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
                 contract: pair_contract.to_string(),
                 amount: offer_asset.amount,
-                msg: to_binary(&PairHookMsg::Swap {
+                msg: to_json_binary(&PairHookMsg::Swap {
                     belief_price: None,
                     max_spread,
                     to,
+                    callback: None,
                 })?,
             })?,
         })),
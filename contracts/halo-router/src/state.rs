@@ -1,11 +1,29 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::CanonicalAddr;
+use cosmwasm_std::{Addr, CanonicalAddr, Decimal, Uint128};
 use cw_storage_plus::Item;
+use haloswap::asset::{Asset, SwapCallback};
+use haloswap::router::SwapOperation;
 
 #[cw_serde]
 pub struct Config {
     pub halo_factory: CanonicalAddr,
+    pub max_referral_commission: Decimal,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// The state of an ongoing `ExecuteSwapOperations`,
+/// `operations` holds the operation being executed and the remaining ones,
+/// `ask_balance` is the router balance of the ask asset before the executed operation
+#[cw_serde]
+pub struct SwapContext {
+    pub operations: Vec<SwapOperation>,
+    pub ask_balance: Uint128,
+    pub offer_asset: Asset,
+    pub minimum_receive: Option<Uint128>,
+    pub receiver: Addr,
+    pub callback: Option<SwapCallback>,
+}
+
+pub const SWAP_CONTEXT: Item<SwapContext> = Item::new("swap_context");
@@ -1,18 +1,23 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    coin, from_binary, to_binary, Addr, Coin, CosmosMsg, StdError, SubMsg, Uint128, WasmMsg,
+    attr, coin, from_json, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Event, Reply,
+    StdError, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 
-use crate::contract::{execute, instantiate, query};
-use crate::operations::asset_into_swap_msg;
+use crate::contract::{execute, instantiate, migrate, query, reply};
+use crate::operations::SWAP_OPERATION_REPLY_ID;
+use crate::state::{SwapContext, SWAP_CONTEXT};
 use haloswap::mock_querier::mock_dependencies;
 
+use cosmwasm_schema::cw_serde;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use haloswap::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo};
+use cw_storage_plus::Item;
+use haloswap::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo, SwapCallback};
 use haloswap::pair::Cw20HookMsg as PairHookMsg;
 use haloswap::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, ExecuteSwapOperationsResponse, InstantiateMsg,
+    MigrateMsg, QueryMsg, SimulateSwapOperationsResponse, SwapOperation,
 };
 
 #[test]
@@ -21,6 +26,7 @@ fn proper_initialization() {
 
     let msg = InstantiateMsg {
         halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -30,20 +36,17 @@ fn proper_initialization() {
 
     // it worked, let's query the state
     let config: ConfigResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
     assert_eq!("halofactory", config.halo_factory.as_str());
 }
 
 #[test]
 fn execute_swap_operations() {
     let mut deps = mock_dependencies(&[]);
-    deps.querier.with_token_balances(&[(
-        &"asset0002".to_string(),
-        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
-    )]);
 
     let msg = InstantiateMsg {
         halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -51,10 +54,65 @@ fn execute_swap_operations() {
     // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+    deps.querier.with_halo_factory(
+        &[
+            (
+                &"ukrwasset0001".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                        AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: "liquidity0000".to_string(),
+                    asset_decimals: [6u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
+                    requirements: CreatePairRequirements {
+                        whitelist: vec![Addr::unchecked("deployer")],
+                        first_asset_minimum: Uint128::zero(),
+                        second_asset_minimum: Uint128::zero(),
+                    },
+                },
+            ),
+            (
+                &"asset0000uluna".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: "liquidity0001".to_string(),
+                    asset_decimals: [6u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
+                    requirements: CreatePairRequirements {
+                        whitelist: vec![Addr::unchecked("deployer")],
+                        first_asset_minimum: Uint128::zero(),
+                        second_asset_minimum: Uint128::zero(),
+                    },
+                },
+            ),
+        ],
+        &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
+    );
+
     let msg = ExecuteMsg::ExecuteSwapOperations {
         operations: vec![],
         minimum_receive: None,
         to: None,
+        referral_address: None,
+        referral_commission: None,
+        callback: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -64,226 +122,203 @@ fn execute_swap_operations() {
         _ => panic!("DO NOT ENTER HERE"),
     }
 
-    let msg = ExecuteMsg::ExecuteSwapOperations {
-        operations: vec![
-            SwapOperation::HaloSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0001".to_string(),
-                },
+    let operations = vec![
+        SwapOperation::HaloSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
             },
-            SwapOperation::HaloSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0001".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
             },
-            SwapOperation::HaloSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0002".to_string(),
-                },
+        },
+        SwapOperation::HaloSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
             },
-        ],
-        minimum_receive: Some(Uint128::from(1000000u128)),
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        },
+    ];
+
+    // the offer asset is not sent
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
         to: None,
+        referral_address: None,
+        referral_commission: None,
+        callback: None,
     };
 
     let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "must provide offer asset"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // only the first hop is executed, the others are executed in reply,
+    // the router balance of the ask asset is recorded before the hop
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: Some(Uint128::from(1000000u128)),
+        to: None,
+        referral_address: None,
+        referral_commission: None,
+        callback: None,
+    };
+
+    let info = mock_info("addr0000", &[coin(1000000u128, "ukrw")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
         res.messages,
-        vec![
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::HaloSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "ukrw".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0001".to_string(),
-                        },
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::HaloSwap {
-                        offer_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0001".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                    },
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair0000".to_string(),
+                funds: vec![coin(1000000u128, "ukrw")],
+                msg: to_json_binary(&PairHookMsg::Swap {
+                    belief_price: None,
+                    max_spread: None,
                     to: None,
+                    callback: None,
                 })
                 .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::HaloSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0002".to_string(),
-                        },
-                    },
-                    to: Some("addr0000".to_string()),
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
-                    asset_info: AssetInfo::Token {
-                        contract_addr: "asset0002".to_string(),
-                    },
-                    prev_balance: Uint128::zero(),
-                    minimum_receive: Uint128::from(1000000u128),
-                    receiver: "addr0000".to_string(),
-                })
-                .unwrap(),
-            })),
-        ]
+            }),
+            SWAP_OPERATION_REPLY_ID,
+        )]
+    );
+    assert_eq!(
+        SWAP_CONTEXT.load(deps.as_ref().storage).unwrap(),
+        SwapContext {
+            operations,
+            ask_balance: Uint128::from(100u128),
+            offer_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+            minimum_receive: Some(Uint128::from(1000000u128)),
+            receiver: Addr::unchecked("addr0000"),
+            callback: None,
+        }
     );
 
+    let operations = vec![
+        SwapOperation::HaloSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        },
+        SwapOperation::HaloSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0002".to_string(),
+            },
+        },
+    ];
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(1000000u128),
-        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
-            operations: vec![
-                SwapOperation::HaloSwap {
-                    offer_asset_info: AssetInfo::NativeToken {
-                        denom: "ukrw".to_string(),
-                    },
-                    ask_asset_info: AssetInfo::Token {
-                        contract_addr: "asset0001".to_string(),
-                    },
-                },
-                SwapOperation::HaloSwap {
-                    offer_asset_info: AssetInfo::Token {
-                        contract_addr: "asset0001".to_string(),
-                    },
-                    ask_asset_info: AssetInfo::NativeToken {
-                        denom: "uluna".to_string(),
-                    },
-                },
-                SwapOperation::HaloSwap {
-                    offer_asset_info: AssetInfo::NativeToken {
-                        denom: "uluna".to_string(),
-                    },
-                    ask_asset_info: AssetInfo::Token {
-                        contract_addr: "asset0002".to_string(),
-                    },
-                },
-            ],
+        msg: to_json_binary(&Cw20HookMsg::ExecuteSwapOperations {
+            operations: operations.clone(),
             minimum_receive: None,
             to: Some("addr0002".to_string()),
+            referral_address: None,
+            referral_commission: None,
+            callback: None,
         })
         .unwrap(),
     });
 
+    // a swap cannot start while another one is ongoing
     let info = mock_info("asset0000", &[]);
+    match execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "a swap is already in progress"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    SWAP_CONTEXT.remove(deps.as_mut().storage);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
         res.messages,
-        vec![
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::HaloSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "ukrw".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0001".to_string(),
-                        },
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::HaloSwap {
-                        offer_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0001".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
                 funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::HaloSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0002".to_string(),
-                        },
-                    },
-                    to: Some("addr0002".to_string()),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pair0001".to_string(),
+                    amount: Uint128::from(1000000u128),
+                    msg: to_json_binary(&PairHookMsg::Swap {
+                        belief_price: None,
+                        max_spread: None,
+                        to: None,
+                        callback: None,
+                    })
+                    .unwrap(),
                 })
                 .unwrap(),
-            }))
-        ]
+            }),
+            SWAP_OPERATION_REPLY_ID,
+        )]
+    );
+    assert_eq!(
+        SWAP_CONTEXT.load(deps.as_ref().storage).unwrap(),
+        SwapContext {
+            operations,
+            ask_balance: Uint128::zero(),
+            offer_asset: Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+            minimum_receive: None,
+            receiver: Addr::unchecked("addr0002"),
+            callback: None,
+        }
     );
 }
 
 #[test]
-fn execute_swap_operation() {
+fn execute_swap_operations_with_callback() {
     let mut deps = mock_dependencies(&[]);
+
     let msg = InstantiateMsg {
         halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
     };
 
     let info = mock_info("addr0000", &[]);
-
-    // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     deps.querier.with_halo_factory(
         &[(
-            &"uusdasset0000".to_string(),
+            &"asset0000uluna".to_string(),
             &PairInfo {
                 asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: "uusd".to_string(),
-                    },
                     AssetInfo::Token {
                         contract_addr: "asset0000".to_string(),
                     },
+                    AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
                 ],
-                contract_addr: "pair0000".to_string(),
-                liquidity_token: "liquidity0000".to_string(),
+                contract_addr: "pair0001".to_string(),
+                liquidity_token: "liquidity0001".to_string(),
                 asset_decimals: [6u8, 6u8],
+                asset_symbols: None,
+                incentive_contract: None,
                 requirements: CreatePairRequirements {
                     whitelist: vec![Addr::unchecked("deployer")],
                     first_asset_minimum: Uint128::zero(),
@@ -291,104 +326,124 @@ fn execute_swap_operation() {
                 },
             },
         )],
-        &[("uusd".to_string(), 6u8)],
+        &[("uluna".to_string(), 6u8)],
     );
-    deps.querier.with_balance(&[(
-        &MOCK_CONTRACT_ADDR.to_string(),
-        [Coin {
-            amount: Uint128::from(1000000u128),
-            denom: "uusd".to_string(),
-        }]
-        .to_vec(),
-    )]);
 
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::HaloSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0000".to_string(),
-            },
+    let operations = vec![SwapOperation::HaloSwap {
+        offer_asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
         },
-        to: None,
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+    }];
+    let callback = SwapCallback {
+        contract: "vault0000".to_string(),
+        msg: to_json_binary(&"deposit").unwrap(),
     };
-    let info = mock_info("addr0000", &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+
+    // a callback cannot be combined with a receiver
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_json_binary(&Cw20HookMsg::ExecuteSwapOperations {
+            operations: operations.clone(),
+            minimum_receive: None,
+            to: Some("addr0002".to_string()),
+            referral_address: None,
+            referral_commission: None,
+            callback: Some(callback.clone()),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "cannot set both a receiver and a callback")
+        }
         _ => panic!("DO NOT ENTER HERE"),
     }
 
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_json_binary(&Cw20HookMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive: None,
+            to: None,
+            referral_address: None,
+            referral_commission: None,
+            callback: Some(callback.clone()),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let swap_context = SWAP_CONTEXT.load(deps.as_ref().storage).unwrap();
+    assert_eq!(swap_context.receiver, Addr::unchecked("vault0000"));
+    assert_eq!(swap_context.callback, Some(callback.clone()));
+
+    // the return asset of the last hop is attached to the callback message
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![coin(990000u128, "uluna")],
+    )]);
+    let res = reply(deps.as_mut(), mock_env(), swap_reply()).unwrap();
     assert_eq!(
         res.messages,
-        vec![SubMsg::new(
-            asset_into_swap_msg(
-                deps.as_ref(),
-                Addr::unchecked("pair0000"),
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: "uusd".to_string(),
-                    },
-                    amount: Uint128::from(1000000u128)
-                },
-                None,
-                None
-            )
-            .unwrap()
-        )],
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vault0000".to_string(),
+            funds: vec![coin(990000u128, "uluna")],
+            msg: callback.msg,
+        }))]
     );
+}
 
-    // optional to address
-    // swap_send
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::HaloSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0000".to_string(),
-            },
-        },
-        to: Some("addr0000".to_string()),
+/// The reply of a hop, the swap event is emitted by a token of the path
+/// and does not report the returned amount
+fn swap_reply() -> Reply {
+    Reply {
+        id: SWAP_OPERATION_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("wasm").add_attributes(vec![
+                attr("action", "swap"),
+                attr("return_amount", "99999999"),
+            ])],
+            data: None,
+        }),
+    }
+}
+
+#[test]
+fn execute_swap_operations_reply() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
     };
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(
-            asset_into_swap_msg(
-                deps.as_ref(),
-                Addr::unchecked("pair0000"),
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: "uusd".to_string(),
-                    },
-                    amount: Uint128::from(1000000u128)
-                },
-                None,
-                Some("addr0000".to_string())
-            )
-            .unwrap()
-        )],
-    );
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
     deps.querier.with_halo_factory(
         &[(
-            &"assetuusd".to_string(),
+            &"asset0000uluna".to_string(),
             &PairInfo {
                 asset_infos: [
                     AssetInfo::Token {
-                        contract_addr: "asset".to_string(),
+                        contract_addr: "asset0000".to_string(),
                     },
                     AssetInfo::NativeToken {
-                        denom: "uusd".to_string(),
+                        denom: "uluna".to_string(),
                     },
                 ],
-                contract_addr: "pair0000".to_string(),
-                liquidity_token: "liquidity0000".to_string(),
+                contract_addr: "pair0001".to_string(),
+                liquidity_token: "liquidity0001".to_string(),
                 asset_decimals: [6u8, 6u8],
+                asset_symbols: None,
+                incentive_contract: None,
                 requirements: CreatePairRequirements {
                     whitelist: vec![Addr::unchecked("deployer")],
                     first_asset_minimum: Uint128::zero(),
@@ -396,45 +451,198 @@ fn execute_swap_operation() {
                 },
             },
         )],
-        &[("uusd".to_string(), 6u8)],
+        &[("uluna".to_string(), 6u8)],
     );
+
+    let operations = vec![
+        SwapOperation::HaloSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        },
+        SwapOperation::HaloSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        },
+    ];
+    let offer_asset = Asset {
+        info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        amount: Uint128::from(1000000u128),
+    };
+    SWAP_CONTEXT
+        .save(
+            deps.as_mut().storage,
+            &SwapContext {
+                operations: operations.clone(),
+                ask_balance: Uint128::from(50000u128),
+                offer_asset: offer_asset.clone(),
+                minimum_receive: Some(Uint128::from(900000u128)),
+                receiver: Addr::unchecked("addr0000"),
+                callback: None,
+            },
+        )
+        .unwrap();
+
+    // the returned amount of the first hop is the increase of the router balance
+    // and it is swapped in the next hop
     deps.querier.with_token_balances(&[(
-        &"asset".to_string(),
+        &"asset0000".to_string(),
         &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
     )]);
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![coin(80000u128, "uluna")],
+    )]);
+    let res = reply(deps.as_mut(), mock_env(), swap_reply()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pair0001".to_string(),
+                    amount: Uint128::from(950000u128),
+                    msg: to_json_binary(&PairHookMsg::Swap {
+                        belief_price: None,
+                        max_spread: None,
+                        to: None,
+                        callback: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+            }),
+            SWAP_OPERATION_REPLY_ID,
+        )]
+    );
+    let swap_context = SWAP_CONTEXT.load(deps.as_ref().storage).unwrap();
+    assert_eq!(swap_context.operations, vec![operations[1].clone()]);
+    assert_eq!(swap_context.ask_balance, Uint128::from(80000u128));
 
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::HaloSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset".to_string(),
+    // the returned amount of the last hop is delivered to the receiver
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![coin(1000000u128, "uluna")],
+    )]);
+    let res = reply(deps.as_mut(), mock_env(), swap_reply()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![coin(920000u128, "uluna")],
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "execute_swap_operations"),
+            attr("receiver", "addr0000"),
+            attr("offer_asset", "1000000ukrw"),
+            attr("return_asset", "920000uluna"),
+        ]
+    );
+
+    let data: ExecuteSwapOperationsResponse = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        ExecuteSwapOperationsResponse {
+            offer_asset,
+            return_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                amount: Uint128::from(920000u128),
             },
-            ask_asset_info: AssetInfo::NativeToken {
+            receiver: "addr0000".to_string(),
+        }
+    );
+    assert!(SWAP_CONTEXT
+        .may_load(deps.as_ref().storage)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn execute_swap_operations_reply_minimum_receive() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let swap_context = SwapContext {
+        operations: vec![SwapOperation::HaloSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+        }],
+        ask_balance: Uint128::zero(),
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
+            amount: Uint128::from(1000000u128),
         },
-        to: Some("addr0000".to_string()),
+        minimum_receive: Some(Uint128::from(1000000u128)),
+        receiver: Addr::unchecked("addr0000"),
+        callback: None,
     };
 
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // success
+    SWAP_CONTEXT
+        .save(deps.as_mut().storage, &swap_context)
+        .unwrap();
+    deps.querier.with_token_balances(&[(
+        &"token0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
+    )]);
+    let res = reply(deps.as_mut(), mock_env(), swap_reply()).unwrap();
     assert_eq!(
         res.messages,
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "asset".to_string(),
+            contract_addr: "token0000".to_string(),
             funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: "pair0000".to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
                 amount: Uint128::from(1000000u128),
-                msg: to_binary(&PairHookMsg::Swap {
-                    belief_price: None,
-                    max_spread: None,
-                    to: Some("addr0000".to_string()),
-                })
-                .unwrap()
             })
-            .unwrap()
+            .unwrap(),
         }))]
     );
+
+    // assertion failed
+    SWAP_CONTEXT
+        .save(deps.as_mut().storage, &swap_context)
+        .unwrap();
+    deps.querier.with_token_balances(&[(
+        &"token0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(999999u128))],
+    )]);
+    let res = reply(deps.as_mut(), mock_env(), swap_reply());
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "assertion failed; minimum receive amount: 1000000, swap amount: 999999"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 }
 
 #[test]
@@ -443,6 +651,7 @@ fn query_buy_with_routes() {
 
     let msg = InstantiateMsg {
         halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -470,6 +679,7 @@ fn query_buy_with_routes() {
                 },
             },
         ],
+        referral_commission: None,
     };
 
     deps.querier.with_halo_factory(
@@ -488,6 +698,8 @@ fn query_buy_with_routes() {
                     contract_addr: "pair0000".to_string(),
                     liquidity_token: "liquidity0000".to_string(),
                     asset_decimals: [6u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
                     requirements: CreatePairRequirements {
                         whitelist: vec![Addr::unchecked("deployer")],
                         first_asset_minimum: Uint128::zero(),
@@ -509,6 +721,8 @@ fn query_buy_with_routes() {
                     contract_addr: "pair0001".to_string(),
                     liquidity_token: "liquidity0001".to_string(),
                     asset_decimals: [6u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
                     requirements: CreatePairRequirements {
                         whitelist: vec![Addr::unchecked("deployer")],
                         first_asset_minimum: Uint128::zero(),
@@ -521,7 +735,7 @@ fn query_buy_with_routes() {
     );
 
     let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
     assert_eq!(
         res,
         SimulateSwapOperationsResponse {
@@ -536,6 +750,7 @@ fn query_reverse_routes_with_from_native() {
 
     let msg = InstantiateMsg {
         halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
     };
 
     let target_amount = 1000000u128;
@@ -554,10 +769,16 @@ fn query_reverse_routes_with_from_native() {
         .to_vec(),
     )]);
 
-    deps.querier.with_token_balances(&[(
-        &"asset0001".to_string(),
-        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
-    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+        ),
+        (
+            &"asset0001".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
+        ),
+    ]);
 
     let msg = QueryMsg::ReverseSimulateSwapOperations {
         ask_amount: Uint128::from(target_amount),
@@ -569,6 +790,7 @@ fn query_reverse_routes_with_from_native() {
                 contract_addr: "asset0000".to_string(),
             },
         }],
+        referral_commission: None,
     };
 
     deps.querier.with_halo_factory(
@@ -587,6 +809,8 @@ fn query_reverse_routes_with_from_native() {
                         },
                     ],
                     asset_decimals: [8u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
                     requirements: CreatePairRequirements {
                         whitelist: vec![Addr::unchecked("deployer")],
                         first_asset_minimum: Uint128::zero(),
@@ -608,6 +832,8 @@ fn query_reverse_routes_with_from_native() {
                         },
                     ],
                     asset_decimals: [8u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
                     requirements: CreatePairRequirements {
                         whitelist: vec![Addr::unchecked("deployer")],
                         first_asset_minimum: Uint128::zero(),
@@ -620,7 +846,7 @@ fn query_reverse_routes_with_from_native() {
     );
 
     let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
 
     assert_eq!(
         res,
@@ -631,48 +857,82 @@ fn query_reverse_routes_with_from_native() {
 
     let offer_amount = res.amount;
 
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::HaloSwap {
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: vec![SwapOperation::HaloSwap {
             offer_asset_info: AssetInfo::NativeToken {
                 denom: "ukrw".to_string(),
             },
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
-        },
+        }],
+        minimum_receive: None,
         to: None,
+        referral_address: None,
+        referral_commission: None,
+        callback: None,
     };
     let info = mock_info("addr0", &[coin(offer_amount.u128(), "ukrw")]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     assert_eq!(
         res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "pair0000".to_string(),
-            funds: vec![coin(target_amount, "ukrw")],
-            msg: to_binary(&PairHookMsg::Swap {
-                belief_price: None,
-                max_spread: None,
-                to: None,
-            })
-            .unwrap(),
-        })),],
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair0000".to_string(),
+                funds: vec![coin(target_amount, "ukrw")],
+                msg: to_json_binary(&PairHookMsg::Swap {
+                    belief_price: None,
+                    max_spread: None,
+                    to: None,
+                    callback: None,
+                })
+                .unwrap(),
+            }),
+            SWAP_OPERATION_REPLY_ID,
+        )],
     );
 }
 
+#[test]
+fn query_reverse_routes_with_missing_pair() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the query fails with an error instead of aborting
+    let msg = QueryMsg::ReverseSimulateSwapOperations {
+        ask_amount: Uint128::from(1000000u128),
+        operations: vec![SwapOperation::HaloSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+        }],
+        referral_commission: None,
+    };
+
+    match query(deps.as_ref(), mock_env(), msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert!(msg.contains("No pair info exists")),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
 #[test]
 fn query_reverse_routes_with_to_native() {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
         halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
     };
 
     let target_amount = 1000000u128;
@@ -703,6 +963,7 @@ fn query_reverse_routes_with_to_native() {
                 denom: "ukrw".to_string(),
             },
         }],
+        referral_commission: None,
     };
 
     deps.querier.with_halo_factory(
@@ -721,6 +982,8 @@ fn query_reverse_routes_with_to_native() {
                         },
                     ],
                     asset_decimals: [8u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
                     requirements: CreatePairRequirements {
                         whitelist: vec![Addr::unchecked("deployer")],
                         first_asset_minimum: Uint128::zero(),
@@ -742,6 +1005,8 @@ fn query_reverse_routes_with_to_native() {
                         },
                     ],
                     asset_decimals: [8u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
                     requirements: CreatePairRequirements {
                         whitelist: vec![Addr::unchecked("deployer")],
                         first_asset_minimum: Uint128::zero(),
@@ -754,7 +1019,7 @@ fn query_reverse_routes_with_to_native() {
     );
 
     let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
 
     assert_eq!(
         res,
@@ -768,7 +1033,7 @@ fn query_reverse_routes_with_to_native() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0".to_string(),
         amount: offer_amount,
-        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
+        msg: to_json_binary(&Cw20HookMsg::ExecuteSwapOperations {
             operations: vec![SwapOperation::HaloSwap {
                 offer_asset_info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -779,6 +1044,9 @@ fn query_reverse_routes_with_to_native() {
             }],
             minimum_receive: None,
             to: None,
+            referral_address: None,
+            referral_commission: None,
+            callback: None,
         })
         .unwrap(),
     });
@@ -787,137 +1055,435 @@ fn query_reverse_routes_with_to_native() {
 
     assert_eq!(
         res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-            funds: vec![],
-            msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                operation: SwapOperation::HaloSwap {
-                    offer_asset_info: AssetInfo::Token {
-                        contract_addr: "asset0000".to_string(),
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pair0000".to_string(),
+                    amount: Uint128::from(target_amount),
+                    msg: to_json_binary(&PairHookMsg::Swap {
+                        belief_price: None,
+                        max_spread: None,
+                        to: None,
+                        callback: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+            }),
+            SWAP_OPERATION_REPLY_ID,
+        )],
+    );
+}
+
+#[test]
+fn execute_swap_operations_with_referral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_halo_factory(
+        &[
+            (
+                &"ukrwasset0001".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                        AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: "liquidity0000".to_string(),
+                    asset_decimals: [6u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
+                    requirements: CreatePairRequirements {
+                        whitelist: vec![Addr::unchecked("deployer")],
+                        first_asset_minimum: Uint128::zero(),
+                        second_asset_minimum: Uint128::zero(),
                     },
-                    ask_asset_info: AssetInfo::NativeToken {
-                        denom: "ukrw".to_string(),
+                },
+            ),
+            (
+                &"asset0000ukrw".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: "liquidity0001".to_string(),
+                    asset_decimals: [6u8, 6u8],
+                    asset_symbols: None,
+                    incentive_contract: None,
+                    requirements: CreatePairRequirements {
+                        whitelist: vec![Addr::unchecked("deployer")],
+                        first_asset_minimum: Uint128::zero(),
+                        second_asset_minimum: Uint128::zero(),
                     },
                 },
-                to: Some("addr0".to_string()),
-            })
-            .unwrap(),
-        })),],
+            ),
+        ],
+        &[("ukrw".to_string(), 6u8)],
     );
 
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::HaloSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0000".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
+    let operations = vec![SwapOperation::HaloSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
         },
+    }];
+
+    // referral commission exceeds the maximum
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
         to: None,
+        referral_address: Some("referral0000".to_string()),
+        referral_commission: Some(Decimal::percent(2)),
+        callback: None,
     };
+    let info = mock_info("addr0000", &[coin(1000000u128, "ukrw")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "referral_commission exceeds the maximum; max referral commission: 0.01"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // referral address without commission
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
+        to: None,
+        referral_address: Some("referral0000".to_string()),
+        referral_commission: None,
+        callback: None,
+    };
+    let info = mock_info("addr0000", &[coin(1000000u128, "ukrw")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "referral_address and referral_commission must be provided together"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
+    // the commission is paid in the offer asset before the first hop
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+    )]);
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
+        to: None,
+        referral_address: Some("referral0000".to_string()),
+        referral_commission: Some(Decimal::percent(1)),
+        callback: None,
+    };
+    let info = mock_info("addr0000", &[coin(1000000u128, "ukrw")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
         res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "referral0000".to_string(),
+                amount: vec![coin(10000u128, "ukrw")],
+            })),
+            SubMsg::reply_on_success(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "pair0000".to_string(),
+                    funds: vec![coin(990000u128, "ukrw")],
+                    msg: to_json_binary(&PairHookMsg::Swap {
+                        belief_price: None,
+                        max_spread: None,
+                        to: None,
+                        callback: None,
+                    })
+                    .unwrap(),
+                }),
+                SWAP_OPERATION_REPLY_ID,
+            ),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("referral_address", "referral0000"),
+            attr("referral_amount", "10000"),
+        ]
+    );
+
+    // cw20 offer asset
+    SWAP_CONTEXT.remove(deps.as_mut().storage);
+    let operations = vec![SwapOperation::HaloSwap {
+        offer_asset_info: AssetInfo::Token {
             contract_addr: "asset0000".to_string(),
-            funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: "pair0000".to_string(),
-                amount: Uint128::from(target_amount),
-                msg: to_binary(&PairHookMsg::Swap {
-                    belief_price: None,
-                    max_spread: None,
-                    to: None,
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+    }];
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_json_binary(&Cw20HookMsg::ExecuteSwapOperations {
+            operations: operations.clone(),
+            minimum_receive: None,
+            to: None,
+            referral_address: Some("referral0000".to_string()),
+            referral_commission: Some(Decimal::permille(5)),
+            callback: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "referral0000".to_string(),
+                    amount: Uint128::from(5000u128),
                 })
                 .unwrap(),
-            })
-            .unwrap(),
-        }))],
+            })),
+            SubMsg::reply_on_success(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "asset0000".to_string(),
+                    funds: vec![],
+                    msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                        contract: "pair0001".to_string(),
+                        amount: Uint128::from(995000u128),
+                        msg: to_json_binary(&PairHookMsg::Swap {
+                            belief_price: None,
+                            max_spread: None,
+                            to: None,
+                            callback: None,
+                        })
+                        .unwrap(),
+                    })
+                    .unwrap(),
+                }),
+                SWAP_OPERATION_REPLY_ID,
+            ),
+        ]
     );
 }
 
 #[test]
-fn assert_minimum_receive_native_token() {
+fn query_routes_with_referral() {
     let mut deps = mock_dependencies(&[]);
-    deps.querier.with_balance(&[(
-        &"addr0000".to_string(),
-        [Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(1000000u128),
-        }]
-        .to_vec(),
-    )]);
+
+    let msg = InstantiateMsg {
+        halo_factory: "halofactory".to_string(),
+        max_referral_commission: Decimal::percent(1),
+    };
 
     let info = mock_info("addr0000", &[]);
-    // success
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_halo_factory(
+        &[(
+            &"ukrwasset0000".to_string(),
+            &PairInfo {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                ],
+                contract_addr: "pair0000".to_string(),
+                liquidity_token: "liquidity0000".to_string(),
+                asset_decimals: [6u8, 6u8],
+                asset_symbols: None,
+                incentive_contract: None,
+                requirements: CreatePairRequirements {
+                    whitelist: vec![Addr::unchecked("deployer")],
+                    first_asset_minimum: Uint128::zero(),
+                    second_asset_minimum: Uint128::zero(),
+                },
+            },
+        )],
+        &[("ukrw".to_string(), 6u8)],
+    );
+
+    let operations = vec![SwapOperation::HaloSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
         },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000000u128),
-        receiver: "addr0000".to_string(),
+    }];
+
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(1000000u128),
+        operations: operations.clone(),
+        referral_commission: Some(Decimal::percent(1)),
+    };
+    let res: SimulateSwapOperationsResponse =
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(990000u128)
+        }
+    );
+
+    // the offer amount covers the referral commission
+    let msg = QueryMsg::ReverseSimulateSwapOperations {
+        ask_amount: Uint128::from(990000u128),
+        operations: operations.clone(),
+        referral_commission: Some(Decimal::percent(1)),
     };
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let res: SimulateSwapOperationsResponse =
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(1000000u128)
+        }
+    );
 
-    // assertion failed; native token
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000001u128),
-        receiver: "addr0000".to_string(),
+    // rounding up when the ask amount is not evenly divisible
+    let msg = QueryMsg::ReverseSimulateSwapOperations {
+        ask_amount: Uint128::from(1001u128),
+        operations: operations.clone(),
+        referral_commission: Some(Decimal::percent(1)),
     };
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    let res: SimulateSwapOperationsResponse =
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(1011u128)
+        }
+    );
+
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(1000000u128),
+        operations,
+        referral_commission: Some(Decimal::percent(2)),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg);
     match res {
         Err(StdError::GenericErr { msg, .. }) => assert_eq!(
             msg,
-            "assertion failed; minimum receive amount: 1000001, swap amount: 1000000"
+            "referral_commission exceeds the maximum; max referral commission: 0.01"
         ),
         _ => panic!("DO NOT ENTER HERE"),
     }
 }
 
 #[test]
-fn assert_minimum_receive_token() {
+fn migrate_from_v0_1() {
     let mut deps = mock_dependencies(&[]);
-    deps.querier.with_token_balances(&[(
-        &"token0000".to_string(),
-        &[(&"addr0000".to_string(), &Uint128::from(1000000u128))],
-    )]);
 
-    let info = mock_info("addr0000", &[]);
-    // success
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::Token {
-            contract_addr: "token0000".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000000u128),
-        receiver: "addr0000".to_string(),
+    // the config layout before 0.2.0
+    #[cw_serde]
+    struct ConfigV0_1 {
+        halo_factory: cosmwasm_std::CanonicalAddr,
+    }
+    let halo_factory = deps.as_ref().api.addr_canonicalize("halofactory").unwrap();
+    Item::new("config")
+        .save(deps.as_mut().storage, &ConfigV0_1 { halo_factory })
+        .unwrap();
+
+    // the contract name must match
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-pair", "0.1.0").unwrap();
+    let msg = MigrateMsg {
+        max_referral_commission: Some(Decimal::percent(1)),
     };
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    match migrate(deps.as_mut(), mock_env(), msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "cannot migrate from a different contract: crates.io:halo-pair"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
-    // assertion failed; native token
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::Token {
-            contract_addr: "token0000".to_string(),
+    // cannot downgrade
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-router", "99.0.0").unwrap();
+    match migrate(deps.as_mut(), mock_env(), msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "cannot migrate from a newer version: 99.0.0")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // the referral commission is required to upgrade the config
+    set_contract_version(deps.as_mut().storage, "crates.io:halo-router", "0.1.0").unwrap();
+    match migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            max_referral_commission: None,
         },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000001u128),
-        receiver: "addr0000".to_string(),
-    };
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
+    ) {
         Err(StdError::GenericErr { msg, .. }) => assert_eq!(
             msg,
-            "assertion failed; minimum receive amount: 1000001, swap amount: 1000000"
+            "max_referral_commission is required to migrate to 0.2.0"
         ),
         _ => panic!("DO NOT ENTER HERE"),
     }
+
+    let res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("from_version", "0.1.0"),
+            attr("to_version", env!("CARGO_PKG_VERSION")),
+        ]
+    );
+
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            halo_factory: "halofactory".to_string(),
+            max_referral_commission: Decimal::percent(1),
+        }
+    );
+    assert_eq!(
+        get_contract_version(deps.as_ref().storage).unwrap().version,
+        env!("CARGO_PKG_VERSION")
+    );
+
+    // migrating the same version keeps the config
+    let res = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            max_referral_commission: None,
+        },
+    );
+    assert!(res.is_ok());
 }
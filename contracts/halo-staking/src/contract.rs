@@ -0,0 +1,645 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Api, Binary, CanonicalAddr, CosmosMsg, Decimal256, Deps,
+    DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ReceiveMsg;
+use cw_utils::one_coin;
+
+use crate::error::ContractError;
+use crate::state::{read_pools, Config, PoolInfo, PoolReward, StakerInfo, CONFIG, POOLS, STAKERS};
+
+use haloswap::asset::{is_token_factory_denom, Asset, AssetInfo};
+use haloswap::migration::assert_migrate_version;
+use haloswap::querier::query_pair_info_by_liquidity_token;
+use haloswap::staking::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse,
+    PoolRewardResponse, PoolsResponse, QueryMsg, Schedule, StakerResponse,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:halo-staking";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The reward assets of a pool are bounded to keep the updates of the pool cheap
+const MAX_REWARDS_PER_POOL: usize = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            halo_factory: deps.api.addr_canonicalize(&msg.halo_factory)?,
+            gauge: None,
+        },
+    )?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig { owner, gauge } => update_config(deps, info, owner, gauge),
+        ExecuteMsg::RegisterPool { lp_token } => register_pool(deps, env, info, lp_token),
+        ExecuteMsg::Bond {} => {
+            let lp_token = one_coin(&info)?;
+            bond(
+                deps,
+                env,
+                AssetInfo::NativeToken {
+                    denom: lp_token.denom,
+                },
+                info.sender,
+                lp_token.amount,
+            )
+        }
+        ExecuteMsg::AddSchedule {
+            lp_token,
+            reward,
+            start_time,
+            end_time,
+        } => {
+            if !reward.is_native_token() {
+                return Err(ContractError::Cw20RewardNotReceived {});
+            }
+            reward.assert_sent_native_token_balance(&info)?;
+
+            add_schedule(
+                deps,
+                env,
+                info.sender,
+                lp_token,
+                reward,
+                start_time,
+                end_time,
+            )
+        }
+        ExecuteMsg::Unbond { lp_token, amount } => unbond(deps, env, info, lp_token, amount),
+        ExecuteMsg::Claim { lp_token } => claim(deps, env, info, lp_token),
+        ExecuteMsg::EmergencyUnbond { lp_token } => emergency_unbond(deps, env, info, lp_token),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_validate(&cw20_msg.sender)?;
+
+    match from_json(&cw20_msg.msg)? {
+        // only the lp token of a registered pool can be bonded
+        Cw20HookMsg::Bond {} => bond(
+            deps,
+            env,
+            AssetInfo::Token {
+                contract_addr: info.sender.to_string(),
+            },
+            sender,
+            cw20_msg.amount,
+        ),
+        Cw20HookMsg::AddSchedule {
+            lp_token,
+            start_time,
+            end_time,
+        } => add_schedule(
+            deps,
+            env,
+            sender,
+            lp_token,
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            },
+            start_time,
+            end_time,
+        ),
+    }
+}
+
+// Only owner can execute it
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    gauge: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    if let Some(gauge) = gauge {
+        config.gauge = if gauge.is_empty() {
+            None
+        } else {
+            Some(
+                deps.api
+                    .addr_canonicalize(deps.api.addr_validate(&gauge)?.as_str())?,
+            )
+        };
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+// Only owner can execute it
+pub fn register_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the native liquidity shares are bonded as funds
+    let liquidity_denom = if is_token_factory_denom(&lp_token) {
+        Some(lp_token.clone())
+    } else {
+        deps.api.addr_validate(&lp_token)?;
+        None
+    };
+    let lp_token_raw = pool_key(deps.api, &lp_token)?;
+    if POOLS.has(deps.storage, &lp_token_raw) {
+        return Err(ContractError::PoolAlreadyRegistered {});
+    }
+
+    // the liquidity token must belong to a pair of the factory
+    let pair_info = query_pair_info_by_liquidity_token(
+        &deps.querier,
+        deps.api.addr_humanize(&config.halo_factory)?,
+        &lp_token,
+    )?;
+
+    POOLS.save(
+        deps.storage,
+        &lp_token_raw,
+        &PoolInfo {
+            pair_contract: deps.api.addr_canonicalize(&pair_info.contract_addr)?,
+            total_bond_amount: Uint128::zero(),
+            last_updated: env.block.time.seconds(),
+            rewards: vec![],
+            liquidity_denom,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_pool"),
+        ("lp_token", lp_token.as_str()),
+        ("pair_contract_addr", &pair_info.contract_addr),
+    ]))
+}
+
+// Only owner or gauge can execute it
+pub fn add_schedule(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    lp_token: String,
+    reward: Asset,
+    start_time: u64,
+    end_time: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    let sender_raw = deps.api.addr_canonicalize(sender.as_str())?;
+    if sender_raw != config.owner && Some(sender_raw) != config.gauge {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if reward.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let now = env.block.time.seconds();
+    if start_time < now || end_time <= start_time {
+        return Err(ContractError::InvalidSchedule {});
+    }
+
+    let lp_token_raw = pool_key(deps.api, &lp_token)?;
+    let mut pool = load_pool(deps.as_ref(), &lp_token_raw)?;
+    pool.update(now)?;
+
+    let reward_info = reward.info.to_raw(deps.api)?;
+    let schedule = Schedule {
+        start_time,
+        end_time,
+        amount: reward.amount,
+    };
+    match pool
+        .rewards
+        .iter_mut()
+        .find(|pool_reward| pool_reward.info.equal(&reward_info))
+    {
+        Some(pool_reward) => pool_reward.schedules.push(schedule),
+        None => {
+            if pool.rewards.len() >= MAX_REWARDS_PER_POOL {
+                return Err(ContractError::TooManyRewards {});
+            }
+
+            pool.rewards.push(PoolReward {
+                info: reward_info,
+                global_index: Decimal256::zero(),
+                schedules: vec![schedule],
+                undistributed: Uint128::zero(),
+            });
+        }
+    }
+
+    POOLS.save(deps.storage, &lp_token_raw, &pool)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_schedule"),
+        ("lp_token", &lp_token),
+        ("reward", &reward.to_string()),
+        ("start_time", &start_time.to_string()),
+        ("end_time", &end_time.to_string()),
+    ]))
+}
+
+pub fn bond(
+    deps: DepsMut,
+    env: Env,
+    lp_token: AssetInfo,
+    staker: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let lp_token_raw = match &lp_token {
+        AssetInfo::Token { contract_addr } => deps.api.addr_canonicalize(contract_addr)?.to_vec(),
+        AssetInfo::NativeToken { denom } => denom.as_bytes().to_vec(),
+    };
+    let staker_raw = deps.api.addr_canonicalize(staker.as_str())?;
+    let mut pool = load_pool(deps.as_ref(), &lp_token_raw)?;
+    // the cw20 liquidity tokens and the native liquidity shares are bonded each their own way
+    if liquidity_asset_info(deps.api, &lp_token_raw, &pool)? != lp_token {
+        return Err(ContractError::PoolNotFound {});
+    }
+    pool.update(env.block.time.seconds())?;
+
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, (&lp_token_raw, &staker_raw))?
+        .unwrap_or_default();
+    staker_info.accrue(&pool)?;
+
+    staker_info.bond_amount = staker_info.bond_amount.checked_add(amount)?;
+    pool.total_bond_amount = pool.total_bond_amount.checked_add(amount)?;
+
+    STAKERS.save(deps.storage, (&lp_token_raw, &staker_raw), &staker_info)?;
+    POOLS.save(deps.storage, &lp_token_raw, &pool)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "bond"),
+        ("lp_token", &lp_token.to_string()),
+        ("staker", staker.as_str()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+pub fn unbond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let lp_token_raw = pool_key(deps.api, &lp_token)?;
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut pool = load_pool(deps.as_ref(), &lp_token_raw)?;
+    pool.update(env.block.time.seconds())?;
+
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, (&lp_token_raw, &staker_raw))?
+        .unwrap_or_default();
+    if staker_info.bond_amount < amount {
+        return Err(ContractError::InsufficientBond {});
+    }
+    staker_info.accrue(&pool)?;
+
+    staker_info.bond_amount -= amount;
+    pool.total_bond_amount = pool.total_bond_amount.checked_sub(amount)?;
+
+    save_staker(deps.storage, &lp_token_raw, &staker_raw, &staker_info)?;
+    POOLS.save(deps.storage, &lp_token_raw, &pool)?;
+
+    Ok(Response::new()
+        .add_message(
+            Asset {
+                info: liquidity_asset_info(deps.api, &lp_token_raw, &pool)?,
+                amount,
+            }
+            .into_msg(info.sender.clone())?,
+        )
+        .add_attributes(vec![
+            ("action", "unbond"),
+            ("lp_token", lp_token.as_str()),
+            ("staker", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+pub fn claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+    let lp_token_raw = pool_key(deps.api, &lp_token)?;
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut pool = load_pool(deps.as_ref(), &lp_token_raw)?;
+    pool.update(env.block.time.seconds())?;
+
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, (&lp_token_raw, &staker_raw))?
+        .unwrap_or_default();
+    staker_info.accrue(&pool)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut claimed: Vec<String> = vec![];
+    for reward in staker_info.rewards.iter_mut() {
+        if reward.pending.is_zero() {
+            continue;
+        }
+
+        let asset = Asset {
+            info: reward.info.to_normal(deps.api)?,
+            amount: reward.pending,
+        };
+        claimed.push(asset.to_string());
+        messages.push(asset.into_msg(info.sender.clone())?);
+        reward.pending = Uint128::zero();
+    }
+
+    if messages.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    save_staker(deps.storage, &lp_token_raw, &staker_raw, &staker_info)?;
+    POOLS.save(deps.storage, &lp_token_raw, &pool)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "claim"),
+        ("lp_token", &lp_token),
+        ("staker", info.sender.as_str()),
+        ("rewards", &claimed.join(",")),
+    ]))
+}
+
+pub fn emergency_unbond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+    let lp_token_raw = pool_key(deps.api, &lp_token)?;
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // the rewards released so far are distributed with the bond still counted
+    let mut pool = load_pool(deps.as_ref(), &lp_token_raw)?;
+    pool.update(env.block.time.seconds())?;
+
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, (&lp_token_raw, &staker_raw))?
+        .unwrap_or_default();
+    if staker_info.bond_amount.is_zero() {
+        return Err(ContractError::InsufficientBond {});
+    }
+
+    // the forfeited rewards go to the stakers left in the pool
+    staker_info.accrue(&pool)?;
+    staker_info.forfeit(&mut pool)?;
+
+    let amount = staker_info.bond_amount;
+    pool.total_bond_amount = pool.total_bond_amount.checked_sub(amount)?;
+
+    STAKERS.remove(deps.storage, (&lp_token_raw, &staker_raw));
+    POOLS.save(deps.storage, &lp_token_raw, &pool)?;
+
+    Ok(Response::new()
+        .add_message(
+            Asset {
+                info: liquidity_asset_info(deps.api, &lp_token_raw, &pool)?,
+                amount,
+            }
+            .into_msg(info.sender.clone())?,
+        )
+        .add_attributes(vec![
+            ("action", "emergency_unbond"),
+            ("lp_token", lp_token.as_str()),
+            ("staker", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+/// The key of the pool, the canonical address of the liquidity token or the denom of the
+/// native liquidity shares
+fn pool_key(api: &dyn Api, lp_token: &str) -> StdResult<Vec<u8>> {
+    if is_token_factory_denom(lp_token) {
+        Ok(lp_token.as_bytes().to_vec())
+    } else {
+        Ok(api.addr_canonicalize(lp_token)?.to_vec())
+    }
+}
+
+/// The liquidity token bonded to the pool
+fn liquidity_asset_info(api: &dyn Api, lp_token: &[u8], pool: &PoolInfo) -> StdResult<AssetInfo> {
+    match &pool.liquidity_denom {
+        Some(denom) => Ok(AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        }),
+        None => Ok(AssetInfo::Token {
+            contract_addr: api
+                .addr_humanize(&CanonicalAddr::from(lp_token))?
+                .to_string(),
+        }),
+    }
+}
+
+fn load_pool(deps: Deps, lp_token: &[u8]) -> Result<PoolInfo, ContractError> {
+    POOLS
+        .may_load(deps.storage, lp_token)?
+        .ok_or(ContractError::PoolNotFound {})
+}
+
+/// The staker is removed once it has neither a bond nor pending rewards
+fn save_staker(
+    storage: &mut dyn Storage,
+    lp_token: &[u8],
+    staker: &[u8],
+    staker_info: &StakerInfo,
+) -> StdResult<()> {
+    if staker_info.is_empty() {
+        STAKERS.remove(storage, (lp_token, staker));
+        Ok(())
+    } else {
+        STAKERS.save(storage, (lp_token, staker), staker_info)
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Pool { lp_token } => to_json_binary(&query_pool(deps, lp_token)?),
+        QueryMsg::Pools { start_after, limit } => {
+            to_json_binary(&query_pools(deps, start_after, limit)?)
+        }
+        QueryMsg::Staker { lp_token, staker } => {
+            to_json_binary(&query_staker(deps, env, lp_token, staker)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        halo_factory: deps.api.addr_humanize(&config.halo_factory)?.to_string(),
+        gauge: config
+            .gauge
+            .map(|gauge| deps.api.addr_humanize(&gauge))
+            .transpose()?
+            .map(|gauge| gauge.to_string()),
+    })
+}
+
+pub fn query_pool(deps: Deps, lp_token: String) -> StdResult<PoolResponse> {
+    let lp_token_raw = pool_key(deps.api, &lp_token)?;
+    let pool = POOLS.load(deps.storage, &lp_token_raw)?;
+
+    pool_response(deps, lp_token, pool)
+}
+
+pub fn query_pools(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PoolsResponse> {
+    let start_after = start_after
+        .map(|lp_token| pool_key(deps.api, &lp_token))
+        .transpose()?;
+
+    let pools = read_pools(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(lp_token, pool)| {
+            let lp_token = liquidity_asset_info(deps.api, &lp_token, &pool)?.to_string();
+            pool_response(deps, lp_token, pool)
+        })
+        .collect::<StdResult<Vec<PoolResponse>>>()?;
+
+    Ok(PoolsResponse { pools })
+}
+
+fn pool_response(deps: Deps, lp_token: String, pool: PoolInfo) -> StdResult<PoolResponse> {
+    Ok(PoolResponse {
+        lp_token,
+        pair_contract: deps.api.addr_humanize(&pool.pair_contract)?.to_string(),
+        total_bond_amount: pool.total_bond_amount,
+        last_updated: pool.last_updated,
+        rewards: pool
+            .rewards
+            .into_iter()
+            .map(|reward| {
+                Ok(PoolRewardResponse {
+                    info: reward.info.to_normal(deps.api)?,
+                    global_index: reward.global_index,
+                    schedules: reward.schedules,
+                    undistributed: reward.undistributed,
+                })
+            })
+            .collect::<StdResult<Vec<PoolRewardResponse>>>()?,
+    })
+}
+
+pub fn query_staker(
+    deps: Deps,
+    env: Env,
+    lp_token: String,
+    staker: String,
+) -> StdResult<StakerResponse> {
+    let lp_token_raw = pool_key(deps.api, &lp_token)?;
+    let staker_raw = deps.api.addr_canonicalize(&staker)?;
+
+    // the pending rewards include the rewards released until the current block
+    let mut pool = POOLS.load(deps.storage, &lp_token_raw)?;
+    pool.update(env.block.time.seconds())?;
+
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, (&lp_token_raw, &staker_raw))?
+        .unwrap_or_default();
+    staker_info.accrue(&pool)?;
+
+    Ok(StakerResponse {
+        lp_token,
+        staker,
+        bond_amount: staker_info.bond_amount,
+        pending_rewards: staker_info
+            .rewards
+            .into_iter()
+            .map(|reward| {
+                Ok(Asset {
+                    info: reward.info.to_normal(deps.api)?,
+                    amount: reward.pending,
+                })
+            })
+            .collect::<StdResult<Vec<Asset>>>()?,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
@@ -0,0 +1,42 @@
+use cosmwasm_std::{OverflowError, StdError};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Pool is not registered")]
+    PoolNotFound {},
+
+    #[error("Pool is already registered")]
+    PoolAlreadyRegistered {},
+
+    #[error("Schedule must end after it starts and must not start in the past")]
+    InvalidSchedule {},
+
+    #[error("Too many reward assets in the pool")]
+    TooManyRewards {},
+
+    #[error("Cw20 rewards must be sent through the cw20 hook")]
+    Cw20RewardNotReceived {},
+
+    #[error("Not enough bonded tokens")]
+    InsufficientBond {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
@@ -0,0 +1,172 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, Decimal256, Order, StdResult, Storage, Uint128, Uint256};
+use cw_storage_plus::{Bound, Item, Map};
+use haloswap::asset::AssetInfoRaw;
+use haloswap::staking::Schedule;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub halo_factory: CanonicalAddr,
+    /// The gauge adding the schedules voted by the HALO stakers
+    pub gauge: Option<CanonicalAddr>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+pub struct PoolReward {
+    pub info: AssetInfoRaw,
+    pub global_index: Decimal256,
+    pub schedules: Vec<Schedule>,
+    /// The reward released while nothing was bonded or forfeited by an emergency unbond,
+    /// distributed along the next release
+    #[serde(default)]
+    pub undistributed: Uint128,
+}
+
+#[cw_serde]
+pub struct PoolInfo {
+    pub pair_contract: CanonicalAddr,
+    pub total_bond_amount: Uint128,
+    pub last_updated: u64,
+    pub rewards: Vec<PoolReward>,
+    /// The token factory denom of the native liquidity shares, bonded as funds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquidity_denom: Option<String>,
+}
+
+impl PoolInfo {
+    /// Distribute the rewards released since the last update to the bonded tokens,
+    /// the rewards released while nothing is bonded are carried to the next update
+    pub fn update(&mut self, now: u64) -> StdResult<()> {
+        if now <= self.last_updated {
+            return Ok(());
+        }
+
+        for reward in self.rewards.iter_mut() {
+            let mut released = reward.undistributed;
+            for schedule in reward.schedules.iter() {
+                released =
+                    released.checked_add(released_amount(schedule, self.last_updated, now))?;
+            }
+
+            if self.total_bond_amount.is_zero() {
+                reward.undistributed = released;
+            } else {
+                reward.global_index += Decimal256::from_ratio(released, self.total_bond_amount);
+                reward.undistributed = Uint128::zero();
+            }
+
+            // finished schedules have released their whole amount
+            reward.schedules.retain(|schedule| schedule.end_time > now);
+        }
+
+        self.last_updated = now;
+        Ok(())
+    }
+}
+
+/// The amount of the schedule released between `from` and `to`, computed from the start of the
+/// schedule so that the whole amount is released whatever the update times are
+fn released_amount(schedule: &Schedule, from: u64, to: u64) -> Uint128 {
+    let duration = schedule.end_time - schedule.start_time;
+    let elapsed =
+        |time: u64| time.clamp(schedule.start_time, schedule.end_time) - schedule.start_time;
+
+    schedule.amount.multiply_ratio(elapsed(to), duration)
+        - schedule.amount.multiply_ratio(elapsed(from), duration)
+}
+
+// key : canonical address of the lp token or denom of the native liquidity shares
+pub const POOLS: Map<&[u8], PoolInfo> = Map::new("pool_info");
+
+#[cw_serde]
+pub struct StakerReward {
+    pub info: AssetInfoRaw,
+    /// The global index of the reward when the staker last accrued
+    pub index: Decimal256,
+    pub pending: Uint128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct StakerInfo {
+    pub bond_amount: Uint128,
+    pub rewards: Vec<StakerReward>,
+}
+
+impl StakerInfo {
+    /// Accrue the rewards distributed by the pool since the last accrual of the staker,
+    /// the pool must be updated beforehand
+    pub fn accrue(&mut self, pool: &PoolInfo) -> StdResult<()> {
+        for pool_reward in pool.rewards.iter() {
+            // a reward unknown to the staker was added after the staker bonded
+            let position = match self
+                .rewards
+                .iter()
+                .position(|reward| reward.info.equal(&pool_reward.info))
+            {
+                Some(position) => position,
+                None => {
+                    self.rewards.push(StakerReward {
+                        info: pool_reward.info.clone(),
+                        index: Decimal256::zero(),
+                        pending: Uint128::zero(),
+                    });
+                    self.rewards.len() - 1
+                }
+            };
+
+            let reward = &mut self.rewards[position];
+            let accrued =
+                Uint256::from(self.bond_amount).mul_floor(pool_reward.global_index - reward.index);
+            reward.pending = reward.pending.checked_add(accrued.try_into()?)?;
+            reward.index = pool_reward.global_index;
+        }
+
+        Ok(())
+    }
+
+    /// Return the pending rewards of the staker to the pool, which distributes them along its
+    /// next release, the staker must be accrued beforehand
+    pub fn forfeit(&mut self, pool: &mut PoolInfo) -> StdResult<()> {
+        for reward in self.rewards.iter_mut() {
+            if let Some(pool_reward) = pool
+                .rewards
+                .iter_mut()
+                .find(|pool_reward| pool_reward.info.equal(&reward.info))
+            {
+                pool_reward.undistributed =
+                    pool_reward.undistributed.checked_add(reward.pending)?;
+            }
+            reward.pending = Uint128::zero();
+        }
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bond_amount.is_zero() && self.rewards.iter().all(|reward| reward.pending.is_zero())
+    }
+}
+
+// key : (lp token, staker)
+pub const STAKERS: Map<(&[u8], &[u8]), StakerInfo> = Map::new("staker_info");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_pools(
+    storage: &dyn Storage,
+    start_after: Option<Vec<u8>>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(Vec<u8>, PoolInfo)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::ExclusiveRaw);
+
+    POOLS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
@@ -0,0 +1,721 @@
+use crate::contract::{execute, instantiate, query_config, query_pool, query_pools, query_staker};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    to_json_binary, BankMsg, Coin, CosmosMsg, Decimal256, Env, OwnedDeps, StdError, SubMsg,
+    Timestamp, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_utils::PaymentError;
+use haloswap::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo};
+use haloswap::mock_querier::{mock_dependencies, WasmMockQuerier};
+use haloswap::staking::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, PoolRewardResponse,
+    Schedule,
+};
+
+const START: u64 = 1_600_000_000;
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn setup(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    deps.querier.with_halo_factory(
+        &[(
+            &"uusdasset0000".to_string(),
+            &PairInfo {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                ],
+                contract_addr: "pair0000".to_string(),
+                liquidity_token: "liquidity0000".to_string(),
+                asset_decimals: [6u8, 6u8],
+                requirements: CreatePairRequirements {
+                    whitelist: vec![],
+                    first_asset_minimum: Uint128::zero(),
+                    second_asset_minimum: Uint128::zero(),
+                },
+                asset_symbols: None,
+                incentive_contract: None,
+            },
+        )],
+        &[],
+    );
+
+    let msg = InstantiateMsg {
+        halo_factory: "factory0000".to_string(),
+    };
+    instantiate(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::RegisterPool {
+        lp_token: "liquidity0000".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+}
+
+fn bond_msg(staker: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: staker.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_json_binary(&Cw20HookMsg::Bond {}).unwrap(),
+    })
+}
+
+fn pending_rewards(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    seconds: u64,
+    staker: &str,
+) -> Vec<Uint128> {
+    query_staker(
+        deps.as_ref(),
+        env_at(seconds),
+        "liquidity0000".to_string(),
+        staker.to_string(),
+    )
+    .unwrap()
+    .pending_rewards
+    .into_iter()
+    .map(|reward| reward.amount)
+    .collect()
+}
+
+#[test]
+fn register_pool() {
+    let mut deps = mock_dependencies(&[]);
+    setup(&mut deps);
+
+    assert_eq!(
+        query_config(deps.as_ref()).unwrap(),
+        ConfigResponse {
+            owner: "owner0000".to_string(),
+            halo_factory: "factory0000".to_string(),
+            gauge: None,
+        }
+    );
+    assert_eq!(
+        query_pool(deps.as_ref(), "liquidity0000".to_string()).unwrap(),
+        PoolResponse {
+            lp_token: "liquidity0000".to_string(),
+            pair_contract: "pair0000".to_string(),
+            total_bond_amount: Uint128::zero(),
+            last_updated: START,
+            rewards: vec![],
+        }
+    );
+
+    // only the owner registers pools
+    let msg = ExecuteMsg::RegisterPool {
+        lp_token: "liquidity0001".to_string(),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+
+    // the liquidity token must belong to a pair of the factory
+    match execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    ) {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => {
+            assert!(msg.contains("No pair info exists"))
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    let msg = ExecuteMsg::RegisterPool {
+        lp_token: "liquidity0000".to_string(),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("owner0000", &[]),
+            msg
+        ),
+        Err(ContractError::PoolAlreadyRegistered {})
+    );
+
+    // the gauge adds schedules along the owner
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        gauge: Some("gauge0000".to_string()),
+    };
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_config(deps.as_ref()).unwrap().gauge,
+        Some("gauge0000".to_string())
+    );
+
+    let msg = ExecuteMsg::AddSchedule {
+        lp_token: "liquidity0000".to_string(),
+        reward: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uaura".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        start_time: START,
+        end_time: START + 100,
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0000", &[Coin::new(100, "uaura")]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("gauge0000", &[Coin::new(100, "uaura")]),
+        msg,
+    )
+    .unwrap();
+}
+
+#[test]
+fn distribute_native_rewards() {
+    let mut deps = mock_dependencies(&[]);
+    setup(&mut deps);
+
+    let reward = Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uaura".to_string(),
+        },
+        amount: Uint128::from(1_000_000u128),
+    };
+    let msg = ExecuteMsg::AddSchedule {
+        lp_token: "liquidity0000".to_string(),
+        reward: reward.clone(),
+        start_time: START + 100,
+        end_time: START + 1100,
+    };
+
+    // the reward must be sent with the message
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("owner0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Std(StdError::generic_err(
+            "Native token balance mismatch between the argument and the transferred"
+        )))
+    );
+
+    // schedules cannot start in the past
+    let past_msg = ExecuteMsg::AddSchedule {
+        lp_token: "liquidity0000".to_string(),
+        reward: reward.clone(),
+        start_time: START - 1,
+        end_time: START + 1100,
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("owner0000", &[Coin::new(1_000_000, "uaura")]),
+            past_msg
+        ),
+        Err(ContractError::InvalidSchedule {})
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[Coin::new(1_000_000, "uaura")]),
+        msg,
+    )
+    .unwrap();
+
+    // only the lp token of a registered pool can be bonded
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("liquidity0001", &[]),
+            bond_msg("addr0000", 100)
+        ),
+        Err(ContractError::PoolNotFound {})
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("liquidity0000", &[]),
+        bond_msg("addr0000", 100),
+    )
+    .unwrap();
+
+    // a quarter of the schedule is released to the only staker
+    execute(
+        deps.as_mut(),
+        env_at(START + 350),
+        mock_info("liquidity0000", &[]),
+        bond_msg("addr0001", 300),
+    )
+    .unwrap();
+    assert_eq!(
+        pending_rewards(&deps, START + 350, "addr0000"),
+        vec![Uint128::from(250_000u128)]
+    );
+    assert_eq!(
+        pending_rewards(&deps, START + 350, "addr0001"),
+        vec![Uint128::zero()]
+    );
+
+    // the rest is shared by the bonds, nothing is released after the end
+    assert_eq!(
+        pending_rewards(&deps, START + 2000, "addr0000"),
+        vec![Uint128::from(437_500u128)]
+    );
+    assert_eq!(
+        pending_rewards(&deps, START + 2000, "addr0001"),
+        vec![Uint128::from(562_500u128)]
+    );
+
+    let msg = ExecuteMsg::Claim {
+        lp_token: "liquidity0000".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 2000),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin::new(437_500, "uaura")],
+        }))]
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 2000),
+            mock_info("addr0000", &[]),
+            msg
+        ),
+        Err(ContractError::NothingToClaim {})
+    );
+
+    // the finished schedule is pruned from the pool
+    let pool = query_pool(deps.as_ref(), "liquidity0000".to_string()).unwrap();
+    assert_eq!(pool.total_bond_amount, Uint128::from(400u128));
+    assert_eq!(
+        pool.rewards,
+        vec![PoolRewardResponse {
+            info: reward.info,
+            global_index: Decimal256::from_ratio(250_000u128, 100u128)
+                + Decimal256::from_ratio(750_000u128, 400u128),
+            schedules: vec![],
+            undistributed: Uint128::zero(),
+        }]
+    );
+}
+
+#[test]
+fn unbond_and_emergency_unbond() {
+    let mut deps = mock_dependencies(&[]);
+    setup(&mut deps);
+
+    let schedule_msg = |sender: &str| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(1_000u128),
+            msg: to_json_binary(&Cw20HookMsg::AddSchedule {
+                lp_token: "liquidity0000".to_string(),
+                start_time: START,
+                end_time: START + 100,
+            })
+            .unwrap(),
+        })
+    };
+
+    // cw20 rewards are sent through the hook by the owner
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("reward0000", &[]),
+            schedule_msg("addr0000")
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    let msg = ExecuteMsg::AddSchedule {
+        lp_token: "liquidity0000".to_string(),
+        reward: Asset {
+            info: AssetInfo::Token {
+                contract_addr: "reward0000".to_string(),
+            },
+            amount: Uint128::from(1_000u128),
+        },
+        start_time: START,
+        end_time: START + 100,
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("owner0000", &[]),
+            msg
+        ),
+        Err(ContractError::Cw20RewardNotReceived {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("reward0000", &[]),
+        schedule_msg("owner0000"),
+    )
+    .unwrap();
+    assert_eq!(
+        query_pool(deps.as_ref(), "liquidity0000".to_string())
+            .unwrap()
+            .rewards,
+        vec![PoolRewardResponse {
+            info: AssetInfo::Token {
+                contract_addr: "reward0000".to_string(),
+            },
+            global_index: Decimal256::zero(),
+            schedules: vec![Schedule {
+                start_time: START,
+                end_time: START + 100,
+                amount: Uint128::from(1_000u128),
+            }],
+            undistributed: Uint128::zero(),
+        }]
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("liquidity0000", &[]),
+        bond_msg("addr0000", 100),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("liquidity0000", &[]),
+        bond_msg("addr0001", 100),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Unbond {
+        lp_token: "liquidity0000".to_string(),
+        amount: Uint128::from(101u128),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 50),
+            mock_info("addr0000", &[]),
+            msg
+        ),
+        Err(ContractError::InsufficientBond {})
+    );
+
+    // the unbonded tokens are returned and the rewards keep accruing on the rest
+    let msg = ExecuteMsg::Unbond {
+        lp_token: "liquidity0000".to_string(),
+        amount: Uint128::from(60u128),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 50),
+        mock_info("addr0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "liquidity0000".to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(60u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // 250 for each bond in the first half, then 500 * 40 / 140 and 500 * 100 / 140
+    assert_eq!(
+        pending_rewards(&deps, START + 100, "addr0000"),
+        vec![Uint128::from(392u128)]
+    );
+    assert_eq!(
+        pending_rewards(&deps, START + 100, "addr0001"),
+        vec![Uint128::from(607u128)]
+    );
+
+    // the emergency unbond returns the bond and forfeits the rewards to the stakers left
+    let msg = ExecuteMsg::EmergencyUnbond {
+        lp_token: "liquidity0000".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 100),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "liquidity0000".to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        pending_rewards(&deps, START + 100, "addr0001"),
+        vec![Uint128::zero()]
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 100),
+            mock_info("addr0001", &[]),
+            msg
+        ),
+        Err(ContractError::InsufficientBond {})
+    );
+
+    let pool = query_pool(deps.as_ref(), "liquidity0000".to_string()).unwrap();
+    assert_eq!(pool.total_bond_amount, Uint128::from(40u128));
+    assert_eq!(pool.rewards[0].undistributed, Uint128::from(607u128));
+    assert_eq!(
+        pending_rewards(&deps, START + 100, "addr0000"),
+        vec![Uint128::from(392u128)]
+    );
+    assert_eq!(
+        pending_rewards(&deps, START + 101, "addr0000"),
+        vec![Uint128::from(999u128)]
+    );
+}
+
+#[test]
+fn carry_undistributed_rewards() {
+    let mut deps = mock_dependencies(&[]);
+    setup(&mut deps);
+
+    let msg = ExecuteMsg::AddSchedule {
+        lp_token: "liquidity0000".to_string(),
+        reward: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uaura".to_string(),
+            },
+            amount: Uint128::from(1_000u128),
+        },
+        start_time: START,
+        end_time: START + 100,
+    };
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[Coin::new(1_000, "uaura")]),
+        msg,
+    )
+    .unwrap();
+
+    // the reward released before the first bond waits for it
+    execute(
+        deps.as_mut(),
+        env_at(START + 50),
+        mock_info("liquidity0000", &[]),
+        bond_msg("addr0000", 100),
+    )
+    .unwrap();
+    let pool = query_pool(deps.as_ref(), "liquidity0000".to_string()).unwrap();
+    assert_eq!(pool.rewards[0].global_index, Decimal256::zero());
+    assert_eq!(pool.rewards[0].undistributed, Uint128::from(500u128));
+
+    // and is distributed along the next release
+    assert_eq!(
+        pending_rewards(&deps, START + 60, "addr0000"),
+        vec![Uint128::from(600u128)]
+    );
+
+    // the reward released after the last unbond waits for the next bond
+    let msg = ExecuteMsg::Unbond {
+        lp_token: "liquidity0000".to_string(),
+        amount: Uint128::from(100u128),
+    };
+    execute(
+        deps.as_mut(),
+        env_at(START + 60),
+        mock_info("addr0000", &[]),
+        msg,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env_at(START + 200),
+        mock_info("liquidity0000", &[]),
+        bond_msg("addr0001", 100),
+    )
+    .unwrap();
+    assert_eq!(
+        pending_rewards(&deps, START + 201, "addr0000"),
+        vec![Uint128::from(600u128)]
+    );
+    assert_eq!(
+        pending_rewards(&deps, START + 201, "addr0001"),
+        vec![Uint128::from(400u128)]
+    );
+}
+
+#[test]
+fn bond_native_liquidity_shares() {
+    let mut deps = mock_dependencies(&[]);
+    setup(&mut deps);
+
+    let lp_denom = "factory/pair0001/uLP".to_string();
+    deps.querier.with_halo_factory(
+        &[(
+            &"uusduaura".to_string(),
+            &PairInfo {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: "uaura".to_string(),
+                    },
+                ],
+                contract_addr: "pair0001".to_string(),
+                liquidity_token: lp_denom.clone(),
+                asset_decimals: [6u8, 6u8],
+                requirements: CreatePairRequirements {
+                    whitelist: vec![],
+                    first_asset_minimum: Uint128::zero(),
+                    second_asset_minimum: Uint128::zero(),
+                },
+                asset_symbols: None,
+                incentive_contract: None,
+            },
+        )],
+        &[],
+    );
+
+    let msg = ExecuteMsg::RegisterPool {
+        lp_token: lp_denom.clone(),
+    };
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_pools(deps.as_ref(), None, None)
+            .unwrap()
+            .pools
+            .into_iter()
+            .map(|pool| (pool.lp_token, pool.pair_contract))
+            .collect::<Vec<_>>(),
+        vec![
+            ("liquidity0000".to_string(), "pair0000".to_string()),
+            (lp_denom.clone(), "pair0001".to_string()),
+        ]
+    );
+
+    // the native shares are bonded as funds, only to their own pool
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::Bond {}
+        ),
+        Err(ContractError::Payment(PaymentError::NoFunds {}))
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0000", &[Coin::new(100, "factory/pair0002/uLP")]),
+            ExecuteMsg::Bond {}
+        ),
+        Err(ContractError::PoolNotFound {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("addr0000", &[Coin::new(100, lp_denom.clone())]),
+        ExecuteMsg::Bond {},
+    )
+    .unwrap();
+    assert_eq!(
+        query_pool(deps.as_ref(), lp_denom.clone())
+            .unwrap()
+            .total_bond_amount,
+        Uint128::from(100u128)
+    );
+
+    let msg = ExecuteMsg::Unbond {
+        lp_token: lp_denom.clone(),
+        amount: Uint128::from(40u128),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 10),
+        mock_info("addr0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin::new(40, lp_denom)],
+        }))]
+    );
+}
@@ -0,0 +1,293 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response,
+    StdResult,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::state::{Config, Operation, CONFIG, OPERATIONS, OPERATION_COUNT};
+
+use haloswap::migration::assert_migrate_version;
+use haloswap::timelock::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, OperationResponse, OperationStatus,
+    OperationsResponse, QueryMsg,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:halo-timelock";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            admin: deps.api.addr_canonicalize(admin.as_str())?,
+            guardian: deps.api.addr_canonicalize(&msg.guardian)?,
+            min_delay: msg.min_delay,
+        },
+    )?;
+    OPERATION_COUNT.save(deps.storage, &0)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Schedule {
+            msgs,
+            description,
+            delay,
+        } => schedule(deps, env, info, msgs, description, delay),
+        ExecuteMsg::Cancel { operation_id } => cancel(deps, info, operation_id),
+        ExecuteMsg::Execute { operation_id } => execute_operation(deps, env, operation_id),
+        ExecuteMsg::UpdateConfig {
+            admin,
+            guardian,
+            min_delay,
+        } => update_config(deps, env, info, admin, guardian, min_delay),
+    }
+}
+
+// Only the contract itself can execute it, through a scheduled operation
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    admin: Option<String>,
+    guardian: Option<String>,
+    min_delay: Option<u64>,
+) -> Result<Response, ContractError> {
+    // permission check
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(admin) = admin {
+        // validate address format
+        let _ = deps.api.addr_validate(&admin)?;
+
+        config.admin = deps.api.addr_canonicalize(&admin)?;
+    }
+
+    if let Some(guardian) = guardian {
+        // validate address format
+        let _ = deps.api.addr_validate(&guardian)?;
+
+        config.guardian = deps.api.addr_canonicalize(&guardian)?;
+    }
+
+    if let Some(min_delay) = min_delay {
+        config.min_delay = min_delay;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+// Only admin can execute it
+pub fn schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<CosmosMsg<Empty>>,
+    description: String,
+    delay: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if msgs.is_empty() {
+        return Err(ContractError::EmptyOperation {});
+    }
+
+    let delay = delay.unwrap_or(config.min_delay);
+    if delay < config.min_delay {
+        return Err(ContractError::InvalidDelay {
+            min: config.min_delay,
+        });
+    }
+
+    let now = env.block.time.seconds();
+    let operation_id = OPERATION_COUNT.load(deps.storage)? + 1;
+    let ready_at = now + delay;
+    OPERATION_COUNT.save(deps.storage, &operation_id)?;
+    OPERATIONS.save(
+        deps.storage,
+        operation_id,
+        &Operation {
+            msgs,
+            description,
+            status: OperationStatus::Pending,
+            scheduled_at: now,
+            ready_at,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "schedule"),
+        ("operation_id", &operation_id.to_string()),
+        ("ready_at", &ready_at.to_string()),
+    ]))
+}
+
+// Only guardian can execute it
+pub fn cancel(
+    deps: DepsMut,
+    info: MessageInfo,
+    operation_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.guardian {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut operation = OPERATIONS.load(deps.storage, operation_id)?;
+    if operation.status != OperationStatus::Pending {
+        return Err(ContractError::NotPending {});
+    }
+
+    operation.status = OperationStatus::Cancelled;
+    OPERATIONS.save(deps.storage, operation_id, &operation)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "cancel"),
+        ("operation_id", &operation_id.to_string()),
+    ]))
+}
+
+/// Anyone can execute an operation once its delay has passed
+pub fn execute_operation(
+    deps: DepsMut,
+    env: Env,
+    operation_id: u64,
+) -> Result<Response, ContractError> {
+    let mut operation = OPERATIONS.load(deps.storage, operation_id)?;
+    match operation.current_status(env.block.time.seconds()) {
+        OperationStatus::Ready => {}
+        OperationStatus::Pending => {
+            return Err(ContractError::NotReady {
+                ready_at: operation.ready_at,
+            })
+        }
+        _ => return Err(ContractError::NotPending {}),
+    }
+
+    operation.status = OperationStatus::Executed;
+    OPERATIONS.save(deps.storage, operation_id, &operation)?;
+
+    Ok(Response::new()
+        .add_messages(operation.msgs)
+        .add_attributes(vec![
+            ("action", "execute"),
+            ("operation_id", &operation_id.to_string()),
+        ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Operation { operation_id } => {
+            to_json_binary(&query_operation(deps, env, operation_id)?)
+        }
+        QueryMsg::Operations {
+            start_before,
+            limit,
+        } => to_json_binary(&query_operations(deps, env, start_before, limit)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        admin: deps.api.addr_humanize(&config.admin)?.to_string(),
+        guardian: deps.api.addr_humanize(&config.guardian)?.to_string(),
+        min_delay: config.min_delay,
+    })
+}
+
+pub fn query_operation(deps: Deps, env: Env, operation_id: u64) -> StdResult<OperationResponse> {
+    let operation = OPERATIONS.load(deps.storage, operation_id)?;
+    Ok(operation_response(&env, operation_id, operation))
+}
+
+pub fn query_operations(
+    deps: Deps,
+    env: Env,
+    start_before: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OperationsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_before.map(Bound::exclusive);
+
+    let operations = OPERATIONS
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| {
+            let (operation_id, operation) = item?;
+            Ok(operation_response(&env, operation_id, operation))
+        })
+        .collect::<StdResult<Vec<OperationResponse>>>()?;
+
+    Ok(OperationsResponse { operations })
+}
+
+fn operation_response(env: &Env, operation_id: u64, operation: Operation) -> OperationResponse {
+    OperationResponse {
+        id: operation_id,
+        status: operation.current_status(env.block.time.seconds()),
+        msgs: operation.msgs,
+        description: operation.description,
+        scheduled_at: operation.scheduled_at,
+        ready_at: operation.ready_at,
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Delay must be at least {min} seconds")]
+    InvalidDelay { min: u64 },
+
+    #[error("Operation has no messages")]
+    EmptyOperation {},
+
+    #[error("Operation is already executed or cancelled")]
+    NotPending {},
+
+    #[error("Operation is not ready until {ready_at}")]
+    NotReady { ready_at: u64 },
+}
@@ -0,0 +1,38 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, CosmosMsg, Empty};
+use cw_storage_plus::{Item, Map};
+use haloswap::timelock::OperationStatus;
+
+#[cw_serde]
+pub struct Config {
+    pub admin: CanonicalAddr,
+    pub guardian: CanonicalAddr,
+    pub min_delay: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const OPERATION_COUNT: Item<u64> = Item::new("operation_count");
+
+#[cw_serde]
+pub struct Operation {
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub description: String,
+    pub status: OperationStatus,
+    pub scheduled_at: u64,
+    pub ready_at: u64,
+}
+
+impl Operation {
+    /// The status with the pending operation ready once the delay has passed
+    pub fn current_status(&self, now: u64) -> OperationStatus {
+        if self.status == OperationStatus::Pending && now >= self.ready_at {
+            return OperationStatus::Ready;
+        }
+
+        self.status
+    }
+}
+
+// key : operation id
+pub const OPERATIONS: Map<u64, Operation> = Map::new("operations");
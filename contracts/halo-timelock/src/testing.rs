@@ -0,0 +1,210 @@
+use crate::contract::{execute, instantiate, query_config, query_operation, query_operations};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{to_json_binary, CosmosMsg, DepsMut, Env, SubMsg, Timestamp, WasmMsg};
+use haloswap::factory::ExecuteMsg as FactoryExecuteMsg;
+use haloswap::timelock::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, OperationResponse, OperationStatus,
+};
+
+const START: u64 = 1_600_000_000;
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn init(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        admin: None,
+        guardian: "guardian0000".to_string(),
+        min_delay: 100,
+    };
+    instantiate(deps, env_at(START), mock_info("admin0000", &[]), msg).unwrap();
+}
+
+fn migrate_pair_msg() -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "factory0000".to_string(),
+        msg: to_json_binary(&FactoryExecuteMsg::MigratePair {
+            contract: "pair0000".to_string(),
+            code_id: Some(2),
+        })
+        .unwrap(),
+        funds: vec![],
+    })
+}
+
+#[test]
+fn schedule_and_execute() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    let msg = ExecuteMsg::Schedule {
+        msgs: vec![migrate_pair_msg()],
+        description: "migrate the pair".to_string(),
+        delay: Some(99),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("addr0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("admin0000", &[]),
+            msg
+        ),
+        Err(ContractError::InvalidDelay { min: 100 })
+    );
+
+    // the delay defaults to the minimum delay
+    let msg = ExecuteMsg::Schedule {
+        msgs: vec![migrate_pair_msg()],
+        description: "migrate the pair".to_string(),
+        delay: None,
+    };
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("admin0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_operation(deps.as_ref(), env_at(START + 10), 1).unwrap(),
+        OperationResponse {
+            id: 1,
+            msgs: vec![migrate_pair_msg()],
+            description: "migrate the pair".to_string(),
+            status: OperationStatus::Pending,
+            scheduled_at: START,
+            ready_at: START + 100,
+        }
+    );
+
+    // anyone executes the operation once it is ready
+    let msg = ExecuteMsg::Execute { operation_id: 1 };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 99),
+            mock_info("anyone", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::NotReady {
+            ready_at: START + 100
+        })
+    );
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 100),
+        mock_info("anyone", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(migrate_pair_msg())]);
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 100),
+            mock_info("anyone", &[]),
+            msg
+        ),
+        Err(ContractError::NotPending {})
+    );
+
+    // the config is only updated through an operation
+    let msg = ExecuteMsg::UpdateConfig {
+        admin: None,
+        guardian: None,
+        min_delay: Some(200),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 100),
+            mock_info("admin0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    let env = env_at(START + 100);
+    let contract = env.contract.address.to_string();
+    execute(deps.as_mut(), env, mock_info(&contract, &[]), msg).unwrap();
+    assert_eq!(
+        query_config(deps.as_ref()).unwrap(),
+        ConfigResponse {
+            admin: "admin0000".to_string(),
+            guardian: "guardian0000".to_string(),
+            min_delay: 200,
+        }
+    );
+}
+
+#[test]
+fn guardian_cancels() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    for delay in [100, 200] {
+        let msg = ExecuteMsg::Schedule {
+            msgs: vec![migrate_pair_msg()],
+            description: "migrate the pair".to_string(),
+            delay: Some(delay),
+        };
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("admin0000", &[]),
+            msg,
+        )
+        .unwrap();
+    }
+
+    let msg = ExecuteMsg::Cancel { operation_id: 2 };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 150),
+            mock_info("admin0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(START + 150),
+        mock_info("guardian0000", &[]),
+        msg,
+    )
+    .unwrap();
+
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 200),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Execute { operation_id: 2 }
+        ),
+        Err(ContractError::NotPending {})
+    );
+
+    // the queue is listed from the latest operation
+    assert_eq!(
+        query_operations(deps.as_ref(), env_at(START + 150), None, None)
+            .unwrap()
+            .operations
+            .into_iter()
+            .map(|operation| (operation.id, operation.status))
+            .collect::<Vec<(u64, OperationStatus)>>(),
+        vec![(2, OperationStatus::Cancelled), (1, OperationStatus::Ready)]
+    );
+}
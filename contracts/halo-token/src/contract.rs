@@ -1,19 +1,36 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+};
 
 use cw2::set_contract_version;
-use cw20_base::contract::{create_accounts, execute as cw20_execute, query as cw20_query};
-use cw20_base::msg::{ExecuteMsg, QueryMsg};
-use cw20_base::state::{MinterData, TokenInfo, TOKEN_INFO};
+use cw20::{EmbeddedLogo, Logo, LogoInfo, MarketingInfoResponse};
+use cw20_base::allowances::{
+    execute_burn_from, execute_decrease_allowance, execute_increase_allowance, execute_send_from,
+    execute_transfer_from, query_allowance,
+};
+use cw20_base::contract::{
+    create_accounts, execute_burn, execute_mint, execute_send, execute_transfer,
+    execute_update_marketing, execute_update_minter, execute_upload_logo, query_balance,
+    query_download_logo, query_marketing_info, query_minter, query_token_info,
+};
+use cw20_base::enumerable::{query_all_accounts, query_owner_allowances, query_spender_allowances};
+use cw20_base::state::{MinterData, TokenInfo, LOGO, MARKETING_INFO, TOKEN_INFO};
 use cw20_base::ContractError;
 
-use haloswap::token::InstantiateMsg;
+use crate::state::PERMIT_NONCES;
+use haloswap::token::{
+    verify_permit_signature, ExecuteMsg, InstantiateMsg, Permit, PermitInfoResponse, QueryMsg,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw20-base";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// same cap as the logos uploaded through cw20_base
+const LOGO_SIZE_CAP: usize = 5 * 1024;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
@@ -57,9 +74,69 @@ pub fn instantiate(
     };
 
     TOKEN_INFO.save(deps.storage, &data)?;
+
+    if let Some(marketing) = msg.marketing {
+        let logo = match marketing.logo {
+            Some(logo) => {
+                verify_logo(&logo)?;
+                LOGO.save(deps.storage, &logo)?;
+
+                match logo {
+                    Logo::Url(url) => Some(LogoInfo::Url(url)),
+                    Logo::Embedded(_) => Some(LogoInfo::Embedded),
+                }
+            }
+            None => None,
+        };
+
+        MARKETING_INFO.save(
+            deps.storage,
+            &MarketingInfoResponse {
+                project: marketing.project,
+                description: marketing.description,
+                marketing: marketing
+                    .marketing
+                    .map(|addr| deps.api.addr_validate(&addr))
+                    .transpose()?,
+                logo,
+            },
+        )?;
+    }
+
     Ok(Response::default())
 }
 
+/// Check the logo as cw20_base does when it is uploaded after the instantiation
+fn verify_logo(logo: &Logo) -> Result<(), ContractError> {
+    match logo {
+        Logo::Embedded(EmbeddedLogo::Svg(logo)) => {
+            // the svg starts with the xml preamble
+            let preamble = logo
+                .split_inclusive(|c| *c == b'>')
+                .next()
+                .ok_or(ContractError::InvalidXmlPreamble {})?;
+            if !(preamble.starts_with(b"<?xml ") && preamble.ends_with(b"?>")) {
+                return Err(ContractError::InvalidXmlPreamble {});
+            }
+            if logo.len() > LOGO_SIZE_CAP {
+                return Err(ContractError::LogoTooBig {});
+            }
+            Ok(())
+        }
+        Logo::Embedded(EmbeddedLogo::Png(logo)) => {
+            const HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+            if logo.len() > LOGO_SIZE_CAP {
+                return Err(ContractError::LogoTooBig {});
+            }
+            if !logo.starts_with(&HEADER) {
+                return Err(ContractError::InvalidPngHeader {});
+            }
+            Ok(())
+        }
+        Logo::Url(_) => Ok(()),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -67,10 +144,134 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    cw20_execute(deps, env, info, msg)
+    match msg {
+        ExecuteMsg::Transfer { recipient, amount } => {
+            execute_transfer(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::Burn { amount } => execute_burn(deps, env, info, amount),
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => execute_send(deps, env, info, contract, amount, msg),
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => execute_increase_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => execute_decrease_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => execute_transfer_from(deps, env, info, owner, recipient, amount),
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => execute_send_from(deps, env, info, owner, contract, amount, msg),
+        ExecuteMsg::BurnFrom { owner, amount } => execute_burn_from(deps, env, info, owner, amount),
+        ExecuteMsg::Mint { recipient, amount } => execute_mint(deps, env, info, recipient, amount),
+        ExecuteMsg::UpdateMinter { new_minter } => {
+            execute_update_minter(deps, env, info, new_minter)
+        }
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => execute_update_marketing(deps, env, info, project, description, marketing),
+        ExecuteMsg::UploadLogo(logo) => execute_upload_logo(deps, env, info, logo),
+        ExecuteMsg::Permit(permit) => execute_permit(deps, env, permit),
+    }
+}
+
+/// Increase the allowance as the owner of the permit once its signature is verified
+pub fn execute_permit(deps: DepsMut, env: Env, permit: Permit) -> Result<Response, ContractError> {
+    let params = permit.params;
+    if params.chain_id != env.block.chain_id || params.token != env.contract.address.as_str() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Permit is not valid on this token",
+        )));
+    }
+
+    if env.block.time.seconds() >= params.expires {
+        return Err(ContractError::Std(StdError::generic_err("Permit expired")));
+    }
+
+    let owner = deps.api.addr_validate(&params.owner)?;
+    let nonce = PERMIT_NONCES
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_default();
+    if params.nonce != nonce {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Invalid permit nonce, expected {}",
+            nonce
+        ))));
+    }
+
+    verify_permit_signature(
+        deps.api,
+        &params,
+        &params.owner,
+        &permit.pubkey,
+        &permit.signature,
+    )?;
+
+    PERMIT_NONCES.save(deps.storage, &owner, &(nonce + 1))?;
+
+    let info = MessageInfo {
+        sender: owner,
+        funds: vec![],
+    };
+    let res = execute_increase_allowance(deps, env, info, params.spender, params.amount, None)?;
+
+    Ok(res.add_attribute("permit_nonce", nonce.to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    cw20_query(deps, env, msg)
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
+        QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
+        QueryMsg::Minter {} => to_json_binary(&query_minter(deps)?),
+        QueryMsg::Allowance { owner, spender } => {
+            to_json_binary(&query_allowance(deps, owner, spender)?)
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => to_json_binary(&query_owner_allowances(deps, owner, start_after, limit)?),
+        QueryMsg::AllSpenderAllowances {
+            spender,
+            start_after,
+            limit,
+        } => to_json_binary(&query_spender_allowances(
+            deps,
+            spender,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AllAccounts { start_after, limit } => {
+            to_json_binary(&query_all_accounts(deps, start_after, limit)?)
+        }
+        QueryMsg::MarketingInfo {} => to_json_binary(&query_marketing_info(deps)?),
+        QueryMsg::DownloadLogo {} => to_json_binary(&query_download_logo(deps)?),
+        QueryMsg::PermitInfo { owner } => to_json_binary(&query_permit_info(deps, owner)?),
+    }
+}
+
+pub fn query_permit_info(deps: Deps, owner: String) -> StdResult<PermitInfoResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+
+    Ok(PermitInfoResponse {
+        nonce: PERMIT_NONCES
+            .may_load(deps.storage, &owner)?
+            .unwrap_or_default(),
+    })
 }
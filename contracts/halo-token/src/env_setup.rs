@@ -1,6 +1,6 @@
 #[cfg(test)]
 pub mod env_setup {
-    use cosmwasm_std::{Addr, Coin, Empty, StdError, Uint128};
+    use cosmwasm_std::{Addr, Coin, Decimal, Empty, StdError, Uint128};
     use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
     use cw20::{Cw20Coin, MinterResponse};
     
@@ -20,6 +20,7 @@ pub mod env_setup {
     use halo_router::contract::{
         execute as HaloRouterExecute,  
         instantiate as HaloRouterInstantiate, 
+        reply as HaloRouterReply,
         query as HaloRouterQuery
     };
 
@@ -90,7 +91,8 @@ pub mod env_setup {
     }
 
     fn halo_router_contract_template() -> Box<dyn Contract<Empty>> {
-        let contract = ContractWrapper::new(HaloRouterExecute, HaloRouterInstantiate, HaloRouterQuery);
+        let contract = ContractWrapper::new(HaloRouterExecute, HaloRouterInstantiate, HaloRouterQuery)
+            .with_reply(HaloRouterReply);
         Box::new(contract)
     }
     
@@ -188,6 +190,7 @@ pub mod env_setup {
                 first_asset_minimum: Uint128::zero(),
                 second_asset_minimum: Uint128::zero(),
             },
+            liquidity_token_kind: None,
         };
 
         // instantiate contract
@@ -221,6 +224,7 @@ pub mod env_setup {
                 Addr::unchecked(ADMIN),
                 &HaloRouterInstantiateMsg {
                     halo_factory: halo_factory_contract_addr.to_string(),
+                    max_referral_commission: Decimal::percent(1),
                 },
                 &[],
                 "test instantiate contract",
@@ -245,6 +249,7 @@ pub mod env_setup {
             decimals: 6,
             initial_balances: vec![],
             mint: None,
+            marketing: None,
         };
 
         // instantiate contract
@@ -282,6 +287,7 @@ pub mod env_setup {
             decimals: HALO_TOKEN_DECIMALS,
             initial_balances: vec![],
             mint: None,
+            marketing: None,
         };
 
         let too_long_token_name_instantiate_msg = HaloTokenInstantiateMsg {
@@ -290,6 +296,7 @@ pub mod env_setup {
             decimals: HALO_TOKEN_DECIMALS,
             initial_balances: vec![],
             mint: None,
+            marketing: None,
         };
 
         let too_short_token_symbol_instantiate_msg = HaloTokenInstantiateMsg {
@@ -298,6 +305,7 @@ pub mod env_setup {
             decimals: HALO_TOKEN_DECIMALS,
             initial_balances: vec![],
             mint: None,
+            marketing: None,
         };
 
         let too_long_token_symbol_instantiate_msg = HaloTokenInstantiateMsg {
@@ -306,6 +314,7 @@ pub mod env_setup {
             decimals: HALO_TOKEN_DECIMALS,
             initial_balances: vec![],
             mint: None,
+            marketing: None,
         };
 
         let too_big_token_decimals_instantiate_msg = HaloTokenInstantiateMsg {
@@ -314,6 +323,7 @@ pub mod env_setup {
             decimals: 20,
             initial_balances: vec![],
             mint: None,
+            marketing: None,
         };
 
         let initial_supply_greater_than_cap_msg = HaloTokenInstantiateMsg {
@@ -328,6 +338,7 @@ pub mod env_setup {
                 minter: ADMIN.to_string(),
                 cap: Some(Uint128::new(90)),
             }),
+            marketing: None,
         };
 
         let err = app
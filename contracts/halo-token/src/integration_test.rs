@@ -1,13 +1,17 @@
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::{coins, from_binary, to_binary, Addr, DepsMut, Response, OwnedDeps, MemoryStorage, testing::{MockApi, MockQuerier, mock_dependencies, mock_info, mock_env}, WasmQuery, Uint128, ContractResult};
+    use cosmwasm_std::{coins, from_json, to_json_binary, to_json_vec, Addr, Binary, DepsMut, Response, OwnedDeps, MemoryStorage, testing::{MockApi, MockQuerier, mock_dependencies, mock_info, mock_env}, WasmQuery, Uint128, ContractResult};
 
-    use cw20_base::{ContractError, msg::QueryMsg, msg::ExecuteMsg};
+    use cw20_base::ContractError;
     
     use crate::env_setup::env_setup::{instantiate_contracts, ADMIN, USER_1};
     use crate::contract::*;
-    use cw20::{Expiration as Cw20Expiration, TokenInfoResponse, Cw20Coin, MinterResponse};
-    use haloswap::token::InstantiateMsg;
+    use cw20::{Expiration as Cw20Expiration, TokenInfoResponse, Cw20Coin, MinterResponse, Logo, EmbeddedLogo, LogoInfo, MarketingInfoResponse, AllAccountsResponse, AllAllowancesResponse, AllSpenderAllowancesResponse, AllowanceResponse};
+    use haloswap::token::{
+        ExecuteMsg, InstantiateMarketingInfo, InstantiateMsg, Permit, PermitInfoResponse, PermitParams, QueryMsg,
+    };
+    use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+    use sha2::{Digest, Sha256};
 
     const MOCK_HALO_FACTORY_ADDR: &str = "halo_factory_addr";
     const MOCK_HALO_PAIR_ADDR: &str = "halo_pair_addr";
@@ -23,6 +27,9 @@ mod tests {
     const MOCK_OFFER_NFT_OFFERER_INSUFFICIENT_BALANCE: &str = "offerer 2";
     const MOCK_OFFER_NFT_OFFERER_INSUFFICIENT_ALLOWANCE: &str = "offerer 3";
 
+    // the address of the secp256k1 key of the bytes [1u8; 32]
+    const PERMIT_OWNER: &str = "aura10xcqpzrky6eff2g52qdye53xkk9jxkvr6kd7z6";
+
     fn mock_deps() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
@@ -34,6 +41,7 @@ mod tests {
                 minter: ADMIN.to_string(),
                 cap: Some(Uint128::new(90)),
             }),
+            marketing: None,
         };
 
         // mock querier
@@ -41,12 +49,12 @@ mod tests {
             match query {
                 WasmQuery::Smart { contract_addr, msg } => match contract_addr.as_str() {
                     MOCK_HALO_TOKEN_ADDR => {
-                        let query_msg = from_binary::<cw20_base::msg::QueryMsg>(msg).unwrap();
+                        let query_msg = from_json::<cw20_base::msg::QueryMsg>(msg).unwrap();
                         match query_msg {
                             cw20_base::msg::QueryMsg::Balance { address, .. } => {
                                 if address == MOCK_OFFER_NFT_OFFERER_INSUFFICIENT_BALANCE {
                                     let result = ContractResult::Ok(
-                                        to_binary(&cw20::BalanceResponse {
+                                        to_json_binary(&cw20::BalanceResponse {
                                             balance: Uint128::from(MOCK_OFFER_CW20_AMOUNT_MINIMUM),
                                         })
                                         .unwrap(),
@@ -54,7 +62,7 @@ mod tests {
                                     cosmwasm_std::SystemResult::Ok(result)
                                 } else {
                                     let result = ContractResult::Ok(
-                                        to_binary(&cw20::BalanceResponse {
+                                        to_json_binary(&cw20::BalanceResponse {
                                             balance: Uint128::from(MOCK_OFFER_CW20_AMOUNT),
                                         })
                                         .unwrap(),
@@ -65,7 +73,7 @@ mod tests {
                             cw20_base::msg::QueryMsg::Allowance { owner, spender: _ } => {
                                 if owner == MOCK_OFFER_NFT_OFFERER_INSUFFICIENT_ALLOWANCE {
                                     let result = ContractResult::Ok(
-                                        to_binary(&cw20::AllowanceResponse {
+                                        to_json_binary(&cw20::AllowanceResponse {
                                             allowance: Uint128::from(
                                                 MOCK_OFFER_CW20_AMOUNT_MINIMUM,
                                             ),
@@ -76,7 +84,7 @@ mod tests {
                                     cosmwasm_std::SystemResult::Ok(result)
                                 } else {
                                     let result = ContractResult::Ok(
-                                        to_binary(&cw20::AllowanceResponse {
+                                        to_json_binary(&cw20::AllowanceResponse {
                                             allowance: Uint128::from(MOCK_OFFER_CW20_AMOUNT),
                                             expires: Cw20Expiration::Never {},
                                         })
@@ -115,7 +123,7 @@ mod tests {
         let deps = mock_deps();
         // query config
         let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
-        let token_info: TokenInfoResponse = from_binary(&res).unwrap();
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
 
         assert_eq!("Cafe Token".to_string(), token_info.name);
         assert_eq!("CAFE".to_string(), token_info.symbol);
@@ -131,6 +139,7 @@ mod tests {
             decimals: 6,
             initial_balances: vec![],
             mint: None, // no minter data
+            marketing: None,
         };
         let mut deps = mock_deps();
 
@@ -138,6 +147,48 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn proper_initialization_with_marketing() {
+        let mut deps = mock_dependencies();
+        let msg = |logo: Logo| InstantiateMsg {
+            name: "Cafe Token".to_string(),
+            symbol: "CAFE".to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            mint: None,
+            marketing: Some(InstantiateMarketingInfo {
+                project: Some("https://cafe.token".to_string()),
+                description: Some("The token of the cafe".to_string()),
+                marketing: Some(ADMIN.to_string()),
+                logo: Some(logo),
+            }),
+        };
+
+        // the embedded logo is checked
+        let res = instantiate_contract(deps.as_mut(), msg(Logo::Embedded(EmbeddedLogo::Png(b"not a png".into()))));
+        assert_eq!(res, Err(ContractError::InvalidPngHeader {}));
+
+        instantiate_contract(deps.as_mut(), msg(Logo::Url("https://cafe.token/logo.png".to_string()))).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::MarketingInfo {}).unwrap();
+        let marketing_info: MarketingInfoResponse = from_json(&res).unwrap();
+        assert_eq!(marketing_info, MarketingInfoResponse {
+            project: Some("https://cafe.token".to_string()),
+            description: Some("The token of the cafe".to_string()),
+            marketing: Some(Addr::unchecked(ADMIN)),
+            logo: Some(LogoInfo::Url("https://cafe.token/logo.png".to_string())),
+        });
+
+        // the marketing address updates the marketing info
+        let msg = ExecuteMsg::UpdateMarketing {
+            project: None,
+            description: Some("The token of the cafe, now with cookies".to_string()),
+            marketing: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(USER_1, &[]), msg.clone());
+        assert_eq!(res, Err(ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+    }
+
 
     mod execute_contract {
         use super::*;
@@ -169,6 +220,96 @@ mod tests {
             assert!(response.is_ok());
 
         }
+
+        // the accounts and the allowances are enumerable
+        #[test]
+        fn proper_query_all_accounts_and_allowances() {
+            let mut deps = mock_deps();
+            create_mint_msg(deps.as_mut(), ADMIN, USER_1, Uint128::from(10u128)).unwrap();
+
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: ADMIN.to_string(),
+                amount: Uint128::from(5u128),
+                expires: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(USER_1, &[]), msg).unwrap();
+
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::AllAccounts { start_after: None, limit: None }).unwrap();
+            let all_accounts: AllAccountsResponse = from_json(&res).unwrap();
+            assert_eq!(all_accounts.accounts, vec![USER_1.to_string()]);
+
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::AllAllowances { owner: USER_1.to_string(), start_after: None, limit: None }).unwrap();
+            let all_allowances: AllAllowancesResponse = from_json(&res).unwrap();
+            assert_eq!(all_allowances.allowances.len(), 1);
+            assert_eq!(all_allowances.allowances[0].spender, ADMIN.to_string());
+            assert_eq!(all_allowances.allowances[0].allowance, Uint128::from(5u128));
+
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::AllSpenderAllowances { spender: ADMIN.to_string(), start_after: None, limit: None }).unwrap();
+            let all_spender_allowances: AllSpenderAllowancesResponse = from_json(&res).unwrap();
+            assert_eq!(all_spender_allowances.allowances.len(), 1);
+            assert_eq!(all_spender_allowances.allowances[0].owner, USER_1.to_string());
+        }
+
+        // a permit signed by the owner increases the allowance once
+        #[test]
+        fn proper_execute_permit() {
+            let mut deps = mock_deps();
+            create_mint_msg(deps.as_mut(), ADMIN, PERMIT_OWNER, Uint128::from(10u128)).unwrap();
+
+            let signing_key = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+            let pubkey = Binary::from(signing_key.verifying_key().to_encoded_point(true).as_bytes());
+            let env = mock_env();
+            let params = PermitParams {
+                chain_id: env.block.chain_id.clone(),
+                token: env.contract.address.to_string(),
+                owner: PERMIT_OWNER.to_string(),
+                spender: ADMIN.to_string(),
+                amount: Uint128::from(5u128),
+                nonce: 0,
+                expires: env.block.time.seconds() + 100,
+            };
+            let sign = |params: &PermitParams| -> Permit {
+                let hash = Sha256::digest(to_json_vec(params).unwrap());
+                let signature: Signature = signing_key.sign_prehash(&hash).unwrap();
+                Permit { params: params.clone(), pubkey: pubkey.clone(), signature: Binary::from(signature.to_bytes().as_slice()) }
+            };
+            let permit_msg = |permit: Permit| ExecuteMsg::Permit(permit);
+
+            // the owner address must derive from the compressed public key of the permit
+            let other_key = SigningKey::from_bytes(&[2u8; 32].into()).unwrap();
+            let mut permit = sign(&params);
+            permit.pubkey = Binary::from(other_key.verifying_key().to_encoded_point(true).as_bytes());
+            let res = execute(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), permit_msg(permit));
+            assert_eq!(res.unwrap_err().to_string(), "Generic error: Permit public key does not match the owner");
+            let mut permit = sign(&params);
+            permit.pubkey = Binary::from(signing_key.verifying_key().to_encoded_point(false).as_bytes());
+            let res = execute(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), permit_msg(permit));
+            assert_eq!(res.unwrap_err().to_string(), "Generic error: Invalid secp256k1 public key");
+
+            // the permit must be signed by the owner with its current nonce before it expires
+            let res = execute(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), permit_msg(sign(&PermitParams { nonce: 1, ..params.clone() })));
+            assert_eq!(res.unwrap_err().to_string(), "Generic error: Invalid permit nonce, expected 0");
+            let res = execute(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), permit_msg(sign(&PermitParams { expires: env.block.time.seconds(), ..params.clone() })));
+            assert_eq!(res.unwrap_err().to_string(), "Generic error: Permit expired");
+            let mut permit = sign(&params);
+            permit.params.amount = Uint128::from(10u128);
+            let res = execute(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), permit_msg(permit));
+            assert_eq!(res.unwrap_err().to_string(), "Generic error: Invalid permit signature");
+
+            // anyone can relay a valid permit
+            execute(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), permit_msg(sign(&params))).unwrap();
+            let res = query(deps.as_ref(), env.clone(), QueryMsg::Allowance { owner: PERMIT_OWNER.to_string(), spender: ADMIN.to_string() }).unwrap();
+            let allowance: AllowanceResponse = from_json(&res).unwrap();
+            assert_eq!(allowance.allowance, Uint128::from(5u128));
+
+            let res = query(deps.as_ref(), env.clone(), QueryMsg::PermitInfo { owner: PERMIT_OWNER.to_string() }).unwrap();
+            let permit_info: PermitInfoResponse = from_json(&res).unwrap();
+            assert_eq!(permit_info, PermitInfoResponse { nonce: 1 });
+
+            // a permit cannot be replayed
+            let res = execute(deps.as_mut(), env, mock_info(ADMIN, &[]), permit_msg(sign(&params)));
+            assert_eq!(res.unwrap_err().to_string(), "Generic error: Invalid permit nonce, expected 1");
+        }
     }
 
 }
\ No newline at end of file
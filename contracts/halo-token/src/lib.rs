@@ -1,3 +1,4 @@
 pub mod contract;
 pub mod env_setup;
-pub mod integration_test;
\ No newline at end of file
+pub mod integration_test;
+pub mod state;
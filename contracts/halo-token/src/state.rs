@@ -0,0 +1,5 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::Map;
+
+// key : owner, the nonce of the next permit of the owner
+pub const PERMIT_NONCES: Map<&Addr, u64> = Map::new("permit_nonce");
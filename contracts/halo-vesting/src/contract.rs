@@ -0,0 +1,327 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use crate::error::ContractError;
+use crate::state::{read_vestings, Config, Vesting, CONFIG, VESTINGS};
+
+use haloswap::migration::assert_migrate_version;
+use haloswap::vesting::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, VestingResponse,
+    VestingSchedule, VestingsResponse,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:halo-vesting";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            halo_token: deps.api.addr_canonicalize(&msg.halo_token)?,
+        },
+    )?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig { owner } => update_config(deps, info, owner),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+        ExecuteMsg::Revoke { recipient } => revoke(deps, env, info, recipient),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // only the owner vests the halo token
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.halo_token
+        || deps.api.addr_canonicalize(&cw20_msg.sender)? != config.owner
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::CreateVesting {
+            recipient,
+            schedule,
+            revocable,
+        } => create_vesting(deps, env, recipient, schedule, revocable, cw20_msg.amount),
+    }
+}
+
+// Only owner can execute it
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn create_vesting(
+    deps: DepsMut,
+    env: Env,
+    recipient: String,
+    schedule: VestingSchedule,
+    revocable: bool,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    if schedule.start_time >= schedule.end_time
+        || schedule.cliff_time < schedule.start_time
+        || schedule.cliff_time > schedule.end_time
+        || schedule.end_time <= env.block.time.seconds()
+    {
+        return Err(ContractError::InvalidSchedule {});
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let recipient_raw = deps.api.addr_canonicalize(recipient.as_str())?;
+    if VESTINGS.has(deps.storage, &recipient_raw) {
+        return Err(ContractError::VestingExists {});
+    }
+
+    VESTINGS.save(
+        deps.storage,
+        &recipient_raw,
+        &Vesting {
+            schedule,
+            total_amount: amount,
+            claimed_amount: Uint128::zero(),
+            revocable,
+            revoked: false,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "create_vesting"),
+        ("recipient", recipient.as_str()),
+        ("amount", &amount.to_string()),
+        ("revocable", &revocable.to_string()),
+    ]))
+}
+
+pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let recipient_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut vesting = VESTINGS
+        .may_load(deps.storage, &recipient_raw)?
+        .ok_or(ContractError::VestingNotFound {})?;
+
+    let amount = vesting.claimable_amount(env.block.time.seconds());
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    vesting.claimed_amount = vesting.claimed_amount.checked_add(amount)?;
+    VESTINGS.save(deps.storage, &recipient_raw, &vesting)?;
+
+    Ok(Response::new()
+        .add_message(transfer_msg(
+            deps.as_ref(),
+            &config,
+            info.sender.clone(),
+            amount,
+        )?)
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("recipient", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+// Only owner can execute it
+pub fn revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient_raw = deps.api.addr_canonicalize(&recipient)?;
+    let mut vesting = VESTINGS
+        .may_load(deps.storage, &recipient_raw)?
+        .ok_or(ContractError::VestingNotFound {})?;
+    if !vesting.revocable {
+        return Err(ContractError::NotRevocable {});
+    }
+
+    // the schedule ends now with the amount vested so far
+    let now = env.block.time.seconds();
+    let vested_amount = vesting.vested_amount(now);
+    let returned_amount = vesting.total_amount - vested_amount;
+    vesting.total_amount = vested_amount;
+    vesting.schedule = VestingSchedule {
+        start_time: vesting.schedule.start_time.min(now),
+        cliff_time: vesting.schedule.cliff_time.min(now),
+        end_time: vesting.schedule.end_time.min(now),
+    };
+    vesting.revocable = false;
+    vesting.revoked = true;
+    VESTINGS.save(deps.storage, &recipient_raw, &vesting)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !returned_amount.is_zero() {
+        messages.push(transfer_msg(
+            deps.as_ref(),
+            &config,
+            info.sender,
+            returned_amount,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "revoke"),
+        ("recipient", &recipient),
+        ("vested_amount", &vested_amount.to_string()),
+        ("returned_amount", &returned_amount.to_string()),
+    ]))
+}
+
+fn transfer_msg(
+    deps: Deps,
+    config: &Config,
+    recipient: Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.halo_token)?.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Vesting { recipient } => to_json_binary(&query_vesting(deps, env, recipient)?),
+        QueryMsg::Vestings { start_after, limit } => {
+            to_json_binary(&query_vestings(deps, env, start_after, limit)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        halo_token: deps.api.addr_humanize(&config.halo_token)?.to_string(),
+    })
+}
+
+pub fn query_vesting(deps: Deps, env: Env, recipient: String) -> StdResult<VestingResponse> {
+    let vesting = VESTINGS.load(deps.storage, &deps.api.addr_canonicalize(&recipient)?)?;
+    Ok(vesting_response(&env, recipient, vesting))
+}
+
+pub fn query_vestings(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VestingsResponse> {
+    let start_after = start_after
+        .map(|recipient| deps.api.addr_canonicalize(&recipient))
+        .transpose()?;
+
+    let vestings = read_vestings(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(recipient, vesting)| {
+            Ok(vesting_response(
+                &env,
+                deps.api.addr_humanize(&recipient)?.to_string(),
+                vesting,
+            ))
+        })
+        .collect::<StdResult<Vec<VestingResponse>>>()?;
+
+    Ok(VestingsResponse { vestings })
+}
+
+fn vesting_response(env: &Env, recipient: String, vesting: Vesting) -> VestingResponse {
+    let now = env.block.time.seconds();
+    VestingResponse {
+        recipient,
+        vested_amount: vesting.vested_amount(now),
+        claimable_amount: vesting.claimable_amount(now),
+        schedule: vesting.schedule,
+        total_amount: vesting.total_amount,
+        claimed_amount: vesting.claimed_amount,
+        revocable: vesting.revocable,
+        revoked: vesting.revoked,
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let stored_version = assert_migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &stored_version.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
+}
@@ -0,0 +1,32 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Schedule must start before it ends with the cliff in between")]
+    InvalidSchedule {},
+
+    #[error("The recipient already has a vesting")]
+    VestingExists {},
+
+    #[error("Vesting not found")]
+    VestingNotFound {},
+
+    #[error("Vesting is not revocable")]
+    NotRevocable {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
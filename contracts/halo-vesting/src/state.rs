@@ -0,0 +1,67 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
+use haloswap::vesting::VestingSchedule;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub halo_token: CanonicalAddr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+pub struct Vesting {
+    pub schedule: VestingSchedule,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub revocable: bool,
+    pub revoked: bool,
+}
+
+impl Vesting {
+    pub fn vested_amount(&self, now: u64) -> Uint128 {
+        let schedule = &self.schedule;
+        if now >= schedule.end_time {
+            return self.total_amount;
+        }
+
+        if now < schedule.cliff_time {
+            return Uint128::zero();
+        }
+
+        self.total_amount.multiply_ratio(
+            now - schedule.start_time,
+            schedule.end_time - schedule.start_time,
+        )
+    }
+
+    pub fn claimable_amount(&self, now: u64) -> Uint128 {
+        self.vested_amount(now) - self.claimed_amount
+    }
+}
+
+// key : recipient
+pub const VESTINGS: Map<&[u8], Vesting> = Map::new("vesting");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_vestings(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(CanonicalAddr, Vesting)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|recipient| Bound::ExclusiveRaw(recipient.to_vec()));
+
+    VESTINGS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (recipient, vesting) = item?;
+            Ok((CanonicalAddr::from(recipient), vesting))
+        })
+        .collect()
+}
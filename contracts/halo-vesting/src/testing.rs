@@ -0,0 +1,243 @@
+use crate::contract::{execute, instantiate, query_vesting, query_vestings};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{to_json_binary, CosmosMsg, DepsMut, Env, SubMsg, Timestamp, Uint128, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use haloswap::vesting::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, VestingResponse, VestingSchedule,
+};
+
+const START: u64 = 1_600_000_000;
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn init(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        halo_token: "halo0000".to_string(),
+    };
+    instantiate(deps, env_at(START), mock_info("owner0000", &[]), msg).unwrap();
+}
+
+fn create_vesting_msg(
+    sender: &str,
+    recipient: &str,
+    schedule: VestingSchedule,
+    revocable: bool,
+) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_json_binary(&Cw20HookMsg::CreateVesting {
+            recipient: recipient.to_string(),
+            schedule,
+            revocable,
+        })
+        .unwrap(),
+    })
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "halo0000".to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn vest_with_cliff() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    let schedule = VestingSchedule {
+        start_time: START,
+        cliff_time: START + 250,
+        end_time: START + 1000,
+    };
+
+    // only the owner vests halo
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("halo0000", &[]),
+            create_vesting_msg("addr0000", "addr0000", schedule.clone(), false)
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("other0000", &[]),
+            create_vesting_msg("owner0000", "addr0000", schedule.clone(), false)
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("halo0000", &[]),
+            create_vesting_msg(
+                "owner0000",
+                "addr0000",
+                VestingSchedule {
+                    start_time: START,
+                    cliff_time: START + 1001,
+                    end_time: START + 1000,
+                },
+                false
+            )
+        ),
+        Err(ContractError::InvalidSchedule {})
+    );
+    execute(
+        deps.as_mut(),
+        env_at(START),
+        mock_info("halo0000", &[]),
+        create_vesting_msg("owner0000", "addr0000", schedule.clone(), false),
+    )
+    .unwrap();
+
+    // nothing vests before the cliff
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 249),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::Claim {}
+        ),
+        Err(ContractError::NothingToClaim {})
+    );
+
+    // the amount vested since the start is released at the cliff
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 250),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("addr0000", 250)]);
+
+    assert_eq!(
+        query_vesting(deps.as_ref(), env_at(START + 600), "addr0000".to_string()).unwrap(),
+        VestingResponse {
+            recipient: "addr0000".to_string(),
+            schedule: schedule.clone(),
+            total_amount: Uint128::from(1000u128),
+            vested_amount: Uint128::from(600u128),
+            claimed_amount: Uint128::from(250u128),
+            claimable_amount: Uint128::from(350u128),
+            revocable: false,
+            revoked: false,
+        }
+    );
+
+    // the rest is released at the end
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 2000),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("addr0000", 750)]);
+
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 2000),
+            mock_info("owner0000", &[]),
+            ExecuteMsg::Revoke {
+                recipient: "addr0000".to_string()
+            }
+        ),
+        Err(ContractError::NotRevocable {})
+    );
+}
+
+#[test]
+fn revoke_vesting() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+
+    let schedule = VestingSchedule {
+        start_time: START,
+        cliff_time: START,
+        end_time: START + 1000,
+    };
+    for recipient in ["addr0000", "addr0001"] {
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("halo0000", &[]),
+            create_vesting_msg("owner0000", recipient, schedule.clone(), true),
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START),
+            mock_info("halo0000", &[]),
+            create_vesting_msg("owner0000", "addr0000", schedule, true)
+        ),
+        Err(ContractError::VestingExists {})
+    );
+
+    let msg = ExecuteMsg::Revoke {
+        recipient: "addr0000".to_string(),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env_at(START + 400),
+            mock_info("addr0000", &[]),
+            msg.clone()
+        ),
+        Err(ContractError::Unauthorized {})
+    );
+
+    // the unvested amount returns to the owner
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 400),
+        mock_info("owner0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("owner0000", 600)]);
+
+    // the vested amount stays claimable and nothing vests anymore
+    let res = execute(
+        deps.as_mut(),
+        env_at(START + 1000),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("addr0000", 400)]);
+
+    assert_eq!(
+        query_vestings(deps.as_ref(), env_at(START + 1000), None, None)
+            .unwrap()
+            .vestings
+            .into_iter()
+            .map(|vesting| (vesting.recipient, vesting.claimable_amount, vesting.revoked))
+            .collect::<Vec<(String, Uint128, bool)>>(),
+        vec![
+            ("addr0000".to_string(), Uint128::zero(), true),
+            ("addr0001".to_string(), Uint128::from(1000u128), false),
+        ]
+    );
+}
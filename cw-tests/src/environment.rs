@@ -1,12 +1,18 @@
 /// We will set up a mock environment for testing
 /// Then instantiate all the contracts we need
-use cosmwasm_std::{Addr, Coin, Uint128, Empty};
+use anyhow::Result as AnyResult;
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{Addr, Api, Binary, BlockInfo, Coin, Decimal, Uint128, Empty, Querier, Storage, WasmMsg, WasmQuery};
 use cw20::{Cw20Coin, MinterResponse};
-use cw_multi_test::{App, AppBuilder, ContractWrapper, Contract, Executor};
+use cw_multi_test::{App, AppBuilder, AppResponse, BankKeeper, ContractWrapper, Contract, CosmosRouter, Executor, FailingModule, Wasm, WasmKeeper};
 use halo_token::contract::{execute as halo_token_execute, instantiate as halo_token_instantiate, query as halo_token_query};
-use halo_pair::contract::{execute as halo_pair_execute, instantiate as halo_pair_instantiate, query as halo_pair_query, reply as halo_pair_reply};
+use halo_pair::contract::{execute as halo_pair_execute, instantiate as halo_pair_instantiate, query as halo_pair_query, reply as halo_pair_reply, migrate as halo_pair_migrate};
 use halo_factory::contract::{execute as halo_factory_execute, instantiate as halo_factory_instantiate, query as halo_factory_query, reply as halo_factory_reply};
-use halo_router::contract::{execute as halo_router_execute, instantiate as halo_router_instantiate, query as halo_router_query};
+use halo_router::contract::{execute as halo_router_execute, instantiate as halo_router_instantiate, query as halo_router_query, reply as halo_router_reply};
+use halo_gov_staking::contract::{execute as halo_gov_staking_execute, instantiate as halo_gov_staking_instantiate, query as halo_gov_staking_query};
+use halo_governance::contract::{execute as halo_governance_execute, instantiate as halo_governance_instantiate, query as halo_governance_query};
+use halo_vesting::contract::{execute as halo_vesting_execute, instantiate as halo_vesting_instantiate, query as halo_vesting_query};
+use halo_airdrop::contract::{execute as halo_airdrop_execute, instantiate as halo_airdrop_instantiate, query as halo_airdrop_query};
 
 // use haloswap::asset::AssetInfo;
 use haloswap::token::InstantiateMsg as HaloTokenInstantiateMsg;
@@ -25,12 +31,98 @@ pub struct CodeIds {
     pub halo_pair_code_id: u64,
     pub halo_factory_code_id: u64,
     pub halo_router_code_id: u64,
+    pub halo_gov_staking_code_id: u64,
+    pub halo_governance_code_id: u64,
+    pub halo_vesting_code_id: u64,
+    pub halo_airdrop_code_id: u64,
+    /// a second upload of the pair code to migrate the pairs to
+    pub halo_pair_upgrade_code_id: u64,
 }
 
 pub const TOKEN_INITIAL_BALANCE: u128 = 1000000000000u128;
 
-fn mock_app() -> App {
-    AppBuilder::new().build(|router, _, storage| {
+/// the app instance with the wasm module supporting instantiate2
+pub type HaloApp = App<BankKeeper, MockApi, MockStorage, FailingModule<Empty, Empty, Empty>, Instantiate2WasmKeeper>;
+
+/// cw-multi-test 0.16 does not support `WasmMsg::Instantiate2`,
+/// so it is executed as an `Instantiate` and the contract gets the next sequential address
+pub struct Instantiate2WasmKeeper(WasmKeeper<Empty, Empty>);
+
+impl Wasm<Empty, Empty> for Instantiate2WasmKeeper {
+    fn query(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        request: WasmQuery,
+    ) -> AnyResult<Binary> {
+        self.0.query(api, storage, querier, block, request)
+    }
+
+    fn execute(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = Empty, QueryC = Empty>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: WasmMsg,
+    ) -> AnyResult<AppResponse> {
+        let msg = match msg {
+            WasmMsg::Instantiate2 { admin, code_id, label, msg, funds, .. } => WasmMsg::Instantiate {
+                admin,
+                code_id,
+                msg,
+                funds,
+                label,
+            },
+            msg => msg,
+        };
+        self.0.execute(api, storage, router, block, sender, msg)
+    }
+
+    fn sudo(
+        &self,
+        api: &dyn Api,
+        contract_addr: Addr,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = Empty, QueryC = Empty>,
+        block: &BlockInfo,
+        msg: Binary,
+    ) -> AnyResult<AppResponse> {
+        self.0.sudo(api, contract_addr, storage, router, block, msg)
+    }
+}
+
+fn mock_app() -> (HaloApp, CodeIds) {
+    let (app, code_ids, _) = mock_app_with_codes(vec![]);
+    (app, code_ids)
+}
+
+/// the codes are stored before building the app as the wasm module is wrapped,
+/// the code ids of the extra contracts are returned in their order
+pub fn mock_app_with_codes(contracts: Vec<Box<dyn Contract<Empty>>>) -> (HaloApp, CodeIds, Vec<u64>) {
+    let mut wasm = WasmKeeper::new();
+    let code_ids = CodeIds {
+        halo_token_code_id: wasm.store_code(halo_token_contract_template()) as u64,
+        halo_pair_code_id: wasm.store_code(halo_pair_contract_template()) as u64,
+        halo_factory_code_id: wasm.store_code(halo_factory_contract_template()) as u64,
+        halo_router_code_id: wasm.store_code(halo_router_contract_template()) as u64,
+        halo_gov_staking_code_id: wasm.store_code(halo_gov_staking_contract_template()) as u64,
+        halo_governance_code_id: wasm.store_code(halo_governance_contract_template()) as u64,
+        halo_vesting_code_id: wasm.store_code(halo_vesting_contract_template()) as u64,
+        halo_airdrop_code_id: wasm.store_code(halo_airdrop_contract_template()) as u64,
+        halo_pair_upgrade_code_id: wasm.store_code(halo_pair_contract_template()) as u64,
+    };
+    let extra_code_ids = contracts
+        .into_iter()
+        .map(|contract| wasm.store_code(contract) as u64)
+        .collect();
+
+    let app = AppBuilder::new()
+        .with_wasm::<FailingModule<Empty, Empty, Empty>, _>(Instantiate2WasmKeeper(wasm))
+        .build(|router, _, storage| {
         router
             .bank
             .init_balance(
@@ -46,7 +138,9 @@ fn mock_app() -> App {
                 }],
             )
             .unwrap();
-    })
+    });
+
+    (app, code_ids, extra_code_ids)
 }
 
 fn halo_token_contract_template() -> Box<dyn Contract<Empty>> {
@@ -63,7 +157,7 @@ fn halo_pair_contract_template() -> Box<dyn Contract<Empty>> {
         halo_pair_execute,
         halo_pair_instantiate,
         halo_pair_query,
-    ).with_reply(halo_pair_reply);
+    ).with_reply(halo_pair_reply).with_migrate(halo_pair_migrate);
     Box::new(contract)
 }
 
@@ -81,35 +175,61 @@ fn halo_router_contract_template() -> Box<dyn Contract<Empty>> {
         halo_router_execute,
         halo_router_instantiate,
         halo_router_query,
+    ).with_reply(halo_router_reply);
+    Box::new(contract)
+}
+
+fn halo_gov_staking_contract_template() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        halo_gov_staking_execute,
+        halo_gov_staking_instantiate,
+        halo_gov_staking_query,
+    );
+    Box::new(contract)
+}
+
+fn halo_governance_contract_template() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        halo_governance_execute,
+        halo_governance_instantiate,
+        halo_governance_query,
+    );
+    Box::new(contract)
+}
+
+fn halo_vesting_contract_template() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        halo_vesting_execute,
+        halo_vesting_instantiate,
+        halo_vesting_query,
+    );
+    Box::new(contract)
+}
+
+fn halo_airdrop_contract_template() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        halo_airdrop_execute,
+        halo_airdrop_instantiate,
+        halo_airdrop_query,
     );
     Box::new(contract)
 }
 
 /// function to instantiate all contracts
 /// @note the address of contract pair_A_and_B & the address of LP token will be retrieved when user add new pair to factory
-/// @return app: App - the app instance
+/// @return app: HaloApp - the app instance
 /// @return token_A: halo_token - the address of token A
 /// @return token_B: halo_token - the address of token B
 /// @return swap_factory: halo_factory - the address of swap factory contract
 /// @return swap_router: halo_router - the address of swap router contract
 /// @return code_ids: CodeIds - the code ids of all contracts
-pub fn instantiate_contracts() -> (App, String, String, String, String, CodeIds) {
-    // Create a new app instance
-    let mut app = mock_app();
-
-    // store the code of all contracts to the app and get the code ids
-    let halo_token_id = app.store_code(halo_token_contract_template());
-    let halo_pair_id = app.store_code(halo_pair_contract_template());
-    let halo_factory_id = app.store_code(halo_factory_contract_template());
-    let halo_router_id = app.store_code(halo_router_contract_template());
-
-    // store the code ids to the struct CodeIds
-    let code_ids = CodeIds {
-        halo_token_code_id: halo_token_id,
-        halo_pair_code_id: halo_pair_id,
-        halo_factory_code_id: halo_factory_id,
-        halo_router_code_id: halo_router_id,
-    };
+pub fn instantiate_contracts() -> (HaloApp, String, String, String, String, CodeIds) {
+    // Create a new app instance with the code of all contracts stored
+    let (mut app, code_ids) = mock_app();
+    let halo_token_id = code_ids.halo_token_code_id;
+    let halo_pair_id = code_ids.halo_pair_code_id;
+    let halo_factory_id = code_ids.halo_factory_code_id;
+    let halo_router_id = code_ids.halo_router_code_id;
 
     // create instantiate message for token_A
     let token_a_instantiate_msg = HaloTokenInstantiateMsg {
@@ -126,6 +246,7 @@ pub fn instantiate_contracts() -> (App, String, String, String, String, CodeIds)
             minter: ADMIN.to_string(),
             cap: Some(Uint128::new(TOKEN_INITIAL_BALANCE)),
         }),
+        marketing: None,
     };
 
     // instantiate token_A
@@ -155,6 +276,7 @@ pub fn instantiate_contracts() -> (App, String, String, String, String, CodeIds)
             minter: ADMIN.to_string(),
             cap: Some(Uint128::new(TOKEN_INITIAL_BALANCE)),
         }),
+        marketing: None,
     };
 
     // instantiate token_B
@@ -216,6 +338,7 @@ pub fn instantiate_contracts() -> (App, String, String, String, String, CodeIds)
     // create instantiate message for swap_router
     let swap_router_instantiate_msg = HaloRouterInstantiateMsg {
         halo_factory: swap_factory_contract_addr.to_string(),
+        max_referral_commission: Decimal::percent(1),
     };
 
     // instantiate swap_router
@@ -0,0 +1,144 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw_multi_test::Executor;
+use tests::environment::{ADMIN, USER, TOKEN_INITIAL_BALANCE, instantiate_contracts};
+use haloswap::airdrop::{
+    ClaimableResponse, ExecuteMsg as AirdropExecuteMsg, InstantiateMsg as AirdropInstantiateMsg,
+    IsClaimedResponse, QueryMsg as AirdropQueryMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+use sha2::{Digest, Sha256};
+
+// the leaf of an airdrop claim is the hash of the address followed by the amount
+fn leaf(address: &str, amount: u128) -> [u8; 32] {
+    Sha256::digest(format!("{}{}", address, amount).as_bytes()).into()
+}
+
+// test to airdrop the HALO token, token A stands for HALO
+mod airdrop_halo {
+    use super::*;
+
+    // the user claims the airdrop and the admin withdraws the rest once it expires
+    #[test]
+    fn claim_and_withdraw() {
+        // instantiate contracts
+        let (mut app,
+            token_a_contract_addr,
+            _token_b_contract_addr,
+            _swap_factory_contract_addr,
+            _swap_router_contract_addr,
+            code_ids
+        ) = instantiate_contracts();
+
+        let airdrop_contract_addr = app
+            .instantiate_contract(
+                code_ids.halo_airdrop_code_id,
+                Addr::unchecked(ADMIN),
+                &AirdropInstantiateMsg {
+                    halo_token: token_a_contract_addr.clone(),
+                },
+                &[],
+                "test instantiate airdrop",
+                None,
+            )
+            .unwrap();
+
+        // the tree airdrops 300 HALO to the user and 200 HALO to the admin
+        let user_leaf = leaf(USER, 300);
+        let admin_leaf = leaf(ADMIN, 200);
+        let mut leaves = [user_leaf, admin_leaf];
+        leaves.sort_unstable();
+        let merkle_root: [u8; 32] = Sha256::digest(leaves.concat()).into();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: airdrop_contract_addr.to_string(),
+                amount: Uint128::new(500),
+            },
+            &[]
+        ).unwrap();
+        let expiration = app.block_info().time.seconds() + 1000;
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            airdrop_contract_addr.clone(),
+            &AirdropExecuteMsg::RegisterMerkleRoot {
+                merkle_root: hex::encode(merkle_root),
+                total_amount: Uint128::new(500),
+                expiration: Some(expiration),
+            },
+            &[]
+        ).unwrap();
+
+        // the user cannot claim more than its airdrop
+        let res = app.execute_contract(
+            Addr::unchecked(USER),
+            airdrop_contract_addr.clone(),
+            &AirdropExecuteMsg::Claim {
+                stage: 1,
+                amount: Uint128::new(301),
+                proof: vec![hex::encode(admin_leaf)],
+            },
+            &[]
+        );
+        assert_eq!(res.unwrap_err().source().unwrap().to_string(), "Merkle proof verification failed");
+
+        let claimable: ClaimableResponse = app.wrap().query_wasm_smart(
+            airdrop_contract_addr.clone(),
+            &AirdropQueryMsg::Claimable {
+                stage: 1,
+                address: USER.to_string(),
+                amount: Uint128::new(300),
+                proof: vec![hex::encode(admin_leaf)],
+            },
+        ).unwrap();
+        assert_eq!(claimable.claimable, Uint128::new(300));
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            airdrop_contract_addr.clone(),
+            &AirdropExecuteMsg::Claim {
+                stage: 1,
+                amount: Uint128::new(300),
+                proof: vec![hex::encode(admin_leaf)],
+            },
+            &[]
+        ).unwrap();
+        let is_claimed: IsClaimedResponse = app.wrap().query_wasm_smart(
+            airdrop_contract_addr.clone(),
+            &AirdropQueryMsg::IsClaimed {
+                stage: 1,
+                address: USER.to_string(),
+            },
+        ).unwrap();
+        assert!(is_claimed.is_claimed);
+
+        // the admin withdraws the 200 HALO not claimed once the airdrop expires
+        let res = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            airdrop_contract_addr.clone(),
+            &AirdropExecuteMsg::Withdraw { stage: 1 },
+            &[]
+        );
+        assert_eq!(res.unwrap_err().source().unwrap().to_string(), "Stage 1 has not expired");
+
+        app.update_block(|block| block.time = block.time.plus_seconds(1000));
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            airdrop_contract_addr.clone(),
+            &AirdropExecuteMsg::Withdraw { stage: 1 },
+            &[]
+        ).unwrap();
+
+        let balance = |address: &str| -> Uint128 {
+            let res: BalanceResponse = app.wrap().query_wasm_smart(
+                token_a_contract_addr.clone(),
+                &Cw20QueryMsg::Balance { address: address.to_string() },
+            ).unwrap();
+            res.balance
+        };
+        assert_eq!(balance(USER), Uint128::new(300));
+        assert_eq!(balance(ADMIN), Uint128::new(TOKEN_INITIAL_BALANCE - 300));
+        assert_eq!(balance(airdrop_contract_addr.as_str()), Uint128::zero());
+    }
+}
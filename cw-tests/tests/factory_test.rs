@@ -4,6 +4,7 @@ use tests::environment::{ADMIN, NATIVE_DENOM, instantiate_contracts};
 use haloswap::factory::{ExecuteMsg as FactoryExecuteMsg, QueryMsg as FactoryQueryMsg};
 use haloswap::asset::{AssetInfo, PairInfo};
 use haloswap::pair::QueryMsg as PairQueryMsg;
+use cw20::{Cw20QueryMsg, MarketingInfoResponse, TokenInfoResponse};
 
 // pub fn create_pair(
 //     mut app: App,
@@ -65,6 +66,7 @@ mod create_pair_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -105,6 +107,7 @@ mod create_pair_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -145,6 +148,7 @@ mod create_pair_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -185,6 +189,7 @@ mod create_pair_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -246,6 +251,12 @@ mod create_pair_cw20_and_cw20 {
 
         // the liquidity_token of the pair info should be the lp_token_contract_addr
         assert_eq!(pair_info.liquidity_token, lp_token_contract_addr.clone());
+
+        // the lp token is named after the symbols of token A and token B
+        let token_info: TokenInfoResponse = app.wrap().query_wasm_smart(lp_token_contract_addr.clone(), &Cw20QueryMsg::TokenInfo {}).unwrap();
+        assert_eq!(token_info.name, "TKA-TKB-LP");
+        let marketing_info: MarketingInfoResponse = app.wrap().query_wasm_smart(lp_token_contract_addr, &Cw20QueryMsg::MarketingInfo {}).unwrap();
+        assert_eq!(marketing_info.description, Some("Haloswap liquidity token of the TKA-TKB pair".to_string()));
     }
 
     // cannot create a new pair if the pair already exists
@@ -275,6 +286,7 @@ mod create_pair_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -352,6 +364,7 @@ mod create_pair_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -392,6 +405,7 @@ mod create_pair_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -469,6 +483,7 @@ mod create_pair_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -517,6 +532,7 @@ mod create_pair_native_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -545,7 +561,7 @@ mod create_pair_native_and_cw20 {
         // create message to allow the native token to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token decimals message on factory contract
@@ -574,7 +590,7 @@ mod create_pair_native_and_cw20 {
         // create message to allow the native token to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token decimals message on factory contract
@@ -603,7 +619,7 @@ mod create_pair_native_and_cw20 {
         // create message to allow the native token to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token decimals message on factory contract
@@ -640,6 +656,7 @@ mod create_pair_native_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -739,6 +756,7 @@ mod create_new_pair_native_and_native {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -767,7 +785,7 @@ mod create_new_pair_native_and_native {
         // create message to allow the native token to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token decimals message on factory contract
@@ -781,7 +799,7 @@ mod create_new_pair_native_and_native {
         // create message to allow the native token 2 to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM_2.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token 2 decimals message on factory contract
@@ -807,6 +825,7 @@ mod create_new_pair_native_and_native {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -892,7 +911,7 @@ mod query_test {
         // create message to allow the native token to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token decimals message on factory contract
@@ -906,7 +925,7 @@ mod query_test {
         // create message to allow the native token 2 to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM_2.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token 2 decimals message on factory contract
@@ -932,6 +951,7 @@ mod query_test {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -957,6 +977,7 @@ mod query_test {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -1002,7 +1023,7 @@ mod query_test {
         // create message to allow the native token to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token decimals message on factory contract
@@ -1016,7 +1037,7 @@ mod query_test {
         // create message to allow the native token 2 to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM_2.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token 2 decimals message on factory contract
@@ -1042,6 +1063,7 @@ mod query_test {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -1067,6 +1089,7 @@ mod query_test {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -0,0 +1,209 @@
+use cosmwasm_std::{to_json_binary, Addr, CosmosMsg, Decimal, StdError, Uint128, WasmMsg};
+use cw_multi_test::{next_block, Executor};
+use tests::environment::{ADMIN, USER, instantiate_contracts};
+use haloswap::factory::{ExecuteMsg as FactoryExecuteMsg, QueryMsg as FactoryQueryMsg};
+use haloswap::asset::{AssetInfo, CreatePairRequirements, PairInfo};
+use haloswap::gov_staking::{Cw20HookMsg as GovStakingHookMsg, InstantiateMsg as GovStakingInstantiateMsg};
+use haloswap::governance::{
+    ExecuteMsg as GovernanceExecuteMsg, InstantiateMsg as GovernanceInstantiateMsg,
+    ProposalResponse, QueryMsg as GovernanceQueryMsg, Status, Vote,
+};
+use cw20::Cw20ExecuteMsg;
+
+// test to hand the admin powers of the factory to the governance
+mod governance_owns_factory {
+    use super::*;
+
+    // the stakers migrate a pair through a proposal
+    #[test]
+    fn migrate_pair_through_proposal() {
+        // instantiate contracts
+        let (mut app,
+            token_a_contract_addr,
+            token_b_contract_addr,
+            swap_factory_contract_addr,
+            _swap_router_contract_addr,
+            code_ids
+        ) = instantiate_contracts();
+
+        // create the pair between token A and token B while the admin owns the factory
+        let asset_infos = [
+            AssetInfo::Token {
+                contract_addr: token_a_contract_addr.clone()
+            },
+            AssetInfo::Token {
+                contract_addr: token_b_contract_addr.clone()
+            },
+        ];
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(swap_factory_contract_addr.clone()),
+            &FactoryExecuteMsg::CreatePair {
+                asset_infos: asset_infos.clone(),
+                requirements: CreatePairRequirements {
+                    whitelist: vec![Addr::unchecked(ADMIN.to_string())],
+                    first_asset_minimum: Uint128::new(1000000),
+                    second_asset_minimum: Uint128::new(1000000),
+                },
+                liquidity_token_kind: None,
+            },
+            &[]
+        ).unwrap();
+        let pair_info: PairInfo = app.wrap().query_wasm_smart(
+            swap_factory_contract_addr.clone(),
+            &FactoryQueryMsg::Pair { asset_infos },
+        ).unwrap();
+
+        // token A is staked for voting power, 600 by the admin and 400 by the user
+        let gov_staking_contract_addr = app
+            .instantiate_contract(
+                code_ids.halo_gov_staking_code_id,
+                Addr::unchecked(ADMIN),
+                &GovStakingInstantiateMsg {
+                    halo_token: token_a_contract_addr.clone(),
+                    unbonding_period: 100,
+                    max_lock_duration: 0,
+                },
+                &[],
+                "test instantiate gov staking",
+                None,
+            )
+            .unwrap();
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: USER.to_string(),
+                amount: Uint128::new(400),
+            },
+            &[]
+        ).unwrap();
+        for (staker, amount) in [(ADMIN, 600u128), (USER, 400u128)] {
+            app.execute_contract(
+                Addr::unchecked(staker),
+                Addr::unchecked(token_a_contract_addr.clone()),
+                &Cw20ExecuteMsg::Send {
+                    contract: gov_staking_contract_addr.to_string(),
+                    amount: Uint128::new(amount),
+                    msg: to_json_binary(&GovStakingHookMsg::Stake { lock_duration: None }).unwrap(),
+                },
+                &[]
+            ).unwrap();
+        }
+        app.update_block(next_block);
+
+        let governance_contract_addr = app
+            .instantiate_contract(
+                code_ids.halo_governance_code_id,
+                Addr::unchecked(ADMIN),
+                &GovernanceInstantiateMsg {
+                    gov_staking: gov_staking_contract_addr.to_string(),
+                    threshold: Decimal::percent(50),
+                    quorum: Decimal::percent(30),
+                    voting_period: 100,
+                    timelock_period: 50,
+                },
+                &[],
+                "test instantiate governance",
+                None,
+            )
+            .unwrap();
+
+        // the admin hands the ownership of the factory to the governance
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(swap_factory_contract_addr.clone()),
+            &FactoryExecuteMsg::UpdateConfig {
+                owner: Some(governance_contract_addr.to_string()),
+                token_code_id: None,
+                pair_code_id: None,
+            },
+            &[]
+        ).unwrap();
+
+        let migrate_msg = FactoryExecuteMsg::MigratePair {
+            contract: pair_info.contract_addr.clone(),
+            code_id: None,
+        };
+        let res = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(swap_factory_contract_addr.clone()),
+            &migrate_msg,
+            &[]
+        );
+        assert_eq!(res.unwrap_err().source().unwrap().to_string(), StdError::generic_err("unauthorized").to_string());
+
+        // the user proposes to upgrade the pair code and migrate the pair to it
+        let factory_msg = |msg: &FactoryExecuteMsg| CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: swap_factory_contract_addr.clone(),
+            msg: to_json_binary(msg).unwrap(),
+            funds: vec![],
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            governance_contract_addr.clone(),
+            &GovernanceExecuteMsg::Propose {
+                title: "Upgrade the pair".to_string(),
+                description: "Migrate the pair to the upgraded code".to_string(),
+                msgs: vec![
+                    factory_msg(&FactoryExecuteMsg::UpdateConfig {
+                        owner: None,
+                        token_code_id: None,
+                        pair_code_id: Some(code_ids.halo_pair_upgrade_code_id),
+                    }),
+                    factory_msg(&migrate_msg),
+                ],
+            },
+            &[]
+        ).unwrap();
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            governance_contract_addr.clone(),
+            &GovernanceExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: Vote::Yes,
+            },
+            &[]
+        ).unwrap();
+
+        // the proposal passes at the end of the votes and waits for the timelock
+        let execute_msg = GovernanceExecuteMsg::Execute { proposal_id: 1 };
+        let res = app.execute_contract(
+            Addr::unchecked(USER),
+            governance_contract_addr.clone(),
+            &execute_msg,
+            &[]
+        );
+        assert_eq!(res.unwrap_err().source().unwrap().to_string(), "Proposal has not passed");
+
+        app.update_block(|block| block.time = block.time.plus_seconds(100));
+        let proposal: ProposalResponse = app.wrap().query_wasm_smart(
+            governance_contract_addr.clone(),
+            &GovernanceQueryMsg::Proposal { proposal_id: 1 },
+        ).unwrap();
+        assert_eq!(proposal.status, Status::Passed);
+
+        let res = app.execute_contract(
+            Addr::unchecked(USER),
+            governance_contract_addr.clone(),
+            &execute_msg,
+            &[]
+        );
+        assert_eq!(
+            res.unwrap_err().source().unwrap().to_string(),
+            format!("Proposal is timelocked until {}", proposal.executable_at)
+        );
+
+        app.update_block(|block| block.time = block.time.plus_seconds(50));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            governance_contract_addr.clone(),
+            &execute_msg,
+            &[]
+        ).unwrap();
+
+        // the pair runs the upgraded code
+        let contract_info = app.wrap().query_wasm_contract_info(pair_info.contract_addr).unwrap();
+        assert_eq!(contract_info.code_id, code_ids.halo_pair_upgrade_code_id);
+    }
+}
@@ -1,12 +1,15 @@
-use cosmwasm_std::{Addr, Uint128, Coin, StdError};
+use cosmwasm_std::{to_json_vec, Addr, Binary, Uint128, Coin, StdError};
 use cw_multi_test::Executor;
 use tests::environment::{ADMIN, instantiate_contracts};
 use haloswap::factory::{NativeTokenDecimalsResponse, ExecuteMsg as FactoryExecuteMsg, QueryMsg as FactoryQueryMsg};
 use haloswap::asset::{Asset, AssetInfo, PairInfo};
-use haloswap::pair::{ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg};
+use haloswap::pair::{ExecuteMsg as PairExecuteMsg, LiquidityPermit, LiquidityPermitParams, QueryMsg as PairQueryMsg};
 use cosmwasm_std::{OverflowError, OverflowOperation};
 use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, BalanceResponse};
 use tests::environment::NATIVE_DENOM;
+use haloswap::token::{ExecuteMsg as TokenExecuteMsg, Permit, PermitParams};
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use sha2::{Digest, Sha256};
 
 // test to add liquidity to a pair of cw20 tokens
 mod add_liquidity_to_cw20_and_cw20 {
@@ -42,6 +45,7 @@ mod add_liquidity_to_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -192,6 +196,7 @@ mod add_liquidity_to_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -341,6 +346,7 @@ mod add_liquidity_to_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -494,6 +500,7 @@ mod add_liquidity_to_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1),
                 second_asset_minimum: Uint128::new(1),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -645,6 +652,7 @@ mod add_liquidity_to_cw20_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -800,6 +808,154 @@ mod add_liquidity_to_cw20_and_cw20 {
         assert_eq!(balance.balance, Uint128::from(10000000u128));
     }
 
+    // the permits signed by the owner approve the pair in the liquidity provision transaction
+    // relayed by the ADMIN
+    #[test]
+    fn provide_liquidity_with_permits() {
+        // the address of the secp256k1 key of the bytes [1u8; 32]
+        let owner = "aura10xcqpzrky6eff2g52qdye53xkk9jxkvr6kd7z6";
+
+        // instantiate contracts
+        let (mut app,
+            token_a_contract_addr,
+            token_b_contract_addr,
+            swap_factory_contract_addr,
+            _swap_router_contract_addr,
+            _code_ids
+        ) = instantiate_contracts();
+
+        let asset_infos = [
+            AssetInfo::Token {
+                contract_addr: token_a_contract_addr.clone()
+            },
+            AssetInfo::Token {
+                contract_addr: token_b_contract_addr.clone()
+            },
+        ];
+
+        // create the pair of token_a and token_b
+        let _res = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(swap_factory_contract_addr.clone()),
+            &FactoryExecuteMsg::CreatePair {
+                asset_infos: asset_infos.clone(),
+                requirements: CreatePairRequirements {
+                    whitelist: vec![Addr::unchecked(owner.to_string())],
+                    first_asset_minimum: Uint128::new(1000000),
+                    second_asset_minimum: Uint128::new(1000000),
+                },
+                liquidity_token_kind: None,
+            },
+            &[]
+        ).unwrap();
+        let pair_info: PairInfo = app.wrap().query_wasm_smart(
+            swap_factory_contract_addr.clone(),
+            &FactoryQueryMsg::Pair { asset_infos },
+        ).unwrap();
+
+        // the owner holds 10000000 token_a and 10000000 token_b
+        for token in [&token_a_contract_addr, &token_b_contract_addr] {
+            let _res = app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(token.clone()),
+                &Cw20ExecuteMsg::Transfer {
+                    recipient: owner.to_string(),
+                    amount: Uint128::from(10000000u128),
+                },
+                &[]
+            ).unwrap();
+        }
+
+        // the owner signs the permits of 10000000 token_a and 10000000 token_b to the pair
+        // and the deposit of both of them to the pair
+        let signing_key = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+        let pubkey = Binary::from(signing_key.verifying_key().to_encoded_point(true).as_bytes());
+        let chain_id = app.block_info().chain_id;
+        let expires = app.block_info().time.seconds() + 100;
+        let permits = [&token_a_contract_addr, &token_b_contract_addr].map(|token| {
+            let params = PermitParams {
+                chain_id: chain_id.clone(),
+                token: token.clone(),
+                owner: owner.to_string(),
+                spender: pair_info.contract_addr.clone(),
+                amount: Uint128::from(10000000u128),
+                nonce: 0,
+                expires,
+            };
+            let hash = Sha256::digest(to_json_vec(&params).unwrap());
+            let signature: Signature = signing_key.sign_prehash(&hash).unwrap();
+            Permit { params, pubkey: pubkey.clone(), signature: Binary::from(signature.to_bytes().as_slice()) }
+        });
+        let params = LiquidityPermitParams {
+            chain_id: chain_id.clone(),
+            pair: pair_info.contract_addr.clone(),
+            owner: owner.to_string(),
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_a_contract_addr.clone()
+                    },
+                    amount: Uint128::from(10000000u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_b_contract_addr.clone()
+                    },
+                    amount: Uint128::from(10000000u128),
+                },
+            ],
+            min_share: Uint128::from(10000000u128),
+            receiver: None,
+            nonce: 0,
+            expires,
+        };
+        let hash = Sha256::digest(to_json_vec(&params).unwrap());
+        let signature: Signature = signing_key.sign_prehash(&hash).unwrap();
+        let liquidity_permit = LiquidityPermit { params, pubkey: pubkey.clone(), signature: Binary::from(signature.to_bytes().as_slice()) };
+
+        // the ADMIN provides the liquidity of the owner, which did not approve the pair beforehand
+        let _res = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(pair_info.contract_addr.clone()),
+            &PairExecuteMsg::ProvideLiquidityWithPermits {
+                liquidity_permit: liquidity_permit.clone(),
+                permits: permits.to_vec(),
+            },
+            &[]
+        ).unwrap();
+
+        // the balance of lp_token of the owner should be 10000000
+        let balance: BalanceResponse = app.wrap().query_wasm_smart(
+            pair_info.liquidity_token.clone(),
+            &Cw20QueryMsg::Balance { address: owner.to_string() },
+        ).unwrap();
+        assert_eq!(balance.balance, Uint128::from(10000000u128));
+        let balance: BalanceResponse = app.wrap().query_wasm_smart(
+            token_a_contract_addr.clone(),
+            &Cw20QueryMsg::Balance { address: owner.to_string() },
+        ).unwrap();
+        assert_eq!(balance.balance, Uint128::zero());
+
+        // the permits cannot be replayed
+        let res = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &TokenExecuteMsg::Permit(permits[0].clone()),
+            &[]
+        );
+        assert_eq!(res.unwrap_err().source().unwrap().to_string(), "Generic error: Invalid permit nonce, expected 1");
+        let res = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(pair_info.contract_addr.clone()),
+            &PairExecuteMsg::ProvideLiquidityWithPermits {
+                liquidity_permit,
+                permits: vec![],
+            },
+            &[]
+        );
+        assert_eq!(res.unwrap_err().source().unwrap().to_string(), "Generic error: Invalid permit nonce, expected 1");
+    }
+
 }
 
 // test to add liquidity to a pair of native token and cw20 token
@@ -824,7 +980,7 @@ mod add_liquidity_to_native_and_cw20 {
         // create message to allow the native token to be used in the pair
         let msg = FactoryExecuteMsg::AddNativeTokenDecimals {
             denom: NATIVE_DENOM.to_string(),
-            decimals: 6u8
+            decimals: Some(6u8)
         };
 
         // execute add native token decimals message on factory contract
@@ -861,6 +1017,7 @@ mod add_liquidity_to_native_and_cw20 {
                 first_asset_minimum: Uint128::new(1000000),
                 second_asset_minimum: Uint128::new(1000000),
             },
+            liquidity_token_kind: None,
         };
 
         // execute create pair message on factory contract
@@ -0,0 +1,457 @@
+use cosmwasm_std::{to_json_binary, Addr, Uint128};
+use cw_multi_test::Executor;
+use tests::environment::{ADMIN, USER, HaloApp, instantiate_contracts};
+use haloswap::factory::ExecuteMsg as FactoryExecuteMsg;
+use haloswap::asset::{Asset, AssetInfo, CreatePairRequirements};
+use haloswap::pair::ExecuteMsg as PairExecuteMsg;
+use haloswap::router::{Cw20HookMsg as RouterHookMsg, SwapOperation};
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, BalanceResponse};
+
+// test to swap through the router
+mod execute_swap_operations {
+    use super::*;
+
+    /// create a pair of token_a and token_b, provide 10000000 of each token to it
+    /// and return the address of the pair contract
+    fn create_pair_with_liquidity(
+        app: &mut HaloApp,
+        token_a_contract_addr: &str,
+        token_b_contract_addr: &str,
+        swap_factory_contract_addr: &str,
+    ) -> String {
+        // create message to create new pair
+        let msg = FactoryExecuteMsg::CreatePair {
+            asset_infos: [
+                AssetInfo::Token {
+                    contract_addr: token_a_contract_addr.to_string()
+                },
+                AssetInfo::Token {
+                    contract_addr: token_b_contract_addr.to_string()
+                },
+            ],
+            requirements: CreatePairRequirements {
+                whitelist: vec![Addr::unchecked(ADMIN.to_string())],
+                first_asset_minimum: Uint128::zero(),
+                second_asset_minimum: Uint128::zero(),
+            },
+            liquidity_token_kind: None,
+        };
+
+        // execute create pair message on factory contract
+        let res = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(swap_factory_contract_addr),
+            &msg,
+            &[]
+        ).unwrap();
+
+        // get the address of the pair contract from the attributes of the factory
+        let pair_contract_addr = res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attribute| attribute.key == "pair_contract_addr")
+            .unwrap()
+            .value
+            .clone();
+
+        // approve the pair contract to spend 10000000 token_a and 10000000 token_b
+        for token_contract_addr in [token_a_contract_addr, token_b_contract_addr] {
+            let msg = Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair_contract_addr.clone(),
+                amount: Uint128::from(10000000u128),
+                expires: None,
+            };
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(token_contract_addr),
+                &msg,
+                &[]
+            ).unwrap();
+        }
+
+        // provide 10000000 token_a and 10000000 token_b to the pair
+        let msg = PairExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_a_contract_addr.to_string()
+                    },
+                    amount: Uint128::from(10000000u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_b_contract_addr.to_string()
+                    },
+                    amount: Uint128::from(10000000u128),
+                },
+            ],
+            slippage_tolerance: None,
+            receiver: None,
+        };
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(pair_contract_addr.clone()),
+            &msg,
+            &[]
+        ).unwrap();
+
+        pair_contract_addr
+    }
+
+    // swap token_a to token_b through the router
+    #[test]
+    fn swap_cw20_to_cw20_successfully() {
+        // instantiate contracts
+        let (mut app,
+            token_a_contract_addr,
+            token_b_contract_addr,
+            swap_factory_contract_addr,
+            swap_router_contract_addr,
+            _code_ids
+        ) = instantiate_contracts();
+
+        let _pair_contract_addr = create_pair_with_liquidity(
+            &mut app,
+            &token_a_contract_addr,
+            &token_b_contract_addr,
+            &swap_factory_contract_addr,
+        );
+
+        // prepare the message to swap 1000000 token_a to token_b and send the result to USER
+        let msg = Cw20ExecuteMsg::Send {
+            contract: swap_router_contract_addr.clone(),
+            amount: Uint128::from(1000000u128),
+            msg: to_json_binary(&RouterHookMsg::ExecuteSwapOperations {
+                operations: vec![SwapOperation::HaloSwap {
+                    offer_asset_info: AssetInfo::Token {
+                        contract_addr: token_a_contract_addr.clone()
+                    },
+                    ask_asset_info: AssetInfo::Token {
+                        contract_addr: token_b_contract_addr.clone()
+                    },
+                }],
+                minimum_receive: Some(Uint128::from(900000u128)),
+                to: Some(USER.to_string()),
+                referral_address: None,
+                referral_commission: None,
+                callback: None,
+            }).unwrap(),
+        };
+
+        // execute the swap
+        let res = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &msg,
+            &[]
+        ).unwrap();
+
+        // query the balance of token_b of USER
+        let balance: BalanceResponse = app.wrap().query_wasm_smart(
+            token_b_contract_addr.clone(),
+            &Cw20QueryMsg::Balance { address: USER.to_string() },
+        ).unwrap();
+
+        // the return amount of the pair is 1000000 * 10000000 / 11000000 = 909090, minus 0.3% commission
+        assert_eq!(balance.balance, Uint128::from(906363u128));
+
+        // the router should not hold any asset after the swap
+        let balance: BalanceResponse = app.wrap().query_wasm_smart(
+            token_b_contract_addr.clone(),
+            &Cw20QueryMsg::Balance { address: swap_router_contract_addr.clone() },
+        ).unwrap();
+        assert_eq!(balance.balance, Uint128::zero());
+
+        // the router reports the result of the swap
+        let return_asset = res.events
+            .iter()
+            .filter(|event| event.ty == "wasm")
+            .flat_map(|event| event.attributes.iter())
+            .find(|attribute| attribute.key == "return_asset")
+            .unwrap()
+            .value
+            .clone();
+        assert_eq!(return_asset, format!("906363{}", token_b_contract_addr));
+    }
+
+    // cannot swap if the return amount is less than the minimum receive
+    #[test]
+    fn cannot_swap_less_than_minimum_receive() {
+        // instantiate contracts
+        let (mut app,
+            token_a_contract_addr,
+            token_b_contract_addr,
+            swap_factory_contract_addr,
+            swap_router_contract_addr,
+            _code_ids
+        ) = instantiate_contracts();
+
+        let _pair_contract_addr = create_pair_with_liquidity(
+            &mut app,
+            &token_a_contract_addr,
+            &token_b_contract_addr,
+            &swap_factory_contract_addr,
+        );
+
+        // prepare the message to swap 1000000 token_a to at least 1000000 token_b
+        let msg = Cw20ExecuteMsg::Send {
+            contract: swap_router_contract_addr.clone(),
+            amount: Uint128::from(1000000u128),
+            msg: to_json_binary(&RouterHookMsg::ExecuteSwapOperations {
+                operations: vec![SwapOperation::HaloSwap {
+                    offer_asset_info: AssetInfo::Token {
+                        contract_addr: token_a_contract_addr.clone()
+                    },
+                    ask_asset_info: AssetInfo::Token {
+                        contract_addr: token_b_contract_addr.clone()
+                    },
+                }],
+                minimum_receive: Some(Uint128::from(1000000u128)),
+                to: None,
+                referral_address: None,
+                referral_commission: None,
+                callback: None,
+            }).unwrap(),
+        };
+
+        // execute the swap
+        let err = app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &msg,
+            &[]
+        ).unwrap_err();
+
+        assert_eq!(
+            err.root_cause().to_string(),
+            "Generic error: assertion failed; minimum receive amount: 1000000, swap amount: 906363"
+        );
+    }
+
+    // a deregistered pair is not used by the router
+    #[test]
+    fn cannot_swap_through_deregistered_pair() {
+        // instantiate contracts
+        let (mut app,
+            token_a_contract_addr,
+            token_b_contract_addr,
+            swap_factory_contract_addr,
+            swap_router_contract_addr,
+            _code_ids
+        ) = instantiate_contracts();
+
+        let _pair_contract_addr = create_pair_with_liquidity(
+            &mut app,
+            &token_a_contract_addr,
+            &token_b_contract_addr,
+            &swap_factory_contract_addr,
+        );
+
+        let asset_infos = [
+            AssetInfo::Token {
+                contract_addr: token_a_contract_addr.clone()
+            },
+            AssetInfo::Token {
+                contract_addr: token_b_contract_addr.clone()
+            },
+        ];
+
+        // deregister the pair
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(swap_factory_contract_addr.clone()),
+            &FactoryExecuteMsg::Deregister { asset_infos: asset_infos.clone() },
+            &[]
+        ).unwrap();
+
+        // prepare the message to swap 1000000 token_a to token_b
+        let msg = Cw20ExecuteMsg::Send {
+            contract: swap_router_contract_addr.clone(),
+            amount: Uint128::from(1000000u128),
+            msg: to_json_binary(&RouterHookMsg::ExecuteSwapOperations {
+                operations: vec![SwapOperation::HaloSwap {
+                    offer_asset_info: asset_infos[0].clone(),
+                    ask_asset_info: asset_infos[1].clone(),
+                }],
+                minimum_receive: None,
+                to: None,
+                referral_address: None,
+                referral_commission: None,
+                callback: None,
+            }).unwrap(),
+        };
+
+        // the router cannot find a pair for the operation
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &msg,
+            &[]
+        ).unwrap_err();
+
+        // the pair can be created again after the deregistration
+        create_pair_with_liquidity(
+            &mut app,
+            &token_a_contract_addr,
+            &token_b_contract_addr,
+            &swap_factory_contract_addr,
+        );
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &msg,
+            &[]
+        ).unwrap();
+    }
+}
+
+// the router reads the return amount of a hop from its balance of the ask asset
+mod swap_return_amount {
+    use super::*;
+    use cosmwasm_std::{
+        coins, BankMsg, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+        StdError, StdResult,
+    };
+    use cw_multi_test::ContractWrapper;
+    use cw_storage_plus::Item;
+    use haloswap::asset::PairInfo;
+    use haloswap::factory::QueryMsg as FactoryQueryMsg;
+    use haloswap::pair::{Cw20HookMsg as PairHookMsg, SwapResponseData};
+    use haloswap::router::{ExecuteMsg as RouterExecuteMsg, InstantiateMsg as RouterInstantiateMsg};
+    use tests::environment::{mock_app_with_codes, NATIVE_DENOM, NATIVE_DENOM_2};
+
+    const PAIR: Item<String> = Item::new("pair");
+
+    // the mock factory returns the mock pair for any asset infos
+    fn factory_instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, pair: String) -> StdResult<Response> {
+        PAIR.save(deps.storage, &pair)?;
+        Ok(Response::new())
+    }
+
+    fn factory_execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+        Err(StdError::generic_err("not supported"))
+    }
+
+    fn factory_query(deps: Deps, _env: Env, msg: FactoryQueryMsg) -> StdResult<Binary> {
+        match msg {
+            FactoryQueryMsg::Pair { asset_infos } => to_json_binary(&PairInfo {
+                asset_infos,
+                contract_addr: PAIR.load(deps.storage)?,
+                liquidity_token: "liquidity".to_string(),
+                asset_decimals: [6u8, 6u8],
+                asset_symbols: None,
+                incentive_contract: None,
+                requirements: CreatePairRequirements {
+                    whitelist: vec![],
+                    first_asset_minimum: Uint128::zero(),
+                    second_asset_minimum: Uint128::zero(),
+                },
+            }),
+            _ => Err(StdError::generic_err("not supported")),
+        }
+    }
+
+    // the mock pair swaps a native offer 1:1 to NATIVE_DENOM_2 and reports twice
+    // the return amount in its swap event and its response data
+    fn pair_instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn pair_execute(_deps: DepsMut, _env: Env, info: MessageInfo, msg: PairHookMsg) -> StdResult<Response> {
+        let PairHookMsg::Swap { to, .. } = msg else {
+            return Err(StdError::generic_err("not supported"));
+        };
+        let offer = info.funds[0].clone();
+        let receiver = to.unwrap_or_else(|| info.sender.to_string());
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: receiver.clone(),
+                amount: coins(offer.amount.u128(), NATIVE_DENOM_2),
+            })
+            .add_attribute("action", "swap")
+            .add_attribute("return_amount", (offer.amount * Uint128::from(2u128)).to_string())
+            .set_data(to_json_binary(&SwapResponseData {
+                offer_asset: AssetInfo::NativeToken { denom: offer.denom },
+                ask_asset: AssetInfo::NativeToken { denom: NATIVE_DENOM_2.to_string() },
+                offer_amount: offer.amount,
+                return_amount: offer.amount * Uint128::from(2u128),
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+                receiver,
+            })?))
+    }
+
+    fn pair_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Err(StdError::generic_err("not supported"))
+    }
+
+    #[test]
+    fn swap_with_the_router_balance() {
+        let (mut app, code_ids, mock_code_ids) = mock_app_with_codes(vec![
+            Box::new(ContractWrapper::new(factory_execute, factory_instantiate, factory_query)),
+            Box::new(ContractWrapper::new(pair_execute, pair_instantiate, pair_query)),
+        ]);
+        let (factory_code_id, pair_code_id) = (mock_code_ids[0], mock_code_ids[1]);
+
+        let pair_contract_addr = app.instantiate_contract(
+            pair_code_id,
+            Addr::unchecked(ADMIN),
+            &Empty {},
+            &[],
+            "mock pair",
+            None,
+        ).unwrap();
+        // fund the mock pair with the ask denom
+        app.send_tokens(
+            Addr::unchecked(ADMIN),
+            pair_contract_addr.clone(),
+            &coins(10000000u128, NATIVE_DENOM_2),
+        ).unwrap();
+
+        let factory_contract_addr = app.instantiate_contract(
+            factory_code_id,
+            Addr::unchecked(ADMIN),
+            &pair_contract_addr.to_string(),
+            &[],
+            "mock factory",
+            None,
+        ).unwrap();
+        let router_contract_addr = app.instantiate_contract(
+            code_ids.halo_router_code_id,
+            Addr::unchecked(ADMIN),
+            &RouterInstantiateMsg {
+                halo_factory: factory_contract_addr.to_string(),
+                max_referral_commission: Decimal::percent(1),
+            },
+            &[],
+            "router",
+            None,
+        ).unwrap();
+
+        // swap 1000000 NATIVE_DENOM to NATIVE_DENOM_2 through the router
+        let msg = RouterExecuteMsg::ExecuteSwapOperations {
+            operations: vec![SwapOperation::HaloSwap {
+                offer_asset_info: AssetInfo::NativeToken { denom: NATIVE_DENOM.to_string() },
+                ask_asset_info: AssetInfo::NativeToken { denom: NATIVE_DENOM_2.to_string() },
+            }],
+            minimum_receive: Some(Uint128::from(1000000u128)),
+            to: Some(USER.to_string()),
+            referral_address: None,
+            referral_commission: None,
+            callback: None,
+        };
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            router_contract_addr,
+            &msg,
+            &coins(1000000u128, NATIVE_DENOM),
+        ).unwrap();
+
+        // USER receives the amount the router actually received, not the reported one
+        let balance = app.wrap().query_balance(USER, NATIVE_DENOM_2).unwrap();
+        assert_eq!(balance.amount, Uint128::from(1000000u128));
+    }
+}
@@ -32,6 +32,7 @@ fn test_instantiate_with_too_large_initial_supply() {
             minter: ADMIN.to_string(),
             cap: Some(Uint128::new(TOKEN_INITIAL_BALANCE)),
         }),
+        marketing: None,
     };
 
     // instantiate token_A
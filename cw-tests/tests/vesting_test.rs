@@ -0,0 +1,145 @@
+use cosmwasm_std::{to_json_binary, Addr, Uint128};
+use cw_multi_test::Executor;
+use tests::environment::{ADMIN, USER, TOKEN_INITIAL_BALANCE, instantiate_contracts};
+use haloswap::vesting::{
+    Cw20HookMsg as VestingHookMsg, ExecuteMsg as VestingExecuteMsg,
+    InstantiateMsg as VestingInstantiateMsg, QueryMsg as VestingQueryMsg, VestingResponse,
+    VestingSchedule,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+
+// test to vest the HALO token, token A stands for HALO
+mod vest_halo {
+    use super::*;
+
+    // the admin vests HALO to the user with a cliff, then revokes it
+    #[test]
+    fn vest_and_revoke() {
+        // instantiate contracts
+        let (mut app,
+            token_a_contract_addr,
+            _token_b_contract_addr,
+            _swap_factory_contract_addr,
+            _swap_router_contract_addr,
+            code_ids
+        ) = instantiate_contracts();
+
+        let vesting_contract_addr = app
+            .instantiate_contract(
+                code_ids.halo_vesting_code_id,
+                Addr::unchecked(ADMIN),
+                &VestingInstantiateMsg {
+                    halo_token: token_a_contract_addr.clone(),
+                },
+                &[],
+                "test instantiate vesting",
+                None,
+            )
+            .unwrap();
+
+        // the admin vests 1000 HALO to the user over 1000 seconds with a cliff at 250 seconds
+        let start_time = app.block_info().time.seconds();
+        let schedule = VestingSchedule {
+            start_time,
+            cliff_time: start_time + 250,
+            end_time: start_time + 1000,
+        };
+        let create_vesting_msg = Cw20ExecuteMsg::Send {
+            contract: vesting_contract_addr.to_string(),
+            amount: Uint128::new(1000),
+            msg: to_json_binary(&VestingHookMsg::CreateVesting {
+                recipient: USER.to_string(),
+                schedule: schedule.clone(),
+                revocable: true,
+            }).unwrap(),
+        };
+
+        // only the admin can vest HALO
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: USER.to_string(),
+                amount: Uint128::new(1000),
+            },
+            &[]
+        ).unwrap();
+        let res = app.execute_contract(
+            Addr::unchecked(USER),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &create_vesting_msg,
+            &[]
+        );
+        assert_eq!(res.unwrap_err().root_cause().to_string(), "Unauthorized");
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(token_a_contract_addr.clone()),
+            &create_vesting_msg,
+            &[]
+        ).unwrap();
+
+        // nothing is claimable before the cliff
+        app.update_block(|block| block.time = block.time.plus_seconds(100));
+        let res = app.execute_contract(
+            Addr::unchecked(USER),
+            vesting_contract_addr.clone(),
+            &VestingExecuteMsg::Claim {},
+            &[]
+        );
+        assert_eq!(res.unwrap_err().source().unwrap().to_string(), "Nothing to claim");
+
+        // the user claims the amount vested at 400 seconds
+        app.update_block(|block| block.time = block.time.plus_seconds(300));
+        let vesting: VestingResponse = app.wrap().query_wasm_smart(
+            vesting_contract_addr.clone(),
+            &VestingQueryMsg::Vesting { recipient: USER.to_string() },
+        ).unwrap();
+        assert_eq!(vesting, VestingResponse {
+            recipient: USER.to_string(),
+            schedule,
+            total_amount: Uint128::new(1000),
+            vested_amount: Uint128::new(400),
+            claimed_amount: Uint128::zero(),
+            claimable_amount: Uint128::new(400),
+            revocable: true,
+            revoked: false,
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            vesting_contract_addr.clone(),
+            &VestingExecuteMsg::Claim {},
+            &[]
+        ).unwrap();
+
+        // the admin revokes the vesting at 600 seconds and gets the unvested amount back
+        app.update_block(|block| block.time = block.time.plus_seconds(200));
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            vesting_contract_addr.clone(),
+            &VestingExecuteMsg::Revoke { recipient: USER.to_string() },
+            &[]
+        ).unwrap();
+
+        // the user claims the rest vested before the revocation
+        app.update_block(|block| block.time = block.time.plus_seconds(400));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            vesting_contract_addr.clone(),
+            &VestingExecuteMsg::Claim {},
+            &[]
+        ).unwrap();
+
+        let balance = |address: &str| -> Uint128 {
+            let res: BalanceResponse = app.wrap().query_wasm_smart(
+                token_a_contract_addr.clone(),
+                &Cw20QueryMsg::Balance { address: address.to_string() },
+            ).unwrap();
+            res.balance
+        };
+        // the user holds the 1000 transferred and the 600 vested
+        assert_eq!(balance(USER), Uint128::new(1600));
+        assert_eq!(balance(ADMIN), Uint128::new(TOKEN_INITIAL_BALANCE - 1600));
+        assert_eq!(balance(vesting_contract_addr.as_str()), Uint128::zero());
+    }
+}
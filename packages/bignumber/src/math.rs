@@ -453,7 +453,7 @@ impl<'de> de::Visitor<'de> for Uint256Visitor {
 #[cfg(test)]
 mod test {
     use super::*;
-    use cosmwasm_std::{from_slice, to_vec, StdResult};
+    use cosmwasm_std::{from_json, to_json_vec, StdResult};
     use std::convert::TryInto;
 
     #[test]
@@ -760,47 +760,47 @@ mod test {
 
     #[test]
     fn decimal_serialize() {
-        assert_eq!(to_vec(&Decimal256::zero()).unwrap(), br#""0""#);
-        assert_eq!(to_vec(&Decimal256::one()).unwrap(), br#""1""#);
-        assert_eq!(to_vec(&Decimal256::percent(8)).unwrap(), br#""0.08""#);
-        assert_eq!(to_vec(&Decimal256::percent(87)).unwrap(), br#""0.87""#);
-        assert_eq!(to_vec(&Decimal256::percent(876)).unwrap(), br#""8.76""#);
-        assert_eq!(to_vec(&Decimal256::percent(8765)).unwrap(), br#""87.65""#);
+        assert_eq!(to_json_vec(&Decimal256::zero()).unwrap(), br#""0""#);
+        assert_eq!(to_json_vec(&Decimal256::one()).unwrap(), br#""1""#);
+        assert_eq!(to_json_vec(&Decimal256::percent(8)).unwrap(), br#""0.08""#);
+        assert_eq!(to_json_vec(&Decimal256::percent(87)).unwrap(), br#""0.87""#);
+        assert_eq!(to_json_vec(&Decimal256::percent(876)).unwrap(), br#""8.76""#);
+        assert_eq!(to_json_vec(&Decimal256::percent(8765)).unwrap(), br#""87.65""#);
     }
 
     #[test]
     fn decimal_deserialize() {
         assert_eq!(
-            from_slice::<Decimal256>(br#""0""#).unwrap(),
+            from_json::<Decimal256>(br#""0""#).unwrap(),
             Decimal256::zero()
         );
         assert_eq!(
-            from_slice::<Decimal256>(br#""1""#).unwrap(),
+            from_json::<Decimal256>(br#""1""#).unwrap(),
             Decimal256::one()
         );
         assert_eq!(
-            from_slice::<Decimal256>(br#""000""#).unwrap(),
+            from_json::<Decimal256>(br#""000""#).unwrap(),
             Decimal256::zero()
         );
         assert_eq!(
-            from_slice::<Decimal256>(br#""001""#).unwrap(),
+            from_json::<Decimal256>(br#""001""#).unwrap(),
             Decimal256::one()
         );
 
         assert_eq!(
-            from_slice::<Decimal256>(br#""0.08""#).unwrap(),
+            from_json::<Decimal256>(br#""0.08""#).unwrap(),
             Decimal256::percent(8)
         );
         assert_eq!(
-            from_slice::<Decimal256>(br#""0.87""#).unwrap(),
+            from_json::<Decimal256>(br#""0.87""#).unwrap(),
             Decimal256::percent(87)
         );
         assert_eq!(
-            from_slice::<Decimal256>(br#""8.76""#).unwrap(),
+            from_json::<Decimal256>(br#""8.76""#).unwrap(),
             Decimal256::percent(876)
         );
         assert_eq!(
-            from_slice::<Decimal256>(br#""87.65""#).unwrap(),
+            from_json::<Decimal256>(br#""87.65""#).unwrap(),
             Decimal256::percent(8765)
         );
     }
@@ -831,9 +831,9 @@ mod test {
     #[test]
     fn uint256_json() {
         let orig = Uint256::from(1234567890987654321u64);
-        let serialized = to_vec(&orig).unwrap();
+        let serialized = to_json_vec(&orig).unwrap();
         assert_eq!(serialized.as_slice(), b"\"1234567890987654321\"");
-        let parsed: Uint256 = from_slice(&serialized).unwrap();
+        let parsed: Uint256 = from_json(&serialized).unwrap();
         assert_eq!(parsed, orig);
     }
 
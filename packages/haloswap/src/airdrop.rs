@@ -0,0 +1,87 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub halo_token: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+    },
+    /// RegisterMerkleRoot opens a new stage, the airdropped HALO is sent to the contract apart
+    RegisterMerkleRoot {
+        /// The hex encoded root of the tree of the sha256 hashes of `{address}{amount}`
+        merkle_root: String,
+        total_amount: Uint128,
+        /// The time the claims of the stage end
+        expiration: Option<u64>,
+    },
+    Claim {
+        stage: u8,
+        amount: Uint128,
+        /// The hex encoded hashes from the leaf to the root
+        proof: Vec<String>,
+    },
+    /// Withdraw returns the amount not claimed in an expired stage to the owner
+    Withdraw {
+        stage: u8,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(MerkleRootResponse)]
+    MerkleRoot { stage: u8 },
+    #[returns(LatestStageResponse)]
+    LatestStage {},
+    #[returns(IsClaimedResponse)]
+    IsClaimed { stage: u8, address: String },
+    /// Claimable returns the amount the address can claim with the proof at the current block,
+    /// zero when the proof is invalid, the stage is expired or the amount is already claimed
+    #[returns(ClaimableResponse)]
+    Claimable {
+        stage: u8,
+        address: String,
+        amount: Uint128,
+        proof: Vec<String>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub halo_token: String,
+}
+
+#[cw_serde]
+pub struct MerkleRootResponse {
+    pub stage: u8,
+    pub merkle_root: String,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub expiration: Option<u64>,
+}
+
+#[cw_serde]
+pub struct LatestStageResponse {
+    pub latest_stage: u8,
+}
+
+#[cw_serde]
+pub struct IsClaimedResponse {
+    pub is_claimed: bool,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub claimable: Uint128,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -3,8 +3,8 @@ use std::fmt;
 
 use crate::querier::{query_balance, query_native_decimals, query_token_balance, query_token_info};
 use cosmwasm_std::{
-    to_binary, Addr, Api, BankMsg, CanonicalAddr, Coin, CosmosMsg, MessageInfo, QuerierWrapper,
-    StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    to_json_binary, Addr, Api, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, MessageInfo,
+    QuerierWrapper, StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
@@ -31,7 +31,7 @@ impl Asset {
         match &self.info {
             AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: contract_addr.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
                     recipient: recipient.to_string(),
                     amount,
                 })?,
@@ -51,6 +51,30 @@ impl Asset {
         Ok(SubMsg::new(self.into_msg(recipient)?))
     }
 
+    /// Deliver the asset to the callback contract together with the callback message,
+    /// as a cw20 `Send` for tokens or as attached funds for native tokens
+    pub fn into_callback_msg(self, callback: SwapCallback) -> StdResult<CosmosMsg> {
+        match self.info {
+            AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: callback.contract,
+                    amount: self.amount,
+                    msg: callback.msg,
+                })?,
+                funds: vec![],
+            })),
+            AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: callback.contract,
+                msg: callback.msg,
+                funds: vec![Coin {
+                    amount: self.amount,
+                    denom,
+                }],
+            })),
+        }
+    }
+
     pub fn assert_sent_native_token_balance(&self, message_info: &MessageInfo) -> StdResult<()> {
         if let AssetInfo::NativeToken { denom } = &self.info {
             match message_info.funds.iter().find(|x| x.denom == *denom) {
@@ -168,6 +192,14 @@ impl AssetInfo {
         }
     }
 
+    /// Classify the denom of a native token, cw20 tokens have no denom kind
+    pub fn denom_kind(&self) -> Option<DenomKind> {
+        match self {
+            AssetInfo::NativeToken { denom } => Some(classify_denom(denom)),
+            AssetInfo::Token { .. } => None,
+        }
+    }
+
     pub fn query_decimals(&self, account_addr: Addr, querier: &QuerierWrapper) -> StdResult<u8> {
         match self {
             AssetInfo::NativeToken { denom } => {
@@ -179,6 +211,74 @@ impl AssetInfo {
             }
         }
     }
+
+    /// The symbol of a cw20 token, or a short name of the denom of a native token
+    pub fn query_symbol(&self, querier: &QuerierWrapper) -> StdResult<String> {
+        match self {
+            AssetInfo::NativeToken { denom } => Ok(match classify_denom(denom) {
+                DenomKind::Native => denom.to_string(),
+                DenomKind::Ibc { hash } => format!("{}{}", IBC_DENOM_PREFIX, &hash[..6]),
+                DenomKind::TokenFactory { subdenom, .. } => subdenom,
+            }),
+            AssetInfo::Token { contract_addr } => {
+                let token_info = query_token_info(querier, Addr::unchecked(contract_addr))?;
+                Ok(token_info.symbol)
+            }
+        }
+    }
+}
+
+/// SwapCallback executes `msg` on `contract` with the swapped asset attached
+#[cw_serde]
+pub struct SwapCallback {
+    pub contract: String,
+    pub msg: Binary,
+}
+
+const IBC_DENOM_PREFIX: &str = "ibc/";
+pub(crate) const TOKEN_FACTORY_DENOM_PREFIX: &str = "factory";
+
+/// The origin of a native denom
+#[cw_serde]
+pub enum DenomKind {
+    /// A denom of the chain itself, such as `uatom`
+    Native,
+    /// A denom transferred over IBC, `ibc/{hash}`
+    Ibc { hash: String },
+    /// A denom minted by the token factory module, `factory/{creator}/{subdenom}`
+    TokenFactory { creator: String, subdenom: String },
+}
+
+/// Classify a native denom by its format,
+/// malformed `ibc/` and `factory/` denoms are classified as native
+pub fn classify_denom(denom: &str) -> DenomKind {
+    if let Some(hash) = denom.strip_prefix(IBC_DENOM_PREFIX) {
+        if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return DenomKind::Ibc {
+                hash: hash.to_uppercase(),
+            };
+        }
+    }
+
+    let parts: Vec<&str> = denom.splitn(3, '/').collect();
+    if let [TOKEN_FACTORY_DENOM_PREFIX, creator, subdenom] = parts[..] {
+        if !creator.is_empty() && !subdenom.is_empty() {
+            return DenomKind::TokenFactory {
+                creator: creator.to_string(),
+                subdenom: subdenom.to_string(),
+            };
+        }
+    }
+
+    DenomKind::Native
+}
+
+pub fn is_ibc_denom(denom: &str) -> bool {
+    matches!(classify_denom(denom), DenomKind::Ibc { .. })
+}
+
+pub fn is_token_factory_denom(denom: &str) -> bool {
+    matches!(classify_denom(denom), DenomKind::TokenFactory { .. })
 }
 
 #[cw_serde]
@@ -258,6 +358,12 @@ pub struct PairInfo {
     pub liquidity_token: String,
     pub asset_decimals: [u8; 2],
     pub requirements: CreatePairRequirements,
+    /// The human readable symbols of the assets, resolved by the pair queries of the factory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_symbols: Option<[String; 2]>,
+    /// The staking contract that distributes liquidity-mining rewards for the pair, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incentive_contract: Option<String>,
 }
 
 #[cw_serde]
@@ -267,12 +373,18 @@ pub struct PairInfoRaw {
     pub liquidity_token: CanonicalAddr,
     pub asset_decimals: [u8; 2],
     pub requirements: CreatePairRequirements,
+    /// The address which created the pair, recorded by the factory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<CanonicalAddr>,
+    /// The token factory denom of the liquidity shares, which replaces the liquidity token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquidity_denom: Option<String>,
 }
 
 impl PairInfoRaw {
     pub fn to_normal(&self, api: &dyn Api) -> StdResult<PairInfo> {
         Ok(PairInfo {
-            liquidity_token: api.addr_humanize(&self.liquidity_token)?.to_string(),
+            liquidity_token: self.liquidity_token(api)?,
             contract_addr: api.addr_humanize(&self.contract_addr)?.to_string(),
             asset_infos: [
                 self.asset_infos[0].to_normal(api)?,
@@ -280,9 +392,27 @@ impl PairInfoRaw {
             ],
             asset_decimals: self.asset_decimals,
             requirements: self.requirements.clone(),
+            asset_symbols: None,
+            incentive_contract: None,
         })
     }
 
+    /// The address of the liquidity token or the denom of the native liquidity shares
+    pub fn liquidity_token(&self, api: &dyn Api) -> StdResult<String> {
+        match &self.liquidity_denom {
+            Some(denom) => Ok(denom.to_string()),
+            None => Ok(api.addr_humanize(&self.liquidity_token)?.to_string()),
+        }
+    }
+
+    /// The key of the liquidity token, its canonical address or the denom of the native shares
+    pub fn liquidity_token_key(&self) -> Vec<u8> {
+        match &self.liquidity_denom {
+            Some(denom) => denom.as_bytes().to_vec(),
+            None => self.liquidity_token.to_vec(),
+        }
+    }
+
     pub fn query_pools(
         &self,
         querier: &QuerierWrapper,
@@ -1,6 +1,8 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use crate::asset::{AssetInfo, CreatePairRequirements, PairInfo};
+use crate::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo};
+use crate::pair::{LiquidityTokenKind, PauseFlags};
+use cosmwasm_std::{Binary, Decimal};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -17,21 +19,79 @@ pub enum ExecuteMsg {
         token_code_id: Option<u64>,
         pair_code_id: Option<u64>,
     },
-    /// CreatePair instantiates pair contract
+    /// UpdatePairCreationConfig replaces the restrictions on the pair creation
+    UpdatePairCreationConfig {
+        pair_creation: PairCreationConfig,
+    },
+    /// CreatePair instantiates pair contract,
+    /// the creation fee must be sent as funds or approved as a cw20 allowance
     CreatePair {
         /// Asset infos
         asset_infos: [AssetInfo; 2],
         /// The requiments to create a pair
         requirements: CreatePairRequirements,
+        /// The kind of the liquidity token of the pair, a cw20 token by default
+        liquidity_token_kind: Option<LiquidityTokenKind>,
+    },
+    /// CreatePairs instantiates several pair contracts,
+    /// the creation fee is charged for each pair
+    CreatePairs {
+        pairs: Vec<CreatePairRequest>,
     },
+    /// AddNativeTokenDecimals registers or updates the decimals of a native token,
+    /// `decimals` can be omitted when the denom has bank metadata
     AddNativeTokenDecimals {
         denom: String,
-        decimals: u8,
+        decimals: Option<u8>,
+    },
+    RemoveNativeTokenDecimals {
+        denom: String,
+    },
+    /// RegisterIbcDenom records the symbol and the channel of an ibc denom,
+    /// the symbol is shown in place of the hash in the pair queries
+    RegisterIbcDenom {
+        denom: String,
+        symbol: String,
+        channel: String,
+    },
+    DeregisterIbcDenom {
+        denom: String,
     },
     MigratePair {
         contract: String,
         code_id: Option<u64>,
     },
+    /// MigratePairs migrates a page of the registered pairs,
+    /// `msg` defaults to a pair `MigrateMsg` without parameters
+    MigratePairs {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+        code_id: Option<u64>,
+        msg: Option<Binary>,
+    },
+    /// UpdatePairConfig forwards the update to the pair and keeps the stored requirements in sync
+    UpdatePairConfig {
+        asset_infos: [AssetInfo; 2],
+        requirements: Option<CreatePairRequirements>,
+        commission_rate: Option<Decimal>,
+        pause: Option<PauseFlags>,
+    },
+    /// SetPairIncentive records the staking contract rewarding the liquidity providers of the pair,
+    /// `None` removes it
+    SetPairIncentive {
+        asset_infos: [AssetInfo; 2],
+        incentive_contract: Option<String>,
+    },
+    /// Deregister removes a pair from the registry and keeps it as deprecated
+    Deregister {
+        asset_infos: [AssetInfo; 2],
+    },
+    /// ReplacePair registers an existing pair contract in place of the current pair,
+    /// the factory must be the admin of the pair contract
+    ReplacePair {
+        asset_infos: [AssetInfo; 2],
+        pair_contract: String,
+    },
 }
 
 #[cw_serde]
@@ -46,8 +106,62 @@ pub enum QueryMsg {
         start_after: Option<[AssetInfo; 2]>,
         limit: Option<u32>,
     },
+    /// PairsByAsset returns the pairs trading the asset
+    #[returns(PairsResponse)]
+    PairsByAsset {
+        asset_info: AssetInfo,
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    #[returns(PairInfo)]
+    PairByLiquidityToken { liquidity_token: String },
+    #[returns(PairInfo)]
+    PairByContract { contract_addr: String },
+    /// PairsByCreator returns the pairs created through the factory by the creator
+    #[returns(PairsResponse)]
+    PairsByCreator {
+        creator: String,
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// NativeTokenDecimals reads the decimals from the bank denom metadata,
+    /// falling back to the registered decimals
     #[returns(NativeTokenDecimalsResponse)]
     NativeTokenDecimals { denom: String },
+    /// NativeTokens returns the registered native tokens
+    #[returns(NativeTokensResponse)]
+    NativeTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(IbcDenomInfo)]
+    IbcDenom { denom: String },
+    /// IbcDenoms returns the registered ibc denoms ordered by their hash
+    #[returns(IbcDenomsResponse)]
+    IbcDenoms {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// OutdatedPairs returns the pairs which are not known to run the code id among
+    /// the `limit` registered pairs after `start_after`,
+    /// `code_id` defaults to the pair code id of the config
+    #[returns(OutdatedPairsResponse)]
+    OutdatedPairs {
+        code_id: Option<u64>,
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// PredictPairAddresses returns the addresses of the pair and its liquidity token
+    /// before the pair of the asset infos is created
+    #[returns(PredictedPairAddressesResponse)]
+    PredictPairAddresses { asset_infos: [AssetInfo; 2] },
+    /// DeprecatedPairs returns the deregistered or replaced pairs of the asset infos
+    #[returns(PairsResponse)]
+    DeprecatedPairs {
+        asset_infos: [AssetInfo; 2],
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
@@ -56,11 +170,35 @@ pub struct ConfigResponse {
     pub owner: String,
     pub pair_code_id: u64,
     pub token_code_id: u64,
+    pub pair_creation: PairCreationConfig,
 }
 
-/// We currently take no arguments for migrations
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct CreatePairRequest {
+    pub asset_infos: [AssetInfo; 2],
+    pub requirements: CreatePairRequirements,
+    pub liquidity_token_kind: Option<LiquidityTokenKind>,
+}
+
+/// The restrictions applied when creating a pair
+#[cw_serde]
+#[derive(Default)]
+pub struct PairCreationConfig {
+    /// The fee paid by the creator of a pair, collected into `fee_collector`
+    pub creation_fee: Option<Asset>,
+    pub fee_collector: Option<String>,
+    /// Only the owner can create pairs
+    pub owner_only: bool,
+    /// When set, both assets of a pair must be listed
+    pub allowed_assets: Option<Vec<AssetInfo>>,
+    pub denied_assets: Vec<AssetInfo>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {
+    /// The code id run by the registered pairs, recorded when migrating from a version before 0.2.0
+    pub pair_code_id: Option<u64>,
+}
 
 // We define a custom struct for each query response
 #[cw_serde]
@@ -68,7 +206,44 @@ pub struct PairsResponse {
     pub pairs: Vec<PairInfo>,
 }
 
+#[cw_serde]
+pub struct OutdatedPairsResponse {
+    pub pairs: Vec<PairInfo>,
+    /// The last visited pair to continue the scan from, none once all the pairs are visited
+    pub start_after: Option<[AssetInfo; 2]>,
+}
+
+#[cw_serde]
+pub struct PredictedPairAddressesResponse {
+    pub pair_contract: String,
+    pub liquidity_token: String,
+}
+
 #[cw_serde]
 pub struct NativeTokenDecimalsResponse {
     pub decimals: u8,
 }
+
+#[cw_serde]
+pub struct NativeToken {
+    pub denom: String,
+    pub decimals: u8,
+}
+
+#[cw_serde]
+pub struct NativeTokensResponse {
+    pub native_tokens: Vec<NativeToken>,
+}
+
+#[cw_serde]
+pub struct IbcDenomInfo {
+    pub denom: String,
+    pub symbol: String,
+    /// The channel the denom was transferred over, `channel-{n}`
+    pub channel: String,
+}
+
+#[cw_serde]
+pub struct IbcDenomsResponse {
+    pub ibc_denoms: Vec<IbcDenomInfo>,
+}
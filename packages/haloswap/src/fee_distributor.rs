@@ -0,0 +1,109 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal256, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::AssetInfo;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The HALO token staked to share the fees
+    pub halo_token: String,
+    pub halo_router: String,
+    /// The asset the fees are converted into and paid in
+    pub reward_asset: AssetInfo,
+    /// The length of an epoch in seconds
+    pub epoch_length: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig {
+        owner: Option<String>,
+        halo_router: Option<String>,
+        epoch_length: Option<u64>,
+    },
+    /// UpdateBridge sets the assets a fee asset is swapped through before the reward asset,
+    /// `None` swaps it directly into the reward asset
+    UpdateBridge {
+        asset_info: AssetInfo,
+        bridge: Option<Vec<AssetInfo>>,
+    },
+    /// Convert swaps the collected balances of the fee assets into the reward asset,
+    /// only the owner can execute it
+    Convert {
+        assets: Vec<ConvertAsset>,
+    },
+    /// Unstake returns the staked HALO, the stake waiting for the next epoch is returned first
+    Unstake {
+        amount: Uint128,
+    },
+    /// Claim sends the rewards distributed to the staker
+    Claim {},
+}
+
+/// A fee asset to convert, the swap fails if it returns less than `minimum_receive` of the reward asset
+#[cw_serde]
+pub struct ConvertAsset {
+    pub info: AssetInfo,
+    pub minimum_receive: Uint128,
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Stake the HALO sent to the contract, the stake shares the fees from the next epoch
+    Stake {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(StateResponse)]
+    State {},
+    #[returns(BridgeResponse)]
+    Bridge { asset_info: AssetInfo },
+    /// Staker returns the stake and the rewards distributed until the current block time
+    #[returns(StakerResponse)]
+    Staker { address: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub halo_token: String,
+    pub halo_router: String,
+    pub reward_asset: AssetInfo,
+    pub epoch_length: u64,
+}
+
+#[cw_serde]
+pub struct StateResponse {
+    pub epoch: u64,
+    pub epoch_start_time: u64,
+    /// The stake sharing the fees of the current epoch
+    pub total_staked: Uint128,
+    /// The stake sharing the fees from the next epoch
+    pub next_epoch_staked: Uint128,
+    /// The rewards distributed per staked token
+    pub global_index: Decimal256,
+    /// The distributed rewards not claimed yet
+    pub unclaimed_rewards: Uint128,
+}
+
+#[cw_serde]
+pub struct BridgeResponse {
+    pub bridge: Vec<AssetInfo>,
+}
+
+#[cw_serde]
+pub struct StakerResponse {
+    pub address: String,
+    pub staked: Uint128,
+    pub next_epoch_staked: Uint128,
+    pub pending_rewards: Uint128,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
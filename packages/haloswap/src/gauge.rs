@@ -0,0 +1,107 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::asset::AssetInfo;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The governance staking contract providing the voting power
+    pub gov_staking: String,
+    /// The LP staking contract receiving the emissions, the gauge must be set in its config
+    pub lp_staking: String,
+    pub reward_asset: AssetInfo,
+    /// The most reward emitted at the end of an epoch, capped by the balance of the gauge
+    pub emission_per_epoch: Uint128,
+    pub epoch_length: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+        emission_per_epoch: Option<Uint128>,
+        epoch_length: Option<u64>,
+    },
+    /// Vote replaces the votes of the sender in the current epoch, the weights sum up to one at most
+    Vote { votes: Vec<GaugeVote> },
+    /// Finalize allocates the emission of the ended epoch to the pools by their weights
+    /// and starts the next epoch, anyone can execute it
+    Finalize {},
+}
+
+#[cw_serde]
+pub struct GaugeVote {
+    /// The liquidity token of the pool, the address of the CW20 token or the denom of the
+    /// native liquidity shares
+    pub lp_token: String,
+    /// The share of the voting power given to the pool
+    pub weight: Decimal,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Epoch returns the epoch open to the votes
+    #[returns(EpochResponse)]
+    Epoch {},
+    /// Vote returns the votes of the voter in an epoch, `epoch` defaults to the current epoch
+    #[returns(VoteResponse)]
+    Vote { voter: String, epoch: Option<u64> },
+    /// Gauges returns the weights of the pools in an epoch and the rewards allocated to them
+    /// once it is finalized, `epoch` defaults to the current epoch
+    #[returns(GaugesResponse)]
+    Gauges {
+        epoch: Option<u64>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub gov_staking: String,
+    pub lp_staking: String,
+    pub reward_asset: AssetInfo,
+    pub emission_per_epoch: Uint128,
+    pub epoch_length: u64,
+}
+
+#[cw_serde]
+pub struct EpochResponse {
+    pub epoch: u64,
+    pub start_time: u64,
+    /// The voting power is read at the beginning of this block
+    pub start_height: u64,
+    pub end_time: u64,
+}
+
+#[cw_serde]
+pub struct VoteResponse {
+    pub voter: String,
+    pub epoch: u64,
+    pub power: Uint128,
+    pub votes: Vec<GaugeVote>,
+}
+
+#[cw_serde]
+pub struct GaugeResponse {
+    pub lp_token: String,
+    pub weight: Uint128,
+    pub allocation: Uint128,
+}
+
+#[cw_serde]
+pub struct GaugesResponse {
+    pub epoch: u64,
+    pub total_weight: Uint128,
+    pub finalized: bool,
+    /// The reward allocated to the pools when the epoch was finalized
+    pub emission: Uint128,
+    pub gauges: Vec<GaugeResponse>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -0,0 +1,103 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub halo_token: String,
+    /// The seconds an unstaked amount waits before it can be claimed
+    pub unbonding_period: u64,
+    /// The longest lock in seconds, a lock of this length doubles the voting power of the stake
+    /// until it ends, zero disables the locks
+    pub max_lock_duration: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig {
+        owner: Option<String>,
+        unbonding_period: Option<u64>,
+        max_lock_duration: Option<u64>,
+    },
+    /// ExtendLock pushes the end of the lock of the sender to `lock_duration` from now
+    ExtendLock {
+        lock_duration: u64,
+    },
+    /// ExpireLock unlocks the stake of an ended lock, anyone can execute it
+    ExpireLock {
+        staker: String,
+    },
+    /// Unstake starts the unbonding of the unlocked stake
+    Unstake {
+        amount: Uint128,
+    },
+    /// Claim withdraws the unbonded stake
+    Claim {},
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Stake the HALO sent to the contract, locking it for `lock_duration` seconds if set
+    Stake { lock_duration: Option<u64> },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(StakerResponse)]
+    Staker { address: String },
+    /// VotingPowerAtHeight returns the voting power at the beginning of the block,
+    /// `height` defaults to the current block. The boost of a lock is only counted
+    /// if the lock has not ended at the time of the query
+    #[returns(VotingPowerAtHeightResponse)]
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+    /// TotalPowerAtHeight returns the total stake at the beginning of the block
+    /// plus the boosts of the locks not ended at the time of the query
+    #[returns(TotalPowerAtHeightResponse)]
+    TotalPowerAtHeight { height: Option<u64> },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub halo_token: String,
+    pub unbonding_period: u64,
+    pub max_lock_duration: u64,
+}
+
+#[cw_serde]
+pub struct Claim {
+    pub amount: Uint128,
+    pub release_at: u64,
+}
+
+#[cw_serde]
+pub struct StakerResponse {
+    pub address: String,
+    pub unlocked: Uint128,
+    pub locked: Uint128,
+    pub lock_end: u64,
+    pub voting_power: Uint128,
+    pub claims: Vec<Claim>,
+}
+
+#[cw_serde]
+pub struct VotingPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct TotalPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -0,0 +1,174 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Empty, Uint128};
+use cw20::Expiration;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The governance staking contract providing the voting power
+    pub gov_staking: String,
+    /// The share of the yes votes among the votes, abstentions left out, to pass a proposal
+    pub threshold: Decimal,
+    /// The share of the total power voting to make a proposal valid
+    pub quorum: Decimal,
+    /// The seconds a proposal is open to the votes
+    pub voting_period: u64,
+    /// The seconds a passed proposal waits after the end of the votes before it can be executed
+    pub timelock_period: u64,
+}
+
+/// The messages of a cw3 proposal contract, weighted by the voting power
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Propose opens the votes on the messages, the proposer votes yes with its power
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg<Empty>>,
+    },
+    Vote {
+        proposal_id: u64,
+        vote: Vote,
+    },
+    /// Execute runs the messages of a passed proposal once the timelock has ended,
+    /// anyone can execute it
+    Execute {
+        proposal_id: u64,
+    },
+    /// Close rejects a proposal failed at the end of the votes, anyone can execute it
+    Close {
+        proposal_id: u64,
+    },
+    /// UpdateConfig can only be executed through a proposal
+    UpdateConfig {
+        threshold: Option<Decimal>,
+        quorum: Option<Decimal>,
+        voting_period: Option<u64>,
+        timelock_period: Option<u64>,
+    },
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum Vote {
+    Yes,
+    No,
+    Abstain,
+    /// Veto is counted as a no vote
+    Veto,
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum Status {
+    Pending,
+    Open,
+    Rejected,
+    Passed,
+    Executed,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(ThresholdResponse)]
+    Threshold {},
+    #[returns(ProposalResponse)]
+    Proposal { proposal_id: u64 },
+    #[returns(ProposalListResponse)]
+    ListProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(ProposalListResponse)]
+    ReverseProposals {
+        start_before: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(VoteResponse)]
+    Vote { proposal_id: u64, voter: String },
+    #[returns(VoteListResponse)]
+    ListVotes {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Voter returns the current voting power of the address
+    #[returns(VoterResponse)]
+    Voter { address: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub gov_staking: String,
+    pub threshold: Decimal,
+    pub quorum: Decimal,
+    pub voting_period: u64,
+    pub timelock_period: u64,
+}
+
+#[cw_serde]
+pub enum ThresholdResponse {
+    ThresholdQuorum {
+        threshold: Decimal,
+        quorum: Decimal,
+        total_weight: Uint128,
+    },
+}
+
+#[cw_serde]
+pub struct Votes {
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+    pub veto: Uint128,
+}
+
+#[cw_serde]
+pub struct ProposalResponse {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub status: Status,
+    /// The end of the votes
+    pub expires: Expiration,
+    /// The threshold and the total power at the start of the votes
+    pub threshold: ThresholdResponse,
+    pub proposer: Addr,
+    pub votes: Votes,
+    /// The time a passed proposal can be executed from
+    pub executable_at: u64,
+}
+
+#[cw_serde]
+pub struct ProposalListResponse {
+    pub proposals: Vec<ProposalResponse>,
+}
+
+#[cw_serde]
+pub struct VoteInfo {
+    pub proposal_id: u64,
+    pub voter: String,
+    pub vote: Vote,
+    pub weight: Uint128,
+}
+
+#[cw_serde]
+pub struct VoteResponse {
+    pub vote: Option<VoteInfo>,
+}
+
+#[cw_serde]
+pub struct VoteListResponse {
+    pub votes: Vec<VoteInfo>,
+}
+
+#[cw_serde]
+pub struct VoterResponse {
+    pub weight: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
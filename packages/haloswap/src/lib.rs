@@ -1,9 +1,19 @@
+pub mod airdrop;
 pub mod asset;
 pub mod factory;
+pub mod fee_distributor;
+pub mod gauge;
+pub mod gov_staking;
+pub mod governance;
+pub mod migration;
 pub mod pair;
 pub mod querier;
 pub mod router;
+pub mod staking;
+pub mod timelock;
 pub mod token;
+pub mod token_factory;
+pub mod vesting;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod mock_querier;
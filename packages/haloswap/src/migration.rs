@@ -0,0 +1,34 @@
+use cosmwasm_std::{StdError, StdResult, Storage};
+use cw2::get_contract_version;
+
+pub use semver::Version;
+
+/// Assert the stored contract is the same contract in the same or an older version
+/// and return the stored version to pick the migration steps to run
+pub fn assert_migrate_version(
+    storage: &dyn Storage,
+    contract_name: &str,
+    contract_version: &str,
+) -> StdResult<Version> {
+    let stored = get_contract_version(storage)?;
+    if stored.contract != contract_name {
+        return Err(StdError::generic_err(format!(
+            "cannot migrate from a different contract: {}",
+            stored.contract
+        )));
+    }
+
+    let stored_version = parse_version(&stored.version)?;
+    if stored_version > parse_version(contract_version)? {
+        return Err(StdError::generic_err(format!(
+            "cannot migrate from a newer version: {}",
+            stored.version
+        )));
+    }
+
+    Ok(stored_version)
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    Version::parse(version).map_err(|e| StdError::generic_err(e.to_string()))
+}
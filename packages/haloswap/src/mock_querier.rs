@@ -1,7 +1,8 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Addr, Coin, ContractResult, Empty, OwnedDeps, Querier,
-    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_json, to_json_binary, Addr, CodeInfoResponse, Coin, ContractInfoResponse, ContractResult,
+    DenomMetadata, Empty, HexBinary, OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError,
+    SystemResult, Uint128, WasmQuery,
 };
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -35,6 +36,7 @@ pub struct WasmMockQuerier {
     base: MockQuerier,
     token_querier: TokenQuerier,
     halo_factory_querier: HaloFactoryQuerier,
+    contract_infos: HashMap<String, ContractInfoResponse>,
 }
 
 #[derive(Clone, Default)]
@@ -105,7 +107,7 @@ pub(crate) fn native_token_decimals_to_map(
 impl Querier for WasmMockQuerier {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         // MockQuerier doesn't support Custom, so we ignore it completely here
-        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+        let request: QueryRequest<Empty> = match from_json(bin_request) {
             Ok(v) => v,
             Err(e) => {
                 return SystemResult::Err(SystemError::InvalidRequest {
@@ -121,7 +123,33 @@ impl Querier for WasmMockQuerier {
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
-            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => match from_binary(msg) {
+            QueryRequest::Wasm(WasmQuery::CodeInfo { code_id }) => {
+                // the checksum is derived from the code id
+                let checksum = HexBinary::from(vec![*code_id as u8; 32]);
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&CodeInfoResponse::new(
+                        *code_id,
+                        "creator".to_string(),
+                        checksum,
+                    ))
+                    .unwrap(),
+                ))
+            }
+            QueryRequest::Wasm(WasmQuery::ContractInfo { contract_addr }) => {
+                if let Some(contract_info) = self.contract_infos.get(contract_addr) {
+                    return SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(contract_info).unwrap(),
+                    ));
+                }
+
+                // the contracts are administrated by the factory
+                let mut contract_info = ContractInfoResponse::default();
+                contract_info.code_id = 1u64;
+                contract_info.creator = "creator".to_string();
+                contract_info.admin = Some("factory".to_string());
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&contract_info).unwrap()))
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => match from_json(msg) {
                 Ok(FactoryQueryMsg::Pair { asset_infos }) => {
                     let key = [asset_infos[0].to_string(), asset_infos[1].to_string()].join("");
                     let mut sort_key: Vec<char> = key.chars().collect();
@@ -131,7 +159,21 @@ impl WasmMockQuerier {
                         .pairs
                         .get(&String::from_iter(sort_key.iter()))
                     {
-                        Some(v) => SystemResult::Ok(ContractResult::Ok(to_binary(v).unwrap())),
+                        Some(v) => SystemResult::Ok(ContractResult::Ok(to_json_binary(v).unwrap())),
+                        None => SystemResult::Err(SystemError::InvalidRequest {
+                            error: "No pair info exists".to_string(),
+                            request: msg.as_slice().into(),
+                        }),
+                    }
+                }
+                Ok(FactoryQueryMsg::PairByLiquidityToken { liquidity_token }) => {
+                    match self
+                        .halo_factory_querier
+                        .pairs
+                        .values()
+                        .find(|pair_info| pair_info.liquidity_token == liquidity_token)
+                    {
+                        Some(v) => SystemResult::Ok(ContractResult::Ok(to_json_binary(v).unwrap())),
                         None => SystemResult::Err(SystemError::InvalidRequest {
                             error: "No pair info exists".to_string(),
                             request: msg.as_slice().into(),
@@ -141,7 +183,7 @@ impl WasmMockQuerier {
                 Ok(FactoryQueryMsg::NativeTokenDecimals { denom }) => {
                     match self.halo_factory_querier.native_token_decimals.get(&denom) {
                         Some(decimals) => SystemResult::Ok(ContractResult::Ok(
-                            to_binary(&NativeTokenDecimalsResponse {
+                            to_json_binary(&NativeTokenDecimalsResponse {
                                 decimals: *decimals,
                             })
                             .unwrap(),
@@ -152,9 +194,21 @@ impl WasmMockQuerier {
                         }),
                     }
                 }
-                _ => match from_binary(msg) {
+                _ => match from_json(msg) {
                     Ok(PairQueryMsg::Pair {}) => {
-                        SystemResult::Ok(ContractResult::from(to_binary(&PairInfo {
+                        // pairs registered to the factory answer with their own pair info
+                        if let Some(pair_info) = self
+                            .halo_factory_querier
+                            .pairs
+                            .values()
+                            .find(|pair_info| &pair_info.contract_addr == contract_addr)
+                        {
+                            return SystemResult::Ok(ContractResult::from(to_json_binary(
+                                pair_info,
+                            )));
+                        }
+
+                        SystemResult::Ok(ContractResult::from(to_json_binary(&PairInfo {
                             asset_infos: [
                                 AssetInfo::NativeToken {
                                     denom: "uluna".to_string(),
@@ -164,6 +218,8 @@ impl WasmMockQuerier {
                                 },
                             ],
                             asset_decimals: [6u8, 6u8],
+                            asset_symbols: None,
+                            incentive_contract: None,
                             contract_addr: "pair0000".to_string(),
                             liquidity_token: "liquidity0000".to_string(),
                             requirements: CreatePairRequirements {
@@ -173,21 +229,21 @@ impl WasmMockQuerier {
                             },
                         })))
                     }
-                    Ok(PairQueryMsg::Simulation { offer_asset }) => {
-                        SystemResult::Ok(ContractResult::from(to_binary(&SimulationResponse {
+                    Ok(PairQueryMsg::Simulation { offer_asset }) => SystemResult::Ok(
+                        ContractResult::from(to_json_binary(&SimulationResponse {
                             return_amount: offer_asset.amount,
                             commission_amount: Uint128::zero(),
                             spread_amount: Uint128::zero(),
-                        })))
-                    }
+                        })),
+                    ),
                     Ok(PairQueryMsg::ReverseSimulation { ask_asset }) => SystemResult::Ok(
-                        ContractResult::from(to_binary(&ReverseSimulationResponse {
+                        ContractResult::from(to_json_binary(&ReverseSimulationResponse {
                             offer_amount: ask_asset.amount,
                             commission_amount: Uint128::zero(),
                             spread_amount: Uint128::zero(),
                         })),
                     ),
-                    _ => match from_binary(msg).unwrap() {
+                    _ => match from_json(msg).unwrap() {
                         Cw20QueryMsg::TokenInfo {} => {
                             let balances: &HashMap<String, Uint128> =
                                 match self.token_querier.balances.get(contract_addr) {
@@ -210,7 +266,7 @@ impl WasmMockQuerier {
                             }
 
                             SystemResult::Ok(ContractResult::Ok(
-                                to_binary(&TokenInfoResponse {
+                                to_json_binary(&TokenInfoResponse {
                                     name: "mAAPL".to_string(),
                                     symbol: "mAAPL".to_string(),
                                     decimals: 8,
@@ -238,7 +294,7 @@ impl WasmMockQuerier {
                                 Some(v) => *v,
                                 None => {
                                     return SystemResult::Ok(ContractResult::Ok(
-                                        to_binary(&Cw20BalanceResponse {
+                                        to_json_binary(&Cw20BalanceResponse {
                                             balance: Uint128::zero(),
                                         })
                                         .unwrap(),
@@ -247,7 +303,7 @@ impl WasmMockQuerier {
                             };
 
                             SystemResult::Ok(ContractResult::Ok(
-                                to_binary(&Cw20BalanceResponse { balance }).unwrap(),
+                                to_json_binary(&Cw20BalanceResponse { balance }).unwrap(),
                             ))
                         }
 
@@ -266,6 +322,7 @@ impl WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
             halo_factory_querier: HaloFactoryQuerier::default(),
+            contract_infos: HashMap::new(),
         }
     }
 
@@ -283,6 +340,20 @@ impl WasmMockQuerier {
         self.halo_factory_querier = HaloFactoryQuerier::new(pairs, native_token_decimals);
     }
 
+    // configure the code id and the admin of a contract
+    pub fn with_contract_info(&mut self, contract_addr: &str, code_id: u64, admin: Option<&str>) {
+        let mut contract_info = ContractInfoResponse::default();
+        contract_info.code_id = code_id;
+        contract_info.creator = "creator".to_string();
+        contract_info.admin = admin.map(|admin| admin.to_string());
+        self.contract_infos
+            .insert(contract_addr.to_string(), contract_info);
+    }
+
+    pub fn with_denom_metadata(&mut self, denom_metadata: &[DenomMetadata]) {
+        self.base.set_denom_metadata(denom_metadata);
+    }
+
     pub fn with_balance(&mut self, balances: &[(&String, Vec<Coin>)]) {
         for (addr, balance) in balances {
             self.base.update_balance(addr.to_string(), balance.clone());
@@ -312,7 +383,7 @@ mod mock_exception {
     fn none_factory_pair_will_err() {
         let deps = mock_dependencies(&[]);
 
-        let msg = to_binary(&FactoryQueryMsg::Pair {
+        let msg = to_json_binary(&FactoryQueryMsg::Pair {
             asset_infos: [
                 AssetInfo::NativeToken {
                     denom: "uluna".to_string(),
@@ -340,7 +411,7 @@ mod mock_exception {
     fn none_tokens_info_will_err() {
         let deps = mock_dependencies(&[]);
 
-        let msg = to_binary(&Cw20QueryMsg::TokenInfo {}).unwrap();
+        let msg = to_json_binary(&Cw20QueryMsg::TokenInfo {}).unwrap();
 
         assert_eq!(
             deps.querier
@@ -359,7 +430,7 @@ mod mock_exception {
     fn none_tokens_balance_will_err() {
         let deps = mock_dependencies(&[]);
 
-        let msg = to_binary(&Cw20QueryMsg::Balance {
+        let msg = to_json_binary(&Cw20QueryMsg::Balance {
             address: "address0000".to_string(),
         })
         .unwrap();
@@ -382,7 +453,7 @@ mod mock_exception {
     fn none_tokens_minter_will_panic() {
         let deps = mock_dependencies(&[]);
 
-        let msg = to_binary(&Cw20QueryMsg::Minter {}).unwrap();
+        let msg = to_json_binary(&Cw20QueryMsg::Minter {}).unwrap();
 
         deps.querier
             .handle_query(&QueryRequest::Wasm(WasmQuery::Smart {
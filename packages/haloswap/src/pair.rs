@@ -1,10 +1,17 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use crate::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo};
+use crate::asset::{Asset, AssetInfo, CreatePairRequirements, PairInfo, SwapCallback};
+use crate::token::{Permit, PermitInfoResponse};
 
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Binary, Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 
+/// The instantiate2 salt of the liquidity token, unique as the pair is the creator
+pub const LIQUIDITY_TOKEN_SALT: &[u8] = b"liquidity_token";
+
+/// The subdenom of the native liquidity shares of a pair, `factory/{pair}/uLP`
+pub const LIQUIDITY_SUBDENOM: &str = "uLP";
+
 #[cw_serde]
 pub struct InstantiateMsg {
     /// Asset infos
@@ -14,6 +21,19 @@ pub struct InstantiateMsg {
     pub asset_decimals: [u8; 2],
     /// The requiments to the first time provide liquidity
     pub requirements: CreatePairRequirements,
+    /// The kind of the liquidity token, a cw20 token by default
+    pub liquidity_token_kind: Option<LiquidityTokenKind>,
+}
+
+/// The liquidity shares are minted as a cw20 token or as a token factory denom
+#[cw_serde]
+#[derive(Default)]
+pub enum LiquidityTokenKind {
+    /// A cw20 token contract instantiated by the pair
+    #[default]
+    Cw20,
+    /// The token factory denom `factory/{pair}/uLP` created by the pair
+    Native,
 }
 
 #[cw_serde]
@@ -25,6 +45,22 @@ pub enum ExecuteMsg {
         slippage_tolerance: Option<Decimal>,
         receiver: Option<String>,
     },
+    /// ProvideLiquidityWithPermits provides the deposit signed by the owner in the liquidity permit,
+    /// the token permits of the owner set the allowances of its tokens to the pair in the same
+    /// transaction. Anyone can relay them, the native assets of the deposit are sent by the relayer
+    ProvideLiquidityWithPermits {
+        liquidity_permit: LiquidityPermit,
+        permits: Vec<Permit>,
+    },
+    /// UpdatePairConfig updates the parameters of the pair, only the factory can execute it
+    UpdatePairConfig {
+        requirements: Option<CreatePairRequirements>,
+        commission_rate: Option<Decimal>,
+        pause: Option<PauseFlags>,
+    },
+    /// WithdrawLiquidity burns the native liquidity shares sent with the message and refunds the assets,
+    /// the cw20 liquidity tokens are withdrawn with the WithdrawLiquidity hook
+    WithdrawLiquidity {},
     // /// Swap an offer asset to the other
     // Swap {
     //     offer_asset: Asset,
@@ -43,6 +79,8 @@ pub enum Cw20HookMsg {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        /// Deliver the return asset to a contract together with a message instead of `to`
+        callback: Option<SwapCallback>,
     },
     WithdrawLiquidity {},
 }
@@ -52,15 +90,64 @@ pub enum Cw20HookMsg {
 pub enum QueryMsg {
     #[returns(PairInfo)]
     Pair {},
+    #[returns(ConfigResponse)]
+    Config {},
     #[returns(PoolResponse)]
     Pool {},
     #[returns(SimulationResponse)]
     Simulation { offer_asset: Asset },
     #[returns(ReverseSimulationResponse)]
     ReverseSimulation { ask_asset: Asset },
+    /// PermitInfo returns the nonce of the next liquidity permit of the owner
+    #[returns(PermitInfoResponse)]
+    PermitInfo { owner: String },
+}
+
+/// A deposit of liquidity signed off-chain by the owner of the assets
+#[cw_serde]
+pub struct LiquidityPermit {
+    pub params: LiquidityPermitParams,
+    /// The compressed secp256k1 public key of the owner, the owner address must derive from it
+    pub pubkey: Binary,
+    /// The secp256k1 signature of the sha256 hash of the json of the params, 64 bytes
+    pub signature: Binary,
+}
+
+#[cw_serde]
+pub struct LiquidityPermitParams {
+    /// The chain and the pair the permit is valid on
+    pub chain_id: String,
+    pub pair: String,
+    pub owner: String,
+    /// The deposited assets, the tokens are transferred from the owner
+    pub assets: [Asset; 2],
+    /// The least liquidity minted for the deposit
+    pub min_share: Uint128,
+    /// The receiver of the liquidity, the owner when not set
+    pub receiver: Option<String>,
+    /// The liquidity permits of an owner are used once each, in the order of their nonces from 0
+    pub nonce: u64,
+    /// The time in seconds from which the permit cannot be used
+    pub expires: u64,
+}
+
+/// The operations of the pair which are suspended,
+/// liquidity can always be withdrawn
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseFlags {
+    pub swap: bool,
+    pub provide_liquidity: bool,
 }
 
 // We define a custom struct for each query response
+#[cw_serde]
+pub struct ConfigResponse {
+    pub factory: String,
+    pub commission_rate: Decimal,
+    pub pause: PauseFlags,
+}
+
 #[cw_serde]
 pub struct PoolResponse {
     pub assets: [Asset; 2],
@@ -83,6 +170,40 @@ pub struct ReverseSimulationResponse {
     pub commission_amount: Uint128,
 }
 
-/// We currently take no arguments for migrations
+/// SwapResponseData is set as the response data of a swap
+#[cw_serde]
+pub struct SwapResponseData {
+    pub offer_asset: AssetInfo,
+    pub ask_asset: AssetInfo,
+    pub offer_amount: Uint128,
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+    pub receiver: String,
+}
+
+/// ProvideLiquidityResponseData is set as the response data of a liquidity provision
+#[cw_serde]
+pub struct ProvideLiquidityResponseData {
+    pub assets: [Asset; 2],
+    pub share: Uint128,
+    pub receiver: String,
+}
+
+/// WithdrawLiquidityResponseData is set as the response data of a liquidity withdrawal
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct WithdrawLiquidityResponseData {
+    pub withdrawn_share: Uint128,
+    pub refund_assets: [Asset; 2],
+    pub receiver: String,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {
+    /// The factory recorded when migrating from a version before 0.3.0,
+    /// defaults to the admin of the pair
+    pub factory: Option<String>,
+    /// The commission rate set when migrating from a version before 0.3.0, defaults to 0.3%
+    pub commission_rate: Option<Decimal>,
+}
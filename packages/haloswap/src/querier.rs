@@ -1,9 +1,13 @@
 use crate::asset::{Asset, AssetInfo, PairInfo};
 use crate::factory::{NativeTokenDecimalsResponse, QueryMsg as FactoryQueryMsg};
+use crate::gov_staking::{
+    QueryMsg as GovStakingQueryMsg, TotalPowerAtHeightResponse, VotingPowerAtHeightResponse,
+};
 use crate::pair::{QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse};
+use crate::staking::{PoolResponse, QueryMsg as StakingQueryMsg};
 
 use cosmwasm_std::{
-    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, QuerierWrapper,
+    to_json_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, QuerierWrapper,
     QueryRequest, StdResult, Uint128, WasmQuery,
 };
 
@@ -38,7 +42,7 @@ pub fn query_token_balance(
 ) -> StdResult<Uint128> {
     let res: Cw20BalanceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: contract_addr.to_string(),
-        msg: to_binary(&Cw20QueryMsg::Balance {
+        msg: to_json_binary(&Cw20QueryMsg::Balance {
             address: account_addr.to_string(),
         })?,
     }))?;
@@ -53,7 +57,7 @@ pub fn query_token_info(
 ) -> StdResult<TokenInfoResponse> {
     let token_info: TokenInfoResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: contract_addr.to_string(),
-        msg: to_binary(&Cw20QueryMsg::TokenInfo {})?,
+        msg: to_json_binary(&Cw20QueryMsg::TokenInfo {})?,
     }))?;
 
     Ok(token_info)
@@ -67,7 +71,7 @@ pub fn query_native_decimals(
     let res: NativeTokenDecimalsResponse =
         querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: factory_contract.to_string(),
-            msg: to_binary(&FactoryQueryMsg::NativeTokenDecimals { denom })?,
+            msg: to_json_binary(&FactoryQueryMsg::NativeTokenDecimals { denom })?,
         }))?;
     Ok(res.decimals)
 }
@@ -79,12 +83,25 @@ pub fn query_pair_info(
 ) -> StdResult<PairInfo> {
     querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: factory_contract.to_string(),
-        msg: to_binary(&FactoryQueryMsg::Pair {
+        msg: to_json_binary(&FactoryQueryMsg::Pair {
             asset_infos: asset_infos.clone(),
         })?,
     }))
 }
 
+pub fn query_pair_info_by_liquidity_token(
+    querier: &QuerierWrapper,
+    factory_contract: Addr,
+    liquidity_token: &str,
+) -> StdResult<PairInfo> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: factory_contract.to_string(),
+        msg: to_json_binary(&FactoryQueryMsg::PairByLiquidityToken {
+            liquidity_token: liquidity_token.to_string(),
+        })?,
+    }))
+}
+
 pub fn simulate(
     querier: &QuerierWrapper,
     pair_contract: Addr,
@@ -92,7 +109,7 @@ pub fn simulate(
 ) -> StdResult<SimulationResponse> {
     querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: pair_contract.to_string(),
-        msg: to_binary(&PairQueryMsg::Simulation {
+        msg: to_json_binary(&PairQueryMsg::Simulation {
             offer_asset: offer_asset.clone(),
         })?,
     }))
@@ -105,7 +122,7 @@ pub fn reverse_simulate(
 ) -> StdResult<ReverseSimulationResponse> {
     querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: pair_contract.to_string(),
-        msg: to_binary(&PairQueryMsg::ReverseSimulation {
+        msg: to_json_binary(&PairQueryMsg::ReverseSimulation {
             ask_asset: ask_asset.clone(),
         })?,
     }))
@@ -117,8 +134,54 @@ pub fn query_pair_info_from_pair(
 ) -> StdResult<PairInfo> {
     let pair_info: PairInfo = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: pair_contract.to_string(),
-        msg: to_binary(&PairQueryMsg::Pair {})?,
+        msg: to_json_binary(&PairQueryMsg::Pair {})?,
     }))?;
 
     Ok(pair_info)
 }
+
+pub fn query_voting_power_at_height(
+    querier: &QuerierWrapper,
+    gov_staking: Addr,
+    address: &Addr,
+    height: u64,
+) -> StdResult<Uint128> {
+    let res: VotingPowerAtHeightResponse =
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: gov_staking.to_string(),
+            msg: to_json_binary(&GovStakingQueryMsg::VotingPowerAtHeight {
+                address: address.to_string(),
+                height: Some(height),
+            })?,
+        }))?;
+
+    Ok(res.power)
+}
+
+pub fn query_total_power_at_height(
+    querier: &QuerierWrapper,
+    gov_staking: Addr,
+    height: u64,
+) -> StdResult<Uint128> {
+    let res: TotalPowerAtHeightResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: gov_staking.to_string(),
+        msg: to_json_binary(&GovStakingQueryMsg::TotalPowerAtHeight {
+            height: Some(height),
+        })?,
+    }))?;
+
+    Ok(res.power)
+}
+
+pub fn query_staking_pool(
+    querier: &QuerierWrapper,
+    lp_staking: Addr,
+    lp_token: &str,
+) -> StdResult<PoolResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: lp_staking.to_string(),
+        msg: to_json_binary(&StakingQueryMsg::Pool {
+            lp_token: lp_token.to_string(),
+        })?,
+    }))
+}
@@ -1,12 +1,14 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 
-use crate::asset::AssetInfo;
+use crate::asset::{Asset, AssetInfo, SwapCallback};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub halo_factory: String,
+    /// The maximum commission a referral can charge on a swap
+    pub max_referral_commission: Decimal,
 }
 
 #[cw_serde]
@@ -18,6 +20,14 @@ pub enum SwapOperation {
 }
 
 impl SwapOperation {
+    pub fn get_offer_asset_info(&self) -> AssetInfo {
+        match self {
+            SwapOperation::HaloSwap {
+                offer_asset_info, ..
+            } => offer_asset_info.clone(),
+        }
+    }
+
     pub fn get_target_asset_info(&self) -> AssetInfo {
         match self {
             SwapOperation::HaloSwap { ask_asset_info, .. } => ask_asset_info.clone(),
@@ -33,21 +43,12 @@ pub enum ExecuteMsg {
         operations: Vec<SwapOperation>,
         minimum_receive: Option<Uint128>,
         to: Option<String>,
-    },
-
-    /// Internal use
-    /// Swap all offer tokens to ask token
-    ExecuteSwapOperation {
-        operation: SwapOperation,
-        to: Option<String>,
-    },
-    /// Internal use
-    /// Check the swap amount is exceed minimum_receive
-    AssertMinimumReceive {
-        asset_info: AssetInfo,
-        prev_balance: Uint128,
-        minimum_receive: Uint128,
-        receiver: String,
+        /// The address receiving the referral commission
+        referral_address: Option<String>,
+        /// The commission rate deducted from the offer amount before the first hop
+        referral_commission: Option<Decimal>,
+        /// Deliver the return asset to a contract together with a message instead of `to`
+        callback: Option<SwapCallback>,
     },
 }
 
@@ -57,6 +58,12 @@ pub enum Cw20HookMsg {
         operations: Vec<SwapOperation>,
         minimum_receive: Option<Uint128>,
         to: Option<String>,
+        /// The address receiving the referral commission
+        referral_address: Option<String>,
+        /// The commission rate deducted from the offer amount before the first hop
+        referral_commission: Option<Decimal>,
+        /// Deliver the return asset to a contract together with a message instead of `to`
+        callback: Option<SwapCallback>,
     },
 }
 
@@ -69,11 +76,13 @@ pub enum QueryMsg {
     SimulateSwapOperations {
         offer_amount: Uint128,
         operations: Vec<SwapOperation>,
+        referral_commission: Option<Decimal>,
     },
     #[returns(SimulateSwapOperationsResponse)]
     ReverseSimulateSwapOperations {
         ask_amount: Uint128,
         operations: Vec<SwapOperation>,
+        referral_commission: Option<Decimal>,
     },
 }
 
@@ -81,6 +90,7 @@ pub enum QueryMsg {
 #[cw_serde]
 pub struct ConfigResponse {
     pub halo_factory: String,
+    pub max_referral_commission: Decimal,
 }
 
 // We define a custom struct for each query response
@@ -89,6 +99,16 @@ pub struct SimulateSwapOperationsResponse {
     pub amount: Uint128,
 }
 
-/// We currently take no arguments for migrations
+/// ExecuteSwapOperationsResponse is set as the response data of `ExecuteSwapOperations`
+#[cw_serde]
+pub struct ExecuteSwapOperationsResponse {
+    pub offer_asset: Asset,
+    pub return_asset: Asset,
+    pub receiver: String,
+}
+
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// Required to migrate from a version before 0.2.0 without referral commissions
+    pub max_referral_commission: Option<Decimal>,
+}
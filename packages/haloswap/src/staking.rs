@@ -0,0 +1,128 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal256, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::{Asset, AssetInfo};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub halo_factory: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// UpdateConfig sets the owner and the gauge allowed to add schedules,
+    /// an empty gauge removes it
+    UpdateConfig {
+        owner: Option<String>,
+        gauge: Option<String>,
+    },
+    /// RegisterPool opens the staking of the liquidity token of a pair registered to the factory,
+    /// the cw20 address of the token or the denom of the native liquidity shares
+    RegisterPool {
+        lp_token: String,
+    },
+    /// Bond the native liquidity shares sent as funds
+    Bond {},
+    /// AddSchedule emits a native reward, sent as funds, linearly from `start_time` to `end_time`
+    AddSchedule {
+        lp_token: String,
+        reward: Asset,
+        start_time: u64,
+        end_time: u64,
+    },
+    Unbond {
+        lp_token: String,
+        amount: Uint128,
+    },
+    /// Claim sends the pending rewards of the staker
+    Claim {
+        lp_token: String,
+    },
+    /// EmergencyUnbond returns the whole bond, the pending rewards are forfeited to the stakers
+    /// left in the pool
+    EmergencyUnbond {
+        lp_token: String,
+    },
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Bond the cw20 liquidity tokens sent to the contract
+    Bond {},
+    /// AddSchedule emits the sent cw20 reward linearly from `start_time` to `end_time`
+    AddSchedule {
+        lp_token: String,
+        start_time: u64,
+        end_time: u64,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(PoolResponse)]
+    Pool { lp_token: String },
+    #[returns(PoolsResponse)]
+    Pools {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Staker returns the bond and the rewards pending at the current block time
+    #[returns(StakerResponse)]
+    Staker { lp_token: String, staker: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub halo_factory: String,
+    pub gauge: Option<String>,
+}
+
+/// An amount of reward released linearly over the period
+#[cw_serde]
+pub struct Schedule {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct PoolRewardResponse {
+    pub info: AssetInfo,
+    /// The reward distributed per bonded token since the pool was registered
+    pub global_index: Decimal256,
+    pub schedules: Vec<Schedule>,
+    /// The reward released while nothing was bonded or forfeited by an emergency unbond,
+    /// distributed along the next release
+    pub undistributed: Uint128,
+}
+
+#[cw_serde]
+pub struct PoolResponse {
+    pub lp_token: String,
+    pub pair_contract: String,
+    pub total_bond_amount: Uint128,
+    pub last_updated: u64,
+    pub rewards: Vec<PoolRewardResponse>,
+}
+
+#[cw_serde]
+pub struct PoolsResponse {
+    pub pools: Vec<PoolResponse>,
+}
+
+#[cw_serde]
+pub struct StakerResponse {
+    pub lp_token: String,
+    pub staker: String,
+    pub bond_amount: Uint128,
+    pub pending_rewards: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -1,13 +1,17 @@
-use crate::asset::{Asset, AssetInfo, AssetInfoRaw, AssetRaw, CreatePairRequirements, PairInfo};
+use crate::asset::{
+    classify_denom, is_ibc_denom, is_token_factory_denom, Asset, AssetInfo, AssetInfoRaw, AssetRaw,
+    CreatePairRequirements, DenomKind, PairInfo,
+};
 use crate::mock_querier::mock_dependencies;
 use crate::querier::{
     query_all_balances, query_balance, query_pair_info, query_token_balance, query_token_info,
 };
+use crate::token_factory::{burn_msg, create_denom_msg, mint_msg, token_factory_denom};
 
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
 use cosmwasm_std::{
-    coin, to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, MessageInfo, StdError, SubMsg, Uint128,
-    WasmMsg,
+    coin, to_json_binary, Addr, Api, BankMsg, Coin, CosmosMsg, MessageInfo, StdError, SubMsg,
+    Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
@@ -157,6 +161,79 @@ fn test_asset_info() {
     );
 }
 
+#[test]
+fn test_classify_denom() {
+    let hash = "27394fb092d2eccd56123c74f36e4c1f926001ceada9ca97ea622b25f41e5eb2";
+
+    assert_eq!(classify_denom("uusd"), DenomKind::Native);
+    assert_eq!(
+        classify_denom(&format!("ibc/{}", hash)),
+        DenomKind::Ibc {
+            hash: hash.to_uppercase(),
+        }
+    );
+    assert_eq!(
+        classify_denom("factory/creator0000/sub/denom"),
+        DenomKind::TokenFactory {
+            creator: "creator0000".to_string(),
+            subdenom: "sub/denom".to_string(),
+        }
+    );
+
+    // malformed denoms are not classified
+    assert_eq!(classify_denom("ibc/HASH"), DenomKind::Native);
+    assert_eq!(classify_denom("factory/creator0000"), DenomKind::Native);
+    assert_eq!(classify_denom("factory//uhalo"), DenomKind::Native);
+
+    assert!(is_ibc_denom(&format!("ibc/{}", hash)));
+    assert!(!is_ibc_denom("uusd"));
+    assert!(is_token_factory_denom("factory/creator0000/uhalo"));
+    assert!(!is_token_factory_denom("uusd"));
+
+    assert_eq!(
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        }
+        .denom_kind(),
+        None
+    );
+}
+
+#[test]
+fn test_asset_info_symbol() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_token_balances(&[(&"asset0000".to_string(), &[])]);
+    let querier = &deps.as_ref().querier;
+
+    let symbol = |asset_info: AssetInfo| asset_info.query_symbol(querier).unwrap();
+    assert_eq!(
+        symbol(AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        }),
+        "uusd"
+    );
+    assert_eq!(
+        symbol(AssetInfo::NativeToken {
+            denom: "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+                .to_string(),
+        }),
+        "ibc/27394F"
+    );
+    assert_eq!(
+        symbol(AssetInfo::NativeToken {
+            denom: "factory/creator0000/uhalo".to_string(),
+        }),
+        "uhalo"
+    );
+    assert_eq!(
+        symbol(AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        }),
+        "mAAPL"
+    );
+}
+
 #[test]
 fn test_asset() {
     let mut deps = mock_dependencies(&[Coin {
@@ -195,7 +272,7 @@ fn test_asset() {
             .unwrap(),
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "asset0000".to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: "addr0000".to_string(),
                 amount: Uint128::from(123123u128),
             })
@@ -210,7 +287,7 @@ fn test_asset() {
             .unwrap(),
         SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "asset0000".to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: "addr0000".to_string(),
                 amount: Uint128::from(123123u128),
             })
@@ -394,6 +471,8 @@ fn query_halo_pair_contract() {
                 contract_addr: "pair0000".to_string(),
                 liquidity_token: "liquidity0000".to_string(),
                 asset_decimals: [6u8, 6u8],
+                asset_symbols: None,
+                incentive_contract: None,
                 requirements: CreatePairRequirements {
                     whitelist: vec![Addr::unchecked("deployer")],
                     first_asset_minimum: Uint128::zero(),
@@ -421,3 +500,55 @@ fn query_halo_pair_contract() {
     assert_eq!(pair_info.contract_addr, Addr::unchecked("pair0000"),);
     assert_eq!(pair_info.liquidity_token, Addr::unchecked("liquidity0000"),);
 }
+
+#[test]
+fn test_token_factory_msgs() {
+    let denom = token_factory_denom("pair0000", "uLP");
+    assert_eq!(denom, "factory/pair0000/uLP");
+    assert!(is_token_factory_denom(&denom));
+
+    assert_eq!(
+        create_denom_msg("pair0000", "uLP"),
+        CosmosMsg::Stargate {
+            type_url: "/osmosis.tokenfactory.v1beta1.MsgCreateDenom".to_string(),
+            value: [&[10u8, 8][..], b"pair0000", &[18, 3], b"uLP"]
+                .concat()
+                .into(),
+        }
+    );
+
+    // the coins are embedded messages with the amount encoded as a string
+    let encoded_coin = [&[10u8, 20][..], denom.as_bytes(), &[18, 3], b"100"].concat();
+    assert_eq!(
+        mint_msg("pair0000", &coin(100, &denom), "addr0000"),
+        CosmosMsg::Stargate {
+            type_url: "/osmosis.tokenfactory.v1beta1.MsgMint".to_string(),
+            value: [
+                &[10u8, 8][..],
+                b"pair0000",
+                &[18, encoded_coin.len() as u8],
+                &encoded_coin,
+                &[26, 8],
+                b"addr0000",
+            ]
+            .concat()
+            .into(),
+        }
+    );
+    assert_eq!(
+        burn_msg("pair0000", &coin(100, &denom), "pair0000"),
+        CosmosMsg::Stargate {
+            type_url: "/osmosis.tokenfactory.v1beta1.MsgBurn".to_string(),
+            value: [
+                &[10u8, 8][..],
+                b"pair0000",
+                &[18, encoded_coin.len() as u8],
+                &encoded_coin,
+                &[26, 8],
+                b"pair0000",
+            ]
+            .concat()
+            .into(),
+        }
+    );
+}
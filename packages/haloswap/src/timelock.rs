@@ -0,0 +1,85 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{CosmosMsg, Empty};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The address scheduling the operations, defaults to the sender
+    pub admin: Option<String>,
+    /// The address able to cancel the scheduled operations
+    pub guardian: String,
+    /// The seconds an operation waits at least before it can be executed
+    pub min_delay: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Schedule queues the messages to be executed after `delay` seconds,
+    /// `delay` defaults to the minimum delay
+    Schedule {
+        msgs: Vec<CosmosMsg<Empty>>,
+        description: String,
+        delay: Option<u64>,
+    },
+    /// Cancel drops a pending operation, only the guardian can execute it
+    Cancel { operation_id: u64 },
+    /// Execute runs a ready operation, anyone can execute it
+    Execute { operation_id: u64 },
+    /// UpdateConfig can only be executed through a scheduled operation
+    UpdateConfig {
+        admin: Option<String>,
+        guardian: Option<String>,
+        min_delay: Option<u64>,
+    },
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum OperationStatus {
+    /// Waiting for the delay to pass
+    Pending,
+    /// The delay has passed, the operation can be executed
+    Ready,
+    Executed,
+    Cancelled,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(OperationResponse)]
+    Operation { operation_id: u64 },
+    /// Operations returns the operations from the latest scheduled
+    #[returns(OperationsResponse)]
+    Operations {
+        start_before: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub admin: String,
+    pub guardian: String,
+    pub min_delay: u64,
+}
+
+#[cw_serde]
+pub struct OperationResponse {
+    pub id: u64,
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub description: String,
+    pub status: OperationStatus,
+    pub scheduled_at: u64,
+    /// The time the operation can be executed from
+    pub ready_at: u64,
+}
+
+#[cw_serde]
+pub struct OperationsResponse {
+    pub operations: Vec<OperationResponse>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
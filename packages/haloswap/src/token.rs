@@ -1,7 +1,15 @@
-use cosmwasm_schema::cw_serde;
+use bech32::FromBase32;
+use cosmwasm_schema::serde::Serialize;
+use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use cosmwasm_std::{StdError, StdResult, Uint128};
-use cw20::{Cw20Coin, MinterResponse};
+use cosmwasm_std::{to_json_vec, Api, Binary, StdError, StdResult, Uint128};
+use cw20::{
+    AllAccountsResponse, AllAllowancesResponse, AllSpenderAllowancesResponse, AllowanceResponse,
+    BalanceResponse, Cw20Coin, DownloadLogoResponse, Expiration, Logo, MarketingInfoResponse,
+    MinterResponse, TokenInfoResponse,
+};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
 
 /// TokenContract InstantiateMsg
 #[cw_serde]
@@ -11,6 +19,145 @@ pub struct InstantiateMsg {
     pub decimals: u8,
     pub initial_balances: Vec<Cw20Coin>,
     pub mint: Option<MinterResponse>,
+    /// The project, description, marketing address and logo shown by the wallets
+    pub marketing: Option<InstantiateMarketingInfo>,
+}
+
+/// Marketing info of the token, same as the one of cw20_base
+#[cw_serde]
+pub struct InstantiateMarketingInfo {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    /// The address allowed to update the marketing info and the logo
+    pub marketing: Option<String>,
+    pub logo: Option<Logo>,
+}
+
+/// The cw20 messages of halo-token along its permits
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Transfer moves tokens to another account without triggering actions
+    Transfer { recipient: String, amount: Uint128 },
+    /// Burn destroys tokens forever
+    Burn { amount: Uint128 },
+    /// Send transfers tokens to a contract and triggers an action on the receiving contract
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// IncreaseAllowance lets the spender move an additional amount of tokens of the sender,
+    /// `expires` overwrites the expiration of the allowance if set
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// DecreaseAllowance lowers the allowance of the spender on the tokens of the sender,
+    /// `expires` overwrites the expiration of the allowance if set
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// TransferFrom moves tokens of the owner to the recipient within the allowance of the sender
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// SendFrom sends tokens of the owner to a contract within the allowance of the sender
+    SendFrom {
+        owner: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// BurnFrom destroys tokens of the owner within the allowance of the sender
+    BurnFrom { owner: String, amount: Uint128 },
+    /// Mint creates new tokens to the recipient, only the minter can execute it
+    Mint { recipient: String, amount: Uint128 },
+    /// UpdateMinter sets the new minter, none removes the minter forever
+    UpdateMinter { new_minter: Option<String> },
+    /// UpdateMarketing sets the marketing info, none leaves a field unchanged and an empty string
+    /// clears it, only the marketing address can execute it
+    UpdateMarketing {
+        project: Option<String>,
+        description: Option<String>,
+        marketing: Option<String>,
+    },
+    /// UploadLogo sets the url, svg or png logo of the token
+    UploadLogo(Logo),
+    /// Permit increases an allowance with the signature of the owner, anyone can submit it
+    Permit(Permit),
+}
+
+/// The cw20 queries of halo-token along the permit info
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(BalanceResponse)]
+    Balance { address: String },
+    #[returns(TokenInfoResponse)]
+    TokenInfo {},
+    #[returns(MinterResponse)]
+    Minter {},
+    #[returns(AllowanceResponse)]
+    Allowance { owner: String, spender: String },
+    #[returns(AllAllowancesResponse)]
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(AllSpenderAllowancesResponse)]
+    AllSpenderAllowances {
+        spender: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(AllAccountsResponse)]
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(MarketingInfoResponse)]
+    MarketingInfo {},
+    #[returns(DownloadLogoResponse)]
+    DownloadLogo {},
+    /// PermitInfo returns the nonce of the next permit of the owner
+    #[returns(PermitInfoResponse)]
+    PermitInfo { owner: String },
+}
+
+/// An allowance signed off-chain by its owner
+#[cw_serde]
+pub struct Permit {
+    pub params: PermitParams,
+    /// The compressed secp256k1 public key of the owner, the owner address must derive from it
+    pub pubkey: Binary,
+    /// The secp256k1 signature of the sha256 hash of the json of the params, 64 bytes
+    pub signature: Binary,
+}
+
+#[cw_serde]
+pub struct PermitParams {
+    /// The chain and the token contract the permit is valid on
+    pub chain_id: String,
+    pub token: String,
+    pub owner: String,
+    pub spender: String,
+    /// The allowance of the spender is increased by the amount
+    pub amount: Uint128,
+    /// The permits of an owner are used once each, in the order of their nonces from 0
+    pub nonce: u64,
+    /// The time in seconds from which the permit cannot be used
+    pub expires: u64,
+}
+
+#[cw_serde]
+pub struct PermitInfoResponse {
+    pub nonce: u64,
 }
 
 impl InstantiateMsg {
@@ -37,6 +184,41 @@ impl InstantiateMsg {
     }
 }
 
+/// Verify the signature of the params of a permit by their owner. The signature is the one of the
+/// sha256 hash of the json of the params, and the owner address is the bech32 of the ripemd160 of
+/// the sha256 of the compressed public key, as the cosmos accounts derive it
+pub fn verify_permit_signature<T: Serialize>(
+    api: &dyn Api,
+    params: &T,
+    owner: &str,
+    pubkey: &Binary,
+    signature: &Binary,
+) -> StdResult<()> {
+    if pubkey.len() != 33 {
+        return Err(StdError::generic_err("Invalid secp256k1 public key"));
+    }
+
+    let (_, data, _) = bech32::decode(owner)
+        .map_err(|_| StdError::generic_err("Permit owner is not a bech32 address"))?;
+    let owner_raw = Vec::<u8>::from_base32(&data)
+        .map_err(|_| StdError::generic_err("Permit owner is not a bech32 address"))?;
+    if owner_raw != Ripemd160::digest(Sha256::digest(pubkey.as_slice())).as_slice() {
+        return Err(StdError::generic_err(
+            "Permit public key does not match the owner",
+        ));
+    }
+
+    let hash = Sha256::digest(to_json_vec(params)?);
+    if !api
+        .secp256k1_verify(&hash, signature, pubkey)
+        .map_err(StdError::from)?
+    {
+        return Err(StdError::generic_err("Invalid permit signature"));
+    }
+
+    Ok(())
+}
+
 fn is_valid_name(name: &str) -> bool {
     let bytes = name.as_bytes();
     if bytes.len() < 3 || bytes.len() > 50 {
@@ -73,6 +255,7 @@ mod test {
             }),
             name: "test_token".to_string(),
             symbol: "TNT".to_string(),
+            marketing: None,
         };
 
         assert_eq!(msg.get_cap(), Some(Uint128::from(1u128)))
@@ -89,6 +272,7 @@ mod test {
             }),
             name: "test_token".to_string(),
             symbol: "TNT".to_string(),
+            marketing: None,
         };
 
         assert_eq!(valid_msg.validate(), Ok(()));
@@ -102,6 +286,7 @@ mod test {
             }),
             name: "a".to_string(),
             symbol: "TNT".to_string(),
+            marketing: None,
         };
 
         assert_eq!(
@@ -120,6 +305,7 @@ mod test {
             }),
             name: "test_token".to_string(),
             symbol: "TN".to_string(),
+            marketing: None,
         };
 
         assert_eq!(
@@ -138,6 +324,7 @@ mod test {
             }),
             name: "test_token".to_string(),
             symbol: "TNT".to_string(),
+            marketing: None,
         };
 
         assert_eq!(
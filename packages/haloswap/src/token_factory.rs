@@ -0,0 +1,75 @@
+use cosmwasm_std::{Binary, Coin, CosmosMsg};
+
+use crate::asset::TOKEN_FACTORY_DENOM_PREFIX;
+
+const MSG_CREATE_DENOM_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgCreateDenom";
+const MSG_MINT_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgMint";
+const MSG_BURN_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgBurn";
+
+/// The denom created by the creator with the subdenom, `factory/{creator}/{subdenom}`
+pub fn token_factory_denom(creator: &str, subdenom: &str) -> String {
+    format!("{}/{}/{}", TOKEN_FACTORY_DENOM_PREFIX, creator, subdenom)
+}
+
+/// MsgCreateDenom creates the denom `factory/{sender}/{subdenom}` administrated by the sender
+pub fn create_denom_msg(sender: &str, subdenom: &str) -> CosmosMsg {
+    let mut value = vec![];
+    encode_bytes(&mut value, 1, sender.as_bytes());
+    encode_bytes(&mut value, 2, subdenom.as_bytes());
+
+    stargate_msg(MSG_CREATE_DENOM_TYPE_URL, value)
+}
+
+/// MsgMint mints the amount of a denom administrated by the sender to the address
+pub fn mint_msg(sender: &str, amount: &Coin, mint_to_address: &str) -> CosmosMsg {
+    let mut value = vec![];
+    encode_bytes(&mut value, 1, sender.as_bytes());
+    encode_bytes(&mut value, 2, &encode_coin(amount));
+    encode_bytes(&mut value, 3, mint_to_address.as_bytes());
+
+    stargate_msg(MSG_MINT_TYPE_URL, value)
+}
+
+/// MsgBurn burns the amount of a denom administrated by the sender from the address
+pub fn burn_msg(sender: &str, amount: &Coin, burn_from_address: &str) -> CosmosMsg {
+    let mut value = vec![];
+    encode_bytes(&mut value, 1, sender.as_bytes());
+    encode_bytes(&mut value, 2, &encode_coin(amount));
+    encode_bytes(&mut value, 3, burn_from_address.as_bytes());
+
+    stargate_msg(MSG_BURN_TYPE_URL, value)
+}
+
+fn stargate_msg(type_url: &str, value: Vec<u8>) -> CosmosMsg {
+    CosmosMsg::Stargate {
+        type_url: type_url.to_string(),
+        value: Binary::from(value),
+    }
+}
+
+/// The protobuf encoding of a cosmos.base.v1beta1.Coin
+fn encode_coin(coin: &Coin) -> Vec<u8> {
+    let mut buf = vec![];
+    encode_bytes(&mut buf, 1, coin.denom.as_bytes());
+    encode_bytes(&mut buf, 2, coin.amount.to_string().as_bytes());
+    buf
+}
+
+/// Append a length-delimited field, the empty fields are omitted as in proto3
+fn encode_bytes(buf: &mut Vec<u8>, field_number: u64, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+
+    encode_varint(buf, field_number << 3 | 2);
+    encode_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
@@ -0,0 +1,84 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub halo_token: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig {
+        owner: Option<String>,
+    },
+    /// Claim sends the vested amount not claimed yet to the sender
+    Claim {},
+    /// Revoke stops a revocable vesting, the vested amount stays claimable
+    /// and the rest is returned to the owner
+    Revoke {
+        recipient: String,
+    },
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// CreateVesting vests the sent HALO to the recipient, only the owner can send it
+    CreateVesting {
+        recipient: String,
+        schedule: VestingSchedule,
+        revocable: bool,
+    },
+}
+
+/// Nothing vests before `cliff_time`, then the amount vests linearly from `start_time` to
+/// `end_time`, a cliff equal to the start makes a linear schedule and a cliff equal to the end
+/// releases the whole amount at once
+#[cw_serde]
+pub struct VestingSchedule {
+    pub start_time: u64,
+    pub cliff_time: u64,
+    pub end_time: u64,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Vesting returns the vesting with the amounts vested at the current block time
+    #[returns(VestingResponse)]
+    Vesting { recipient: String },
+    #[returns(VestingsResponse)]
+    Vestings {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub halo_token: String,
+}
+
+#[cw_serde]
+pub struct VestingResponse {
+    pub recipient: String,
+    pub schedule: VestingSchedule,
+    pub total_amount: Uint128,
+    pub vested_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub claimable_amount: Uint128,
+    pub revocable: bool,
+    pub revoked: bool,
+}
+
+#[cw_serde]
+pub struct VestingsResponse {
+    pub vestings: Vec<VestingResponse>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}